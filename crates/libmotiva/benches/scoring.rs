@@ -1,8 +1,8 @@
 use std::hint::black_box;
 
-use criterion::{Criterion, criterion_group, criterion_main};
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 
-use libmotiva::{MockedElasticsearch, prelude::*};
+use libmotiva::{MockedElasticsearch, fixtures, prelude::*};
 use tokio::runtime::Runtime;
 
 fn name_based(c: &mut Criterion) {
@@ -10,10 +10,7 @@ fn name_based(c: &mut Criterion) {
   let motiva = rt.block_on(async { Motiva::new(MockedElasticsearch::default()).build().await.unwrap() });
 
   let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
-  let rhs = std::iter::repeat(vec![Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build()])
-    .take(10)
-    .flatten()
-    .collect::<Vec<_>>();
+  let rhs = std::iter::repeat_n(Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build(), 10).collect::<Vec<_>>();
 
   c.bench_function("name_based", |b| b.iter(|| black_box(motiva.score::<NameBased>(&lhs, rhs.clone(), &Default::default()))));
 }
@@ -23,10 +20,7 @@ fn name_qualified(c: &mut Criterion) {
   let motiva = rt.block_on(async { Motiva::new(MockedElasticsearch::default()).build().await.unwrap() });
 
   let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
-  let rhs = std::iter::repeat(vec![Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build()])
-    .take(10)
-    .flatten()
-    .collect::<Vec<_>>();
+  let rhs = std::iter::repeat_n(Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build(), 10).collect::<Vec<_>>();
 
   c.bench_function("name_qualified", |b| b.iter(|| black_box(motiva.score::<NameQualified>(&lhs, rhs.clone(), &Default::default()))));
 }
@@ -36,13 +30,52 @@ fn logic_v1(c: &mut Criterion) {
   let motiva = rt.block_on(async { Motiva::new(MockedElasticsearch::default()).build().await.unwrap() });
 
   let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
-  let rhs = std::iter::repeat(vec![Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build()])
-    .take(10)
-    .flatten()
-    .collect::<Vec<_>>();
+  let rhs = std::iter::repeat_n(Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build(), 10).collect::<Vec<_>>();
 
   c.bench_function("logic_v1", |b| b.iter(|| black_box(motiva.score::<LogicV1>(&lhs, rhs.clone(), &Default::default()))));
 }
 
-criterion_group!(benches, name_based, name_qualified, logic_v1);
+fn logic_v1_many_candidates(c: &mut Criterion) {
+  let rt = Runtime::new().unwrap();
+  let motiva = rt.block_on(async { Motiva::new(MockedElasticsearch::default()).build().await.unwrap() });
+
+  let lhs = SearchEntity::builder("Person")
+    .properties(&[("name", &["Vladimir Vladimirovich Putin", "Владимир Путин"]), ("birthDate", &["1952-10-07"]), ("nationality", &["ru"])])
+    .build();
+
+  let names = ["Vladimir Putin", "Vladimir Poutine", "Wladimir Putin", "Vladimir Pudin", "Volodymyr Zelensky"];
+  let rhs = (0..100)
+    .map(|idx| Entity::builder("Person").properties(&[("name", &[names[idx % names.len()]]), ("birthDate", &["1952-10-07"])]).build())
+    .collect::<Vec<_>>();
+
+  c.bench_function("logic_v1_many_candidates", |b| b.iter(|| black_box(motiva.score::<LogicV1>(&lhs, rhs.clone(), &Default::default()))));
+}
+
+fn logic_v1_throughput(c: &mut Criterion) {
+  let rt = Runtime::new().unwrap();
+  let motiva = rt.block_on(async { Motiva::new(MockedElasticsearch::default()).build().await.unwrap() });
+
+  let mut group = c.benchmark_group("logic_v1_throughput");
+
+  for (name, lhs, rhs) in fixtures::throughput_cases() {
+    group.throughput(Throughput::Elements(rhs.len() as u64));
+    group.bench_function(name, |b| b.iter(|| black_box(motiva.score::<LogicV1>(&lhs, rhs.clone(), &Default::default()))));
+  }
+
+  group.finish();
+}
+
+fn precompute(c: &mut Criterion) {
+  c.bench_function("precompute", |b| {
+    b.iter(|| {
+      black_box(
+        SearchEntity::builder("Person")
+          .properties(&[("name", &["Vladimir Vladimirovich Putin", "Владимир Путин"]), ("birthDate", &["1952-10-07"])])
+          .build(),
+      )
+    })
+  });
+}
+
+criterion_group!(benches, name_based, name_qualified, logic_v1, logic_v1_many_candidates, logic_v1_throughput, precompute);
 criterion_main!(benches);
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
+use any_ascii::any_ascii;
 use itertools::Itertools;
 use jiff::{
   Span, Timestamp,
@@ -7,7 +8,7 @@ use jiff::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{IndexProvider, fetcher::CatalogFetcher};
+use crate::{IndexProvider, error::MotivaError, fetcher::CatalogFetcher};
 
 pub(crate) const OPENSANCTIONS_CATALOG_URL: &str = "https://data.opensanctions.org/datasets/latest/index.json";
 
@@ -35,6 +36,31 @@ impl Default for Manifest {
   }
 }
 
+impl Manifest {
+  /// Check the manifest for configuration mistakes that would otherwise only
+  /// surface as opaque errors when fetching catalogs.
+  pub fn validate(&self) -> Result<(), MotivaError> {
+    let mut errors = Vec::new();
+
+    for (idx, catalog) in self.catalogs.iter().enumerate() {
+      if catalog.url.trim().is_empty() {
+        errors.push(format!("catalog #{idx} has an empty URL"));
+      }
+    }
+
+    for dataset in &self.datasets {
+      if dataset.datasets.as_ref().is_some_and(|children| children.contains(&dataset.name)) {
+        errors.push(format!("dataset {} lists itself as a child", dataset.name));
+      }
+    }
+
+    match errors.is_empty() {
+      true => Ok(()),
+      false => Err(MotivaError::ConfigError(format!("invalid manifest: {}", errors.join(", ")))),
+    }
+  }
+}
+
 #[cfg(test)]
 impl Manifest {
   fn test() -> Self {
@@ -76,6 +102,7 @@ pub struct ManifestDataset {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Catalog {
   pub datasets: Vec<CatalogDataset>,
   #[serde(default)]
@@ -84,12 +111,102 @@ pub struct Catalog {
   pub current: Vec<String>,
   #[serde(default)]
   pub outdated: Vec<String>,
+  /// Number of distinct entities in each scope loaded from the manifest.
+  #[serde(default)]
+  pub scope_entity_counts: HashMap<String, u64>,
 
   #[serde(skip)]
   pub loaded_datasets: LoadedDatasets,
 }
 
 impl Catalog {
+  /// Replace a dataset with a freshly merged version of it, keeping its
+  /// resolved children, and update the index status lists accordingly.
+  pub(crate) fn replace_dataset(&mut self, mut dataset: CatalogDataset, status: Option<IndexStatus>) {
+    if let Some(previous) = self.loaded_datasets.get(&dataset.name) {
+      dataset.datasets = previous.datasets.clone();
+    }
+
+    self.current.retain(|name| name != &dataset.name);
+    self.outdated.retain(|name| name != &dataset.name);
+
+    match status {
+      Some(IndexStatus::Current) => self.current.push(dataset.name.clone()),
+      Some(IndexStatus::Outdated) => self.outdated.push(dataset.name.clone()),
+      None => {}
+    }
+
+    match self.datasets.iter_mut().find(|ds| ds.name == dataset.name) {
+      Some(existing) => *existing = dataset.clone(),
+      None => self.datasets.push(dataset.clone()),
+    }
+
+    self.index_stale = !self.outdated.is_empty();
+    self.loaded_datasets.insert(dataset.name.clone(), dataset);
+    self.count_scope_entities();
+  }
+
+  /// Add a freshly merged dataset, replacing any dataset of the same name
+  /// declared by a previous catalog.
+  ///
+  /// The last declaration wins, but a dataset stays loaded if any of the
+  /// catalogs declaring it has it in its scopes.
+  fn push_dataset(&mut self, mut dataset: CatalogDataset, status: Option<IndexStatus>) {
+    if let Some(position) = self.datasets.iter().position(|existing| existing.name == dataset.name) {
+      tracing::warn!(dataset = dataset.name, "dataset is declared more than once, keeping the last declaration");
+
+      dataset.load |= self.datasets.remove(position).load;
+
+      self.current.retain(|name| name != &dataset.name);
+      self.outdated.retain(|name| name != &dataset.name);
+    }
+
+    match status {
+      Some(IndexStatus::Current) => self.current.push(dataset.name.clone()),
+      Some(IndexStatus::Outdated) => self.outdated.push(dataset.name.clone()),
+      None => {}
+    }
+
+    self.datasets.push(dataset);
+  }
+
+  /// Canonical name of a loaded dataset or scope, looked up regardless of
+  /// case and accents (e.g. `DEFAULT` resolves to `default`).
+  pub fn resolve_dataset_name(&self, name: &str) -> Option<&str> {
+    if let Some((name, _)) = self.loaded_datasets.get_key_value(name) {
+      return Some(name);
+    }
+
+    let key = dataset_key(name);
+
+    self.loaded_datasets.keys().find(|candidate| dataset_key(candidate) == key).map(String::as_str)
+  }
+
+  /// Total number of entities in a scope, summed over its resolved children.
+  ///
+  /// Datasets reachable through several paths are only counted once, and a
+  /// scope without children counts its own entities.
+  pub fn entity_count_for_scope(&self, scope: &str) -> u64 {
+    let Some(dataset) = self.resolve_dataset_name(scope).and_then(|scope| self.loaded_datasets.get(scope)) else {
+      return 0;
+    };
+
+    if dataset.children.is_empty() {
+      return dataset.entity_count;
+    }
+
+    dataset.datasets.iter().unique().filter_map(|name| self.loaded_datasets.get(name)).map(|child| child.entity_count).sum()
+  }
+
+  pub(crate) fn count_scope_entities(&mut self) {
+    self.scope_entity_counts = self
+      .loaded_datasets
+      .values()
+      .filter(|dataset| dataset.load)
+      .map(|dataset| (dataset.name.clone(), self.entity_count_for_scope(&dataset.name)))
+      .collect();
+  }
+
   pub(crate) fn resolve_relationships(&mut self, loaded: Vec<CatalogDataset>) -> anyhow::Result<()> {
     for dataset in loaded {
       if dataset.children.is_empty() {
@@ -132,6 +249,7 @@ impl Catalog {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CatalogDataset {
   pub name: String,
   pub title: String,
@@ -163,8 +281,11 @@ pub struct CatalogDataset {
   pub _type: Option<String>,
   pub publisher: Option<CatalogDatasetPublisher>,
   pub coverage: Option<CatalogDatasetCoverage>,
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub last_change: Option<DateTime>,
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub last_export: Option<DateTime>,
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub updated_at: Option<DateTime>,
 
   #[serde(skip)]
@@ -176,10 +297,12 @@ pub struct CatalogDataset {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CatalogDatasetResource {
   name: String,
   title: Option<String>,
   url: String,
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   timestamp: Option<DateTime>,
   size: u64,
   mime_type: String,
@@ -188,6 +311,7 @@ pub struct CatalogDatasetResource {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CatalogDatasetPublisher {
   pub name: String,
   pub acronym: Option<String>,
@@ -198,25 +322,160 @@ pub struct CatalogDatasetPublisher {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CatalogDatasetCoverage {
+  #[cfg_attr(feature = "openapi", schema(value_type = String))]
   pub start: Date,
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub end: Option<Date>,
   pub countries: Vec<String>,
   pub schedule: Option<String>,
   pub frequency: String,
 }
 
+/// Changes to the catalog between two refreshes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CatalogDiff {
+  /// Datasets that were not in the previous catalog.
+  pub added: Vec<String>,
+  /// Datasets that are not in the catalog anymore.
+  pub removed: Vec<String>,
+  /// Datasets whose upstream version changed.
+  pub updated: Vec<DatasetVersionChange>,
+  /// Datasets that became outdated, compared to the index.
+  pub outdated: Vec<String>,
+  /// Datasets that became current, compared to the index.
+  pub current: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DatasetVersionChange {
+  pub name: String,
+  pub previous: String,
+  pub current: String,
+}
+
+impl CatalogDiff {
+  /// Compute the changes from `previous` to `current`.
+  pub fn between(previous: &Catalog, current: &Catalog) -> CatalogDiff {
+    let before = previous.datasets.iter().map(|dataset| (dataset.name.as_str(), dataset)).collect::<HashMap<_, _>>();
+    let after = current.datasets.iter().map(|dataset| (dataset.name.as_str(), dataset)).collect::<HashMap<_, _>>();
+
+    let newly = |now: &[String], then: &[String]| now.iter().filter(|name| !then.contains(name)).cloned().sorted().collect::<Vec<_>>();
+
+    CatalogDiff {
+      added: after.keys().filter(|name| !before.contains_key(*name)).map(|name| name.to_string()).sorted().collect(),
+      removed: before.keys().filter(|name| !after.contains_key(*name)).map(|name| name.to_string()).sorted().collect(),
+      updated: after
+        .iter()
+        .filter_map(|(name, dataset)| before.get(name).filter(|previous| previous.version != dataset.version).map(|previous| (name, previous, dataset)))
+        .map(|(name, previous, dataset)| DatasetVersionChange {
+          name: name.to_string(),
+          previous: previous.version.clone(),
+          current: dataset.version.clone(),
+        })
+        .sorted_by(|lhs, rhs| lhs.name.cmp(&rhs.name))
+        .collect(),
+      outdated: newly(&current.outdated, &previous.outdated),
+      current: newly(&current.current, &previous.current),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty() && self.outdated.is_empty() && self.current.is_empty()
+  }
+}
+
+/// Index status of a dataset, compared to its upstream version.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum IndexStatus {
+  Current,
+  Outdated,
+}
+
+/// Annotate a dataset from an upstream catalog with its manifest scope and
+/// index version, and return its index status if it is indexed.
+fn merge_catalog_dataset(ds: &mut CatalogDataset, scopes: &[String], indices: &[(String, String)], outdated_grace: Span) -> Option<IndexStatus> {
+  if scopes.contains(&ds.name) {
+    ds.load = true;
+  }
+
+  for resource in &ds.resources {
+    if resource.name == "entities.ftm.json" {
+      ds.entities_url = Some(resource.url.clone());
+    }
+  }
+
+  let (_, version) = indices.iter().find(|(name, _)| name == &ds.name)?;
+
+  ds.index_version = Some(version.clone());
+
+  if version.as_str() == ds.version {
+    ds.index_current = true;
+
+    return Some(IndexStatus::Current);
+  }
+
+  let indexed_ts_str = version.split("-").next()?;
+  let indexed_timestamp = DateTime::strptime("%Y%m%d%H%M%S", indexed_ts_str).ok()?;
+
+  match ds.last_export {
+    Some(last_export) if last_export <= indexed_timestamp + outdated_grace => Some(IndexStatus::Current),
+    _ => Some(IndexStatus::Outdated),
+  }
+}
+
+/// Build a catalog entry for a dataset declared directly in the manifest.
+fn manifest_dataset(ds: ManifestDataset, indices: &[(String, String)]) -> CatalogDataset {
+  let mut dataset = CatalogDataset {
+    _type: if ds.datasets.as_ref().map(|d| !d.is_empty()).unwrap_or_default() {
+      Some("collection".into())
+    } else {
+      None
+    },
+    name: ds.name.clone(),
+    title: ds.title,
+    load: true,
+    version: ds.version.unwrap_or_else(|| format!("{}-mot", Timestamp::now().strftime("%Y%m%d%H%M%S"))),
+    index_version: None,
+    index_current: false,
+    children: ds.datasets.unwrap_or_default(),
+    ..Default::default()
+  };
+
+  if let Some((_, version)) = indices.iter().find(|(name, _)| name == &ds.name) {
+    dataset.index_version = Some(version.clone());
+    dataset.index_current = Some(&dataset.version) == dataset.index_version.as_ref();
+  }
+
+  dataset
+}
+
+/// Form of a dataset name used to compare names regardless of case and accents.
+fn dataset_key(name: &str) -> String {
+  any_ascii(name.trim()).to_lowercase()
+}
+
+/// Datasets to load from a catalog, as declared in the manifest.
+fn catalog_scopes(spec: &ManifestCatalog) -> Vec<String> {
+  spec.scopes.iter().cloned().chain(spec.scope.clone()).collect()
+}
+
 pub async fn get_merged_catalog<P: IndexProvider, F: CatalogFetcher>(fetcher: &F, index: &P, outdated_grace: Span) -> anyhow::Result<Catalog> {
-  let manifest = fetcher.fetch_manifest().await?;
+  merge_manifest_catalogs(fetcher, index, outdated_grace, fetcher.fetch_manifest().await?).await
+}
+
+/// Build the merged catalog from an already fetched manifest.
+pub(crate) async fn merge_manifest_catalogs<P: IndexProvider, F: CatalogFetcher>(fetcher: &F, index: &P, outdated_grace: Span, manifest: Manifest) -> anyhow::Result<Catalog> {
   let indices = index.list_indices().await?;
   let mut catalog = Catalog::default();
 
-  for mut spec in manifest.catalogs {
+  for spec in manifest.catalogs {
     match fetcher.fetch_catalog(&spec.url, spec.auth_token.as_deref()).await {
-      Ok(mut upstream) => {
-        if let Some(scope) = spec.scope {
-          spec.scopes.push(scope);
-        }
+      Ok(upstream) => {
+        let scopes = catalog_scopes(&spec);
 
         tracing::trace!(
           url = spec.url,
@@ -225,48 +484,11 @@ pub async fn get_merged_catalog<P: IndexProvider, F: CatalogFetcher>(fetcher: &F
           upstream.datasets.len()
         );
 
-        for ds in &mut upstream.datasets {
-          if spec.scopes.contains(&ds.name) {
-            ds.load = true;
-          }
-
-          for resource in &ds.resources {
-            if resource.name == "entities.ftm.json" {
-              ds.entities_url = Some(resource.url.clone());
-            }
-          }
-
-          if let Some((_, version)) = indices.iter().find(|(name, _)| name == &ds.name) {
-            ds.index_version = Some(version.clone());
-
-            match version.as_str() == ds.version {
-              true => {
-                ds.index_current = true;
-                catalog.current.push(ds.name.clone());
-              }
-
-              false => {
-                let Some(indexed_ts_str) = version.split("-").next() else {
-                  continue;
-                };
-
-                let Ok(indexed_timestamp) = DateTime::strptime("%Y%m%d%H%M%S", indexed_ts_str) else { continue };
-
-                match ds.last_export {
-                  Some(last_export) if last_export <= indexed_timestamp + outdated_grace => {
-                    catalog.current.push(ds.name.clone());
-                  }
-
-                  _ => {
-                    catalog.outdated.push(ds.name.clone());
-                  }
-                }
-              }
-            }
-          }
-        }
+        for mut ds in upstream.datasets {
+          let status = merge_catalog_dataset(&mut ds, &scopes, &indices, outdated_grace);
 
-        catalog.datasets.extend(upstream.datasets);
+          catalog.push_dataset(ds, status);
+        }
       }
 
       Err(err) => {
@@ -285,39 +507,53 @@ pub async fn get_merged_catalog<P: IndexProvider, F: CatalogFetcher>(fetcher: &F
   }
 
   for ds in manifest.datasets {
-    let mut dataset = CatalogDataset {
-      _type: if ds.datasets.as_ref().map(|d| !d.is_empty()).unwrap_or_default() {
-        Some("collection".into())
-      } else {
-        None
-      },
-      name: ds.name.clone(),
-      title: ds.title,
-      load: true,
-      version: ds.version.unwrap_or_else(|| format!("{}-mot", Timestamp::now().strftime("%Y%m%d%H%M%S"))),
-      index_version: None,
-      index_current: false,
-      children: ds.datasets.unwrap_or_default(),
-      ..Default::default()
-    };
-
-    if let Some((_, version)) = indices.iter().find(|(name, _)| name == &ds.name) {
-      dataset.index_version = Some(version.clone());
-      dataset.index_current = Some(&dataset.version) == dataset.index_version.as_ref();
-    }
-
-    catalog.datasets.push(dataset);
+    catalog.push_dataset(manifest_dataset(ds, &indices), None);
   }
 
   catalog.index_stale = !catalog.outdated.is_empty();
   catalog.loaded_datasets = catalog.datasets.iter().map(|dataset| (dataset.name.clone(), dataset.clone())).collect::<HashMap<_, _>>();
   catalog.resolve_relationships(catalog.datasets.clone())?;
+  catalog.count_scope_entities();
 
   tracing::info!(datasets = catalog.datasets.len(), "fetched catalog");
 
   Ok(catalog)
 }
 
+/// Fetch and merge a single dataset, as [`get_merged_catalog`] would.
+///
+/// Since the last declaration of a dataset wins, the manifest is looked up
+/// first, then upstream catalogs are fetched in reverse manifest order, until
+/// one contains the dataset. Returns `None` if no catalog nor the manifest
+/// declare it.
+pub(crate) async fn get_merged_dataset<P: IndexProvider, F: CatalogFetcher>(fetcher: &F, index: &P, outdated_grace: Span, name: &str) -> anyhow::Result<Option<(CatalogDataset, Option<IndexStatus>)>> {
+  let manifest = fetcher.fetch_manifest().await?;
+  let indices = index.list_indices().await?;
+
+  if let Some(ds) = manifest.datasets.into_iter().find(|ds| ds.name == name) {
+    return Ok(Some((manifest_dataset(ds, &indices), None)));
+  }
+
+  for spec in manifest.catalogs.into_iter().rev() {
+    let upstream = match fetcher.fetch_catalog(&spec.url, spec.auth_token.as_deref()).await {
+      Ok(upstream) => upstream,
+
+      Err(err) => {
+        tracing::warn!("error" = ?err, "encountered issue parsing dataset at {}", spec.url);
+        continue;
+      }
+    };
+
+    if let Some(mut dataset) = upstream.datasets.into_iter().find(|ds| ds.name == name) {
+      let status = merge_catalog_dataset(&mut dataset, &catalog_scopes(&spec), &indices, outdated_grace);
+
+      return Ok(Some((dataset, status)));
+    }
+  }
+
+  Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
   use std::collections::HashMap;
@@ -391,6 +627,110 @@ mod tests {
     assert!(!datasets_by_name["dataset3"].index_current);
   }
 
+  #[tokio::test]
+  async fn merge_catalog_multiple_scopes() {
+    let dataset = |name: &str, children: &[&str], entity_count: u64| CatalogDataset {
+      name: name.to_string(),
+      children: children.iter().map(|c| c.to_string()).collect(),
+      entity_count,
+      ..Default::default()
+    };
+
+    let catalog = Catalog {
+      datasets: vec![
+        dataset("default", &["sanctions", "peps"], 0),
+        dataset("sanctions", &["us_ofac"], 0),
+        dataset("peps", &["wd_peps"], 0),
+        dataset("crime", &["interpol"], 0),
+        dataset("us_ofac", &[], 10),
+        dataset("wd_peps", &[], 5),
+        dataset("interpol", &[], 2),
+      ],
+      ..Default::default()
+    };
+
+    let mut catalogs = HashMap::default();
+    catalogs.insert(OPENSANCTIONS_CATALOG_URL.to_string(), catalog);
+
+    let manifest: Manifest = serde_json::from_str(&format!(r#"{{"catalogs":[{{"url":"{OPENSANCTIONS_CATALOG_URL}","scopes":["sanctions","peps"],"scope":"crime"}}]}}"#)).unwrap();
+    let fetcher = TestFetcher { manifest, catalogs };
+
+    let catalog = super::get_merged_catalog(&fetcher, &MockedElasticsearch::default(), Span::default()).await.unwrap();
+    let datasets_by_name = catalog.datasets.iter().map(|ds| (ds.name.as_str(), ds)).collect::<HashMap<_, _>>();
+
+    assert!(datasets_by_name["sanctions"].load);
+    assert!(datasets_by_name["peps"].load);
+    assert!(datasets_by_name["crime"].load);
+
+    assert!(!datasets_by_name["default"].load);
+    assert!(!datasets_by_name["us_ofac"].load);
+
+    assert_eq!(
+      catalog.scope_entity_counts,
+      HashMap::from([("sanctions".to_string(), 10), ("peps".to_string(), 5), ("crime".to_string(), 2)])
+    );
+  }
+
+  #[tokio::test]
+  async fn merge_catalog_duplicate_datasets() {
+    let dataset = |name: &str, version: &str| CatalogDataset {
+      name: name.to_string(),
+      version: version.to_string(),
+      ..Default::default()
+    };
+
+    let catalogs = HashMap::from([
+      (
+        "http://first".to_string(),
+        Catalog {
+          datasets: vec![dataset("shared", "1"), dataset("first_only", "1")],
+          ..Default::default()
+        },
+      ),
+      (
+        "http://second".to_string(),
+        Catalog {
+          datasets: vec![dataset("shared", "2"), dataset("second_only", "1")],
+          ..Default::default()
+        },
+      ),
+    ]);
+
+    let manifest = Manifest {
+      catalogs: vec![
+        ManifestCatalog {
+          url: "http://first".to_string(),
+          scope: Some("shared".to_string()),
+          ..Default::default()
+        },
+        ManifestCatalog {
+          url: "http://second".to_string(),
+          ..Default::default()
+        },
+      ],
+      datasets: vec![],
+    };
+
+    let fetcher = TestFetcher { manifest, catalogs };
+    let indices = vec![("shared".to_string(), "1".to_string())];
+    let index = MockedElasticsearch::builder().indices(indices).build();
+
+    let catalog = super::get_merged_catalog(&fetcher, &index, Span::default()).await.unwrap();
+    let shared = catalog.datasets.iter().filter(|ds| ds.name == "shared").collect::<Vec<_>>();
+
+    assert_eq!(catalog.datasets.len(), 3);
+    assert_eq!(shared.len(), 1);
+    assert_eq!(shared[0].version, "2");
+    assert!(shared[0].load);
+    // The first declaration was current with the index, but the last one is not
+    assert!(catalog.current.is_empty());
+    assert!(catalog.outdated.is_empty());
+
+    let (dataset, _) = super::get_merged_dataset(&fetcher, &index, Span::default(), "shared").await.unwrap().unwrap();
+
+    assert_eq!(dataset.version, "2");
+  }
+
   #[tokio::test]
   async fn merge_catalog_grace_and_resources() {
     let catalog = Catalog {
@@ -520,6 +860,61 @@ mod tests {
     assert!(datasets_by_name["simple"].index_current);
   }
 
+  #[test]
+  fn validate_manifest() {
+    assert!(Manifest::default().validate().is_ok());
+    assert!(Manifest::test().validate().is_ok());
+
+    let manifest = Manifest {
+      catalogs: vec![ManifestCatalog {
+        url: " ".to_string(),
+        resource_name: Some("entities.ftm.json".to_string()),
+        ..Default::default()
+      }],
+      datasets: vec![],
+    };
+
+    assert_eq!(manifest.validate().unwrap_err().to_string(), "invalid configuration: invalid manifest: catalog #0 has an empty URL");
+
+    let manifest = Manifest {
+      catalogs: vec![ManifestCatalog {
+        url: "http://catalog".to_string(),
+        ..Default::default()
+      }],
+      datasets: vec![],
+    };
+
+    assert!(manifest.validate().is_ok());
+
+    let manifest = Manifest {
+      catalogs: vec![],
+      datasets: vec![ManifestDataset {
+        name: "loop".to_string(),
+        datasets: Some(vec!["other".to_string(), "loop".to_string()]),
+        ..Default::default()
+      }],
+    };
+
+    assert_eq!(
+      manifest.validate().unwrap_err().to_string(),
+      "invalid configuration: invalid manifest: dataset loop lists itself as a child"
+    );
+
+    let manifest = Manifest {
+      catalogs: vec![ManifestCatalog::default()],
+      datasets: vec![ManifestDataset {
+        name: "loop".to_string(),
+        datasets: Some(vec!["loop".to_string()]),
+        ..Default::default()
+      }],
+    };
+
+    assert_eq!(
+      manifest.validate().unwrap_err().to_string(),
+      "invalid configuration: invalid manifest: catalog #0 has an empty URL, dataset loop lists itself as a child"
+    );
+  }
+
   #[test]
   fn resolve_dataset_relationships() {
     fn dataset(name: &str, children: &[&str]) -> CatalogDataset {
@@ -567,4 +962,69 @@ mod tests {
     assert_eq!(catalog.loaded_datasets["a"].datasets, vec!["c".to_string()]);
     assert_eq!(catalog.loaded_datasets["b"].datasets, vec!["c".to_string()]);
   }
+
+  #[test]
+  fn entity_count_for_scope() {
+    fn dataset(name: &str, children: &[&str], entity_count: u64) -> CatalogDataset {
+      CatalogDataset {
+        name: name.to_string(),
+        children: children.iter().map(|c| c.to_string()).collect(),
+        entity_count,
+        load: name == "default",
+        ..Default::default()
+      }
+    }
+
+    let datasets = vec![
+      dataset("default", &["sanctions", "peps", "extra"], 0),
+      dataset("sanctions", &["us_ofac", "eu_fsf"], 0),
+      dataset("peps", &["eu_fsf", "wd_peps"], 0),
+      dataset("us_ofac", &[], 100),
+      dataset("eu_fsf", &[], 20),
+      dataset("wd_peps", &[], 3),
+      dataset("extra", &[], 4),
+    ];
+
+    let mut catalog = Catalog {
+      loaded_datasets: datasets.iter().map(|d| (d.name.clone(), d.clone())).collect(),
+      ..Default::default()
+    };
+
+    catalog.resolve_relationships(datasets).unwrap();
+    catalog.count_scope_entities();
+
+    assert_eq!(catalog.entity_count_for_scope("default"), 127);
+    assert_eq!(catalog.entity_count_for_scope("sanctions"), 120);
+    assert_eq!(catalog.entity_count_for_scope("peps"), 23);
+    assert_eq!(catalog.entity_count_for_scope("extra"), 4);
+    assert_eq!(catalog.entity_count_for_scope("unknown"), 0);
+    assert_eq!(catalog.entity_count_for_scope("DEFAULT"), 127);
+    assert_eq!(catalog.scope_entity_counts, HashMap::from([("default".to_string(), 127)]));
+  }
+
+  #[test]
+  fn resolve_dataset_name() {
+    let catalog = Catalog {
+      loaded_datasets: ["default", "us_ofac_sdn", "sécurité"]
+        .into_iter()
+        .map(|name| {
+          (
+            name.to_string(),
+            CatalogDataset {
+              name: name.to_string(),
+              ..Default::default()
+            },
+          )
+        })
+        .collect(),
+      ..Default::default()
+    };
+
+    assert_eq!(catalog.resolve_dataset_name("default"), Some("default"));
+    assert_eq!(catalog.resolve_dataset_name("DEFAULT"), Some("default"));
+    assert_eq!(catalog.resolve_dataset_name("Défault"), Some("default"));
+    assert_eq!(catalog.resolve_dataset_name("US_OFAC_SDN"), Some("us_ofac_sdn"));
+    assert_eq!(catalog.resolve_dataset_name("SECURITE"), Some("sécurité"));
+    assert_eq!(catalog.resolve_dataset_name("ofac"), None);
+  }
 }
@@ -10,6 +10,8 @@ pub enum MotivaError {
   ResourceNotFound,
   #[error("invalid schema: {0}")]
   InvalidSchema(String),
+  #[error("invalid parameter: {0}")]
+  InvalidParameter(String),
   #[error(transparent)]
   IndexError(#[from] elasticsearch::Error),
   #[error(transparent)]
@@ -1,7 +1,12 @@
-use std::{collections::HashMap, fs::File};
+use std::{
+  collections::HashMap,
+  fs::File,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
 
 use anyhow::Context;
-use reqwest::header;
+use reqwest::{StatusCode, header};
 
 use crate::{
   Catalog,
@@ -27,11 +32,81 @@ pub enum ManifestFormat {
   Yaml,
 }
 
-#[derive(Clone, Default)]
+/// Error returned when a remote manifest or catalog could not be retrieved.
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+  #[error("could not reach {0}")]
+  Network(String, #[source] reqwest::Error),
+  #[error("{0} responded with status {1}")]
+  Status(String, StatusCode),
+  #[error("could not parse response from {0}")]
+  Parse(String, #[source] anyhow::Error),
+}
+
+impl FetchError {
+  /// Whether the request could succeed if attempted again.
+  fn is_transient(&self) -> bool {
+    match self {
+      FetchError::Network(..) => true,
+      FetchError::Status(_, status) => status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS,
+      FetchError::Parse(..) => false,
+    }
+  }
+}
+
+/// Validators returned alongside a resource, used to make conditional requests.
+#[derive(Clone, Debug, Default)]
+struct CacheValidators {
+  etag: Option<header::HeaderValue>,
+  last_modified: Option<header::HeaderValue>,
+}
+
+impl CacheValidators {
+  fn from_headers(headers: &header::HeaderMap) -> Self {
+    Self {
+      etag: headers.get(header::ETAG).cloned(),
+      last_modified: headers.get(header::LAST_MODIFIED).cloned(),
+    }
+  }
+
+  fn is_empty(&self) -> bool {
+    self.etag.is_none() && self.last_modified.is_none()
+  }
+}
+
+/// Last version of a catalog, kept to be reused when upstream reports it did not change.
+#[derive(Clone, Debug)]
+struct CachedCatalog {
+  validators: CacheValidators,
+  catalog: Catalog,
+}
+
+#[derive(Clone)]
 pub struct HttpCatalogFetcher {
   pub manifest_url: Option<String>,
   pub protocol: ManifestProtocol,
   pub format: ManifestFormat,
+  /// Number of additional attempts made after a transient failure
+  pub retries: usize,
+  /// Delay before the first retry, doubled after each attempt
+  pub retry_backoff: Duration,
+  /// Headers sent with every manifest and catalog request
+  pub headers: header::HeaderMap,
+  cache: Arc<Mutex<HashMap<String, CachedCatalog>>>,
+}
+
+impl Default for HttpCatalogFetcher {
+  fn default() -> Self {
+    Self {
+      manifest_url: None,
+      protocol: ManifestProtocol::default(),
+      format: ManifestFormat::default(),
+      retries: 3,
+      retry_backoff: Duration::from_secs(1),
+      headers: header::HeaderMap::default(),
+      cache: Arc::default(),
+    }
+  }
 }
 
 impl HttpCatalogFetcher {
@@ -58,7 +133,31 @@ impl HttpCatalogFetcher {
       None => (ManifestProtocol::Http, ManifestFormat::Json),
     };
 
-    Ok(Self { protocol, format, manifest_url: url })
+    Ok(Self {
+      protocol,
+      format,
+      manifest_url: url,
+      ..Default::default()
+    })
+  }
+
+  /// Set the headers sent with every manifest and catalog request.
+  ///
+  /// An `Authorization` header set here is overridden by the token configured
+  /// on a specific catalog, if any.
+  pub fn with_headers(mut self, user_agent: Option<&str>, headers: &HashMap<String, String>) -> anyhow::Result<Self> {
+    for (name, value) in headers {
+      let name = header::HeaderName::from_bytes(name.as_bytes()).with_context(|| format!("invalid header name: {name}"))?;
+      let value = header::HeaderValue::from_str(value).with_context(|| format!("invalid value for header {name}"))?;
+
+      self.headers.insert(name, value);
+    }
+
+    if let Some(user_agent) = user_agent {
+      self.headers.insert(header::USER_AGENT, header::HeaderValue::from_str(user_agent).context("invalid user agent")?);
+    }
+
+    Ok(self)
   }
 }
 
@@ -77,17 +176,34 @@ impl CatalogFetcher for HttpCatalogFetcher {
   async fn fetch_catalog(&self, url: &str, auth_token: Option<&str>) -> anyhow::Result<Catalog> {
     tracing::debug!(url, "fetching catalog");
 
-    let client = reqwest::Client::default();
-
     let token = match auth_token {
-      Some(token) => Some(shellexpand::env(token)?),
+      Some(token) => Some(shellexpand::env(token)?.into_owned()),
       None => None,
     };
 
-    match token {
-      Some(token) => Ok(client.get(url).header(header::AUTHORIZATION, format!("Token {token}")).send().await?.json::<Catalog>().await?),
-      None => Ok(client.get(url).send().await?.json::<Catalog>().await?),
+    let cached = self.cache.lock().expect("catalog cache lock poisoned").get(url).cloned();
+
+    let (body, validators) = match self.get(url, token.as_deref(), cached.as_ref().map(|cached| &cached.validators)).await? {
+      Some(fetched) => fetched,
+      None => {
+        tracing::debug!(url, "catalog was not modified, reusing cached version");
+
+        // A conditional request is only sent when a cached version exists.
+        return Ok(cached.map(|cached| cached.catalog).unwrap_or_default());
+      }
+    };
+
+    let catalog = serde_json::from_slice::<Catalog>(&body).map_err(|err| FetchError::Parse(url.to_string(), err.into()))?;
+
+    if !validators.is_empty() {
+      self
+        .cache
+        .lock()
+        .expect("catalog cache lock poisoned")
+        .insert(url.to_string(), CachedCatalog { validators, catalog: catalog.clone() });
     }
+
+    Ok(catalog)
   }
 }
 
@@ -95,15 +211,84 @@ impl HttpCatalogFetcher {
   async fn fetch_http(&self, url: &str) -> anyhow::Result<Manifest> {
     tracing::debug!(url, "fetching http manifest");
 
-    let response = reqwest::get(url).await.context("could not reach manifest location")?;
+    let (body, _) = self
+      .get(url, None, None)
+      .await
+      .and_then(|fetched| fetched.ok_or_else(|| FetchError::Status(url.to_string(), StatusCode::NOT_MODIFIED)))
+      .context("could not retrieve manifest")?;
 
-    match self.format {
-      ManifestFormat::Json => response.json().await.context("invalid manifest file"),
+    let manifest = match self.format {
+      ManifestFormat::Json => serde_json::from_slice(&body).map_err(anyhow::Error::from),
       ManifestFormat::Yaml => {
         tracing::warn!("using a YAML manifest is deprecated, support will be removed in a future version, use JSON instead");
 
-        serde_yaml::from_str(&response.text().await?).context("could not parse local manifest file as YAML")
+        serde_yaml::from_slice(&body).map_err(anyhow::Error::from)
+      }
+    };
+
+    manifest.map_err(|err| FetchError::Parse(url.to_string(), err)).context("invalid manifest file")
+  }
+
+  /// Retrieve the body at `url`, retrying transient failures with exponential backoff.
+  ///
+  /// When `validators` are provided, the request is made conditional and `None`
+  /// is returned if the server reports the resource was not modified.
+  async fn get(&self, url: &str, token: Option<&str>, validators: Option<&CacheValidators>) -> Result<Option<(Vec<u8>, CacheValidators)>, FetchError> {
+    let client = reqwest::Client::builder()
+      .default_headers(self.headers.clone())
+      .build()
+      .map_err(|err| FetchError::Network(url.to_string(), err))?;
+
+    let mut backoff = self.retry_backoff;
+    let mut attempt = 0;
+
+    loop {
+      let mut request = client.get(url);
+
+      if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Token {token}"));
+      }
+
+      if let Some(validators) = validators {
+        if let Some(etag) = &validators.etag {
+          request = request.header(header::IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &validators.last_modified {
+          request = request.header(header::IF_MODIFIED_SINCE, last_modified.clone());
+        }
+      }
+
+      let err = match request.send().await {
+        Ok(response) if response.status() == StatusCode::NOT_MODIFIED && validators.is_some() => return Ok(None),
+        Ok(response) if response.status().is_success() => {
+          let validators = CacheValidators::from_headers(response.headers());
+
+          match response.bytes().await {
+            Ok(body) => return Ok(Some((body.to_vec(), validators))),
+            Err(err) => FetchError::Network(url.to_string(), err),
+          }
+        }
+        Ok(response) => FetchError::Status(url.to_string(), response.status()),
+        Err(err) => FetchError::Network(url.to_string(), err),
+      };
+
+      if attempt >= self.retries || !err.is_transient() {
+        return Err(err);
       }
+
+      attempt += 1;
+
+      tracing::warn!(
+        url,
+        attempt,
+        error = err.to_string(),
+        "transient error while fetching remote resource, retrying in {}ms",
+        backoff.as_millis()
+      );
+
+      tokio::time::sleep(backoff).await;
+
+      backoff *= 2;
     }
   }
 
@@ -157,14 +342,23 @@ impl CatalogFetcher for TestFetcher {
 
 #[cfg(test)]
 mod tests {
+  use std::collections::HashMap;
+
   use wiremock::{Mock, MockServer, ResponseTemplate, matchers::*};
 
   use crate::{
     CatalogFetcher, HttpCatalogFetcher,
     catalog::OPENSANCTIONS_CATALOG_URL,
-    fetcher::{ManifestFormat, ManifestProtocol},
+    fetcher::{FetchError, ManifestFormat, ManifestProtocol},
   };
 
+  fn fetcher(url: String) -> HttpCatalogFetcher {
+    HttpCatalogFetcher {
+      retry_backoff: std::time::Duration::from_millis(1),
+      ..HttpCatalogFetcher::from_manifest_url(Some(url)).unwrap()
+    }
+  }
+
   #[test]
   fn detect_protocol_and_format() {
     let tests = &[
@@ -321,4 +515,181 @@ mod tests {
     assert_eq!(manifest.catalogs[0].scope.as_deref(), Some("myscope"));
     assert_eq!(manifest.catalogs[0].resource_name.as_deref(), Some("ents.json"));
   }
+
+  #[tokio::test]
+  async fn retry_manifest_after_transient_failure() {
+    let mock = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/manifest.json"))
+      .respond_with(ResponseTemplate::new(503))
+      .up_to_n_times(2)
+      .mount(&mock)
+      .await;
+
+    Mock::given(method("GET"))
+      .and(path("/manifest.json"))
+      .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"catalogs":[{"url": "http://myurl.tld","scope":"myscope"}]}"#, "application/json"))
+      .mount(&mock)
+      .await;
+
+    let manifest = fetcher(format!("{}/manifest.json", mock.uri())).fetch_manifest().await.unwrap();
+
+    assert_eq!(manifest.catalogs[0].url, "http://myurl.tld");
+    assert_eq!(mock.received_requests().await.unwrap().len(), 3);
+  }
+
+  #[tokio::test]
+  async fn retry_catalog_after_transient_failure() {
+    let mock = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/catalog.json"))
+      .respond_with(ResponseTemplate::new(500))
+      .up_to_n_times(1)
+      .mount(&mock)
+      .await;
+
+    Mock::given(method("GET"))
+      .and(path("/catalog.json"))
+      .and(header("authorization", "Token helloworld"))
+      .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"datasets": [{"name": "thecatalog", "title": "The Catalog", "version": "20260110175500-abc"}]}"#, "application/json"))
+      .mount(&mock)
+      .await;
+
+    let catalog = fetcher(format!("{}/manifest.json", mock.uri()))
+      .fetch_catalog(&format!("{}/catalog.json", mock.uri()), Some("helloworld"))
+      .await
+      .unwrap();
+
+    assert_eq!(catalog.datasets[0].name, "thecatalog");
+    assert_eq!(mock.received_requests().await.unwrap().len(), 2);
+  }
+
+  #[tokio::test]
+  async fn give_up_after_retries() {
+    let mock = MockServer::start().await;
+
+    Mock::given(method("GET")).and(path("/catalog.json")).respond_with(ResponseTemplate::new(502)).mount(&mock).await;
+
+    let fetcher = HttpCatalogFetcher {
+      retries: 2,
+      ..fetcher(format!("{}/manifest.json", mock.uri()))
+    };
+    let err = fetcher.fetch_catalog(&format!("{}/catalog.json", mock.uri()), None).await.unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<FetchError>(), Some(FetchError::Status(_, status)) if status.as_u16() == 502));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 3);
+  }
+
+  #[tokio::test]
+  async fn do_not_retry_parse_errors() {
+    let mock = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/catalog.json"))
+      .respond_with(ResponseTemplate::new(200).set_body_raw("not json", "application/json"))
+      .mount(&mock)
+      .await;
+
+    let err = fetcher(format!("{}/manifest.json", mock.uri()))
+      .fetch_catalog(&format!("{}/catalog.json", mock.uri()), None)
+      .await
+      .unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<FetchError>(), Some(FetchError::Parse(..))));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn network_errors_are_reported() {
+    let fetcher = HttpCatalogFetcher {
+      retries: 0,
+      ..fetcher("http://127.0.0.1:1/manifest.json".to_string())
+    };
+    let err = fetcher.fetch_manifest().await.unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<FetchError>(), Some(FetchError::Network(..))));
+  }
+
+  #[tokio::test]
+  async fn send_configured_headers() {
+    let mock = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/manifest.json"))
+      .and(header("user-agent", "motiva-test/1.0"))
+      .and(header("x-mirror-key", "secret"))
+      .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"catalogs":[{"url": "http://myurl.tld","scope":"myscope"}]}"#, "application/json"))
+      .mount(&mock)
+      .await;
+
+    Mock::given(method("GET"))
+      .and(path("/catalog.json"))
+      .and(header("user-agent", "motiva-test/1.0"))
+      .and(header("authorization", "Token helloworld"))
+      .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"datasets": []}"#, "application/json"))
+      .mount(&mock)
+      .await;
+
+    let headers = HashMap::from([("X-Mirror-Key".to_string(), "secret".to_string()), ("Authorization".to_string(), "Token default".to_string())]);
+    let fetcher = fetcher(format!("{}/manifest.json", mock.uri())).with_headers(Some("motiva-test/1.0"), &headers).unwrap();
+
+    fetcher.fetch_manifest().await.unwrap();
+    fetcher.fetch_catalog(&format!("{}/catalog.json", mock.uri()), Some("helloworld")).await.unwrap();
+
+    let requests = mock.received_requests().await.unwrap();
+
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].headers.get("authorization").unwrap(), "Token default");
+    assert_eq!(requests[1].headers.get_all("authorization").iter().count(), 1);
+  }
+
+  #[test]
+  fn invalid_headers() {
+    let fetcher = HttpCatalogFetcher::default();
+
+    assert!(fetcher.clone().with_headers(None, &HashMap::from([("invalid header".to_string(), "value".to_string())])).is_err());
+    assert!(fetcher.with_headers(Some("bad\nagent"), &HashMap::new()).is_err());
+  }
+
+  #[tokio::test]
+  async fn reuse_catalog_when_not_modified() {
+    let mock = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/catalog.json"))
+      .and(header("if-none-match", "\"v1\""))
+      .respond_with(ResponseTemplate::new(304))
+      .mount(&mock)
+      .await;
+
+    Mock::given(method("GET"))
+      .and(path("/catalog.json"))
+      .respond_with(
+        ResponseTemplate::new(200)
+          .insert_header("etag", "\"v1\"")
+          .insert_header("last-modified", "Sat, 10 Jan 2026 17:55:00 GMT")
+          .set_body_raw(r#"{"datasets": [{"name": "thecatalog", "title": "The Catalog", "version": "20260110175500-abc"}]}"#, "application/json"),
+      )
+      .mount(&mock)
+      .await;
+
+    let fetcher = fetcher(format!("{}/manifest.json", mock.uri()));
+    let url = format!("{}/catalog.json", mock.uri());
+
+    let first = fetcher.fetch_catalog(&url, None).await.unwrap();
+    let second = fetcher.fetch_catalog(&url, None).await.unwrap();
+
+    assert_eq!(second.datasets.len(), 1);
+    assert_eq!(second.datasets[0].name, first.datasets[0].name);
+    assert_eq!(second.datasets[0].version, first.datasets[0].version);
+
+    let requests = mock.received_requests().await.unwrap();
+
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].headers.get("if-none-match").is_none());
+    assert_eq!(requests[1].headers.get("if-none-match").unwrap(), "\"v1\"");
+    assert_eq!(requests[1].headers.get("if-modified-since").unwrap(), "Sat, 10 Jan 2026 17:55:00 GMT");
+  }
 }
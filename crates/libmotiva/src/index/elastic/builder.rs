@@ -1,7 +1,7 @@
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-use crate::index::elastic::config::EsOptions;
+use crate::index::elastic::config::{EsFlavor, EsOptions, version_at_least};
 use crate::index::elastic::{DEFAULT_INDEX_PREFIX, IndexState, SCOPED_INDEX_SUFFIX};
 use crate::{error::MotivaError, index::elastic::config::IndexVersion, prelude::ElasticsearchProvider};
 use anyhow::Context;
@@ -14,6 +14,10 @@ use reqwest::StatusCode;
 
 impl ElasticsearchProvider {
   pub async fn new<'o>(url: &str, options: EsOptions<'o>) -> Result<ElasticsearchProvider, MotivaError> {
+    if options.flavor == EsFlavor::OpenSearch && matches!(options.auth, EsAuthMethod::ApiKey(..) | EsAuthMethod::EncodedApiKey(_)) {
+      return Err(MotivaError::ConfigError("OpenSearch does not support API key authentication".into()));
+    }
+
     let es = {
       let parsed_url = Url::parse(url).context("invalid index URL")?;
       let transport_builder = TransportBuilder::new(SingleNodeConnectionPool::new(parsed_url));
@@ -50,6 +54,21 @@ impl ElasticsearchProvider {
       })),
     };
 
+    if let Some(minimum) = options.minimum_version.as_deref() {
+      match tokio::time::timeout(Duration::from_secs(5), provider.detect_cluster_version(options.flavor)).await {
+        Ok(Ok(version)) if !version_at_least(&version, minimum) => {
+          return Err(MotivaError::OtherError(anyhow::anyhow!(
+            "index cluster version {version} is older than the minimum supported version {minimum}"
+          )));
+        }
+
+        Ok(Ok(version)) => tracing::debug!(version, "detected index cluster version"),
+        Ok(Err(err @ MotivaError::ConfigError(_))) => return Err(err),
+        Ok(Err(err)) => tracing::warn!(error = err.to_string(), minimum, "could not check index cluster version"),
+        Err(_) => tracing::warn!(minimum, "timed out checking index cluster version"),
+      }
+    }
+
     let _ = tokio::time::timeout(Duration::from_secs(5), provider.refresh_index_state()).await;
 
     Ok(provider)
@@ -113,7 +132,7 @@ mod tests {
   use std::sync::{Arc, RwLock};
 
   use crate::index::elastic::builder::EsTlsVerification;
-  use crate::index::elastic::config::EsOptions;
+  use crate::index::elastic::config::{EsFlavor, EsOptions};
   use crate::{
     index::elastic::{IndexState, config::IndexVersion},
     prelude::{ElasticsearchProvider, EsAuthMethod},
@@ -216,6 +235,100 @@ mod tests {
     assert_eq!(provider.state.read().unwrap().scoped_index, None);
   }
 
+  async fn es_builder_with_cluster_version(version: &str, minimum: &str) -> Result<ElasticsearchProvider, crate::MotivaError> {
+    use serde_json::json;
+    use wiremock::{
+      Mock, MockServer, ResponseTemplate,
+      matchers::{method, path},
+    };
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "version": { "number": version } })))
+      .mount(&server)
+      .await;
+
+    ElasticsearchProvider::new(
+      &server.uri(),
+      EsOptions {
+        minimum_version: Some(minimum.to_string()),
+        ..Default::default()
+      },
+    )
+    .await
+  }
+
+  #[tokio::test]
+  async fn es_builder_minimum_version() {
+    assert!(es_builder_with_cluster_version("8.15.0", "8.0.0").await.is_ok());
+
+    let error = es_builder_with_cluster_version("7.17.3", "8.0.0").await.err().unwrap();
+
+    assert_eq!(error.to_string(), "index cluster version 7.17.3 is older than the minimum supported version 8.0.0");
+  }
+
+  #[tokio::test]
+  async fn es_builder_minimum_version_unreachable() {
+    let options = EsOptions {
+      minimum_version: Some("8.0.0".to_string()),
+      ..Default::default()
+    };
+
+    assert!(ElasticsearchProvider::new("http://url:9200", options).await.is_ok());
+  }
+
+  async fn es_builder_with_opensearch(flavor: EsFlavor, minimum: &str) -> Result<ElasticsearchProvider, crate::MotivaError> {
+    use wiremock::{
+      Mock, MockServer, ResponseTemplate,
+      matchers::{method, path},
+    };
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .and(path("/"))
+      .respond_with(ResponseTemplate::new(200).set_body_raw(include_str!("fixtures/opensearch/info.json"), "application/json"))
+      .mount(&server)
+      .await;
+
+    ElasticsearchProvider::new(
+      &server.uri(),
+      EsOptions {
+        minimum_version: Some(minimum.to_string()),
+        flavor,
+        ..Default::default()
+      },
+    )
+    .await
+  }
+
+  #[tokio::test]
+  async fn es_builder_opensearch() {
+    assert!(es_builder_with_opensearch(EsFlavor::OpenSearch, "2.0.0").await.is_ok());
+    assert!(es_builder_with_opensearch(EsFlavor::OpenSearch, "3.0.0").await.is_err());
+
+    let error = es_builder_with_opensearch(EsFlavor::Elasticsearch, "8.0.0").await.err().unwrap();
+
+    assert_eq!(error.to_string(), "invalid configuration: index cluster runs opensearch, but was configured for elasticsearch");
+
+    let options = EsOptions {
+      auth: EsAuthMethod::EncodedApiKey("secret".to_string()),
+      flavor: EsFlavor::OpenSearch,
+      ..Default::default()
+    };
+
+    assert!(matches!(ElasticsearchProvider::new("http://url:9200", options).await, Err(crate::MotivaError::ConfigError(_))));
+  }
+
+  #[test]
+  fn es_flavor_from_str() {
+    assert_eq!("opensearch".parse::<EsFlavor>().unwrap(), EsFlavor::OpenSearch);
+    assert_eq!("elasticsearch".parse::<EsFlavor>().unwrap(), EsFlavor::Elasticsearch);
+    assert!("solr".parse::<EsFlavor>().is_err());
+  }
+
   fn provider_with_prefix(prefix: &str) -> ElasticsearchProvider {
     ElasticsearchProvider {
       es: Elasticsearch::default(),
@@ -1,4 +1,4 @@
-use std::{fmt::Display, sync::PoisonError};
+use std::{fmt::Display, str::FromStr, sync::PoisonError};
 
 use ahash::HashMap;
 use elasticsearch::indices::IndicesGetMappingParts;
@@ -12,6 +12,42 @@ pub struct EsOptions<'o> {
   pub auth: EsAuthMethod,
   pub tls: &'o EsTlsVerification,
   pub index_name: Option<String>,
+  /// Refuse to start against a cluster older than this version (e.g. `8.0.0`).
+  pub minimum_version: Option<String>,
+  /// Search engine running the cluster.
+  pub flavor: EsFlavor,
+}
+
+/// Search engine behind the index URL.
+///
+/// OpenSearch speaks the same query DSL, but does not support Elasticsearch
+/// API keys and versions its releases independently.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EsFlavor {
+  #[default]
+  Elasticsearch,
+  OpenSearch,
+}
+
+impl EsFlavor {
+  fn distribution(&self) -> &'static str {
+    match self {
+      EsFlavor::Elasticsearch => "elasticsearch",
+      EsFlavor::OpenSearch => "opensearch",
+    }
+  }
+}
+
+impl FromStr for EsFlavor {
+  type Err = anyhow::Error;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "elasticsearch" => Ok(EsFlavor::Elasticsearch),
+      "opensearch" => Ok(EsFlavor::OpenSearch),
+      other => Err(anyhow::anyhow!("unsupported index flavor: {other}")),
+    }
+  }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -103,6 +139,59 @@ impl ElasticsearchProvider {
 
     Err(MotivaError::OtherError(anyhow::anyhow!("index {} has an unrecognized mapping", self.main_index)))
   }
+
+  /// Retrieve the version number reported by the cluster.
+  ///
+  /// Since OpenSearch numbers its versions independently, a cluster of another
+  /// flavor than the one configured is reported as a configuration error.
+  pub(crate) async fn detect_cluster_version(&self, flavor: EsFlavor) -> Result<String, MotivaError> {
+    let info: ClusterInfo = self.es.info().send().await?.error_for_status_code()?.json().await?;
+    let distribution = info.version.distribution.as_deref().unwrap_or("elasticsearch");
+
+    if distribution != flavor.distribution() {
+      return Err(MotivaError::ConfigError(format!("index cluster runs {distribution}, but was configured for {}", flavor.distribution())));
+    }
+
+    Ok(info.version.number)
+  }
+}
+
+#[derive(Deserialize)]
+struct ClusterInfo {
+  version: ClusterVersion,
+}
+
+#[derive(Deserialize)]
+struct ClusterVersion {
+  number: String,
+  /// Only reported by OpenSearch.
+  distribution: Option<String>,
+}
+
+/// Whether a dotted version number is at least the provided minimum.
+///
+/// Missing components are considered to be zero, and pre-release suffixes
+/// (`8.0.0-SNAPSHOT`) are ignored.
+pub(crate) fn version_at_least(version: &str, minimum: &str) -> bool {
+  fn components(version: &str) -> Vec<u64> {
+    version
+      .split(['-', '+'])
+      .next()
+      .unwrap_or_default()
+      .split('.')
+      .map(|component| component.parse().unwrap_or(0))
+      .collect()
+  }
+
+  let (version, minimum) = (components(version), components(minimum));
+  let len = version.len().max(minimum.len());
+
+  let pad = |mut components: Vec<u64>| {
+    components.resize(len, 0);
+    components
+  };
+
+  pad(version) >= pad(minimum)
 }
 
 #[cfg(test)]
@@ -123,6 +212,15 @@ mod tests {
   use super::IndexVersion;
   use crate::{ElasticsearchProvider, MotivaError, index::IndexProvider};
 
+  #[test]
+  fn version_at_least() {
+    assert!(super::version_at_least("8.11.1", "8.0.0"));
+    assert!(super::version_at_least("8.0", "8.0.0"));
+    assert!(super::version_at_least("9.0.0-SNAPSHOT", "8.19"));
+    assert!(!super::version_at_least("7.17.3", "8"));
+    assert!(!super::version_at_least("8.9.0", "8.10.0"));
+  }
+
   #[test]
   fn index_version_display() {
     assert_eq!(IndexVersion::V4.to_string(), "v4");
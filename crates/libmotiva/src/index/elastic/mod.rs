@@ -12,12 +12,13 @@ use std::{
 
 use ahash::RandomState;
 use elasticsearch::Elasticsearch;
+use itertools::Itertools;
 use jiff::civil::DateTime;
 use serde::{Deserialize, Serialize};
 
 use crate::{
   index::elastic::config::IndexVersion,
-  matching::IndexType,
+  matching::{CaptionPreference, IndexType},
   model::{Entity, Properties, Schema},
   schemas::SCHEMAS,
 };
@@ -76,12 +77,31 @@ struct EsResponse {
   took: u64,
 }
 
+#[derive(Deserialize)]
+struct EsCountResponse {
+  count: u64,
+}
+
 #[allow(dead_code)]
 #[derive(Deserialize)]
-struct EsError {
-  #[serde(rename = "type")]
-  type_: String,
-  reason: String,
+#[serde(untagged)]
+enum EsError {
+  Detailed {
+    #[serde(rename = "type")]
+    type_: String,
+    reason: String,
+  },
+  /// Some OpenSearch plugins (security, for example) report errors as a bare string.
+  Message(String),
+}
+
+impl EsError {
+  fn reason(&self) -> &str {
+    match self {
+      EsError::Detailed { reason, .. } => reason,
+      EsError::Message(message) => message,
+    }
+  }
 }
 
 #[derive(Default, Deserialize)]
@@ -91,10 +111,28 @@ struct EsResults {
 }
 
 #[derive(Default, Deserialize)]
+#[serde(from = "EsTotal")]
 struct EsCounts {
   value: u64,
 }
 
+/// Total hit count, which OpenSearch returns as a bare integer when
+/// `rest_total_hits_as_int` is enabled.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EsTotal {
+  Object { value: u64 },
+  Count(u64),
+}
+
+impl From<EsTotal> for EsCounts {
+  fn from(total: EsTotal) -> Self {
+    match total {
+      EsTotal::Object { value } | EsTotal::Count(value) => EsCounts { value },
+    }
+  }
+}
+
 #[derive(Deserialize)]
 struct Aggregation {
   buckets: Vec<AggregationBucket>,
@@ -109,57 +147,114 @@ struct AggregationBucket {
 pub(crate) struct EsEntity {
   #[serde(rename(deserialize = "_id"))]
   pub id: String,
+  #[serde(rename(deserialize = "_score"), default)]
+  pub score: Option<f64>,
   pub _source: EsEntitySource,
+  /// Names of the should clauses the hit matched, when the query named them.
+  #[serde(default)]
+  pub matched_queries: Vec<String>,
 }
 
 impl From<EsEntity> for Entity {
   fn from(entity: EsEntity) -> Self {
-    let caption = entity.caption().to_string();
+    entity.into_entity(CaptionPreference::default())
+  }
+}
 
-    Self {
-      id: entity.id,
+impl EsEntity {
+  pub fn into_entity(mut self, preference: CaptionPreference) -> Entity {
+    let caption = self.caption(preference).to_string();
+    let canonical_caption = self.caption(CaptionPreference::default()).to_string();
+
+    // Surface names written in the preferred script first, keeping the indexed
+    // order otherwise.
+    if let CaptionPreference::Script(script) = preference
+      && let Some(names) = self._source.properties.get_mut("name")
+    {
+      names.sort_by_key(|name| whatlang::detect_script(name) != Some(script));
+    }
+
+    // Surface the caption first when it was picked among names regardless of their order.
+    if preference == CaptionPreference::Complete
+      && let Some(names) = self._source.properties.get_mut("name")
+    {
+      names.sort_by_key(|name| name != &caption);
+    }
+
+    Entity {
+      id: self.id,
       caption,
-      schema: entity._source.schema,
-      datasets: entity._source.datasets,
-      referents: entity._source.referents,
-      target: entity._source.target,
-      first_seen: entity._source.first_seen,
-      last_seen: entity._source.last_seen,
-      last_change: entity._source.last_change,
+      schema: self._source.schema,
+      datasets: self._source.datasets,
+      referents: self._source.referents,
+      target: self._source.target,
+      first_seen: self._source.first_seen,
+      last_seen: self._source.last_seen,
+      last_change: self._source.last_change,
       properties: Properties {
-        strings: entity._source.properties,
+        strings: self._source.properties,
         ..Default::default()
       },
+      matched_queries: self.matched_queries.into_iter().sorted().dedup().collect(),
+      canonical_caption,
       ..Default::default()
     }
   }
-}
 
-impl EsEntity {
-  pub fn caption(&self) -> &str {
-    if !self._source.caption.is_empty() {
-      return &self._source.caption;
+  pub fn caption(&self, preference: CaptionPreference) -> &str {
+    let indexed = &self._source.caption;
+
+    let Some(schema) = SCHEMAS.get(self._source.schema.as_str()) else {
+      return if indexed.is_empty() { &self.id } else { indexed };
+    };
+
+    let candidates = || schema.caption.iter().filter_map(|prop| self._source.properties.get(prop)).filter(|values| !values.is_empty());
+
+    if preference == CaptionPreference::Complete {
+      let names = candidates().chain(self._source.properties.get("alias")).flatten();
+
+      if let Some(name) = best_caption(names.collect::<Vec<_>>().into_iter()) {
+        return name;
+      }
     }
 
-    match SCHEMAS.get(self._source.schema.as_str()) {
-      Some(schema) => {
-        for prop in &schema.caption {
-          if let Some(values) = self._source.properties.get(prop)
-            && let Some(first) = values.first()
-          {
-            // TODO: heuristic to pick the "best" name for Things.
-            return first;
-          }
-        }
-
-        &self._source.caption
+    if let CaptionPreference::Script(script) = preference {
+      if !indexed.is_empty() && whatlang::detect_script(indexed) == Some(script) {
+        return indexed;
+      }
+
+      let in_script = candidates().find_map(|values| best_caption(values.iter().filter(|name| whatlang::detect_script(name) == Some(script))));
+
+      if let Some(name) = in_script {
+        return name;
       }
+    }
 
-      None => &self._source.caption,
+    if !indexed.is_empty() {
+      return indexed;
+    }
+
+    match candidates().next() {
+      Some(values) if preference == CaptionPreference::First => values[0].as_str(),
+      Some(values) => best_caption(values.iter()).unwrap_or(&self.id),
+      // Entities without any caption property value are captioned with their ID.
+      None => &self.id,
     }
   }
 }
 
+/// Pick the longest name among candidates, favoring those that do not look
+/// like abbreviations. Ties are resolved in favor of the first candidate.
+fn best_caption<'e>(names: impl DoubleEndedIterator<Item = &'e String>) -> Option<&'e str> {
+  names.rev().max_by_key(|name| (!is_abbreviated(name), name.chars().count())).map(String::as_str)
+}
+
+fn is_abbreviated(name: &str) -> bool {
+  let is_acronym = !name.contains(char::is_whitespace) && name.chars().any(char::is_alphabetic) && name.chars().filter(|c| c.is_alphabetic()).all(char::is_uppercase);
+
+  is_acronym || name.split_whitespace().any(|token| token.len() > 1 && token.ends_with('.'))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct EsEntitySource {
   pub caption: String,
@@ -179,17 +274,19 @@ mod tests {
   use std::collections::HashMap;
 
   use elasticsearch::Elasticsearch;
+  use whatlang::Script;
 
   use crate::{
     ElasticsearchProvider,
     index::elastic::{EsEntity, EsEntitySource, config::IndexVersion},
-    matching::IndexType,
+    matching::{CaptionPreference, IndexType},
     model::{Entity, HasProperties, Schema},
   };
 
   fn build_entity() -> EsEntity {
     EsEntity {
       id: "id".to_string(),
+      score: None,
       _source: EsEntitySource {
         schema: Schema::from("Person"),
         caption: "The Caption".to_string(),
@@ -206,6 +303,7 @@ mod tests {
           props
         },
       },
+      matched_queries: Vec::new(),
     }
   }
 
@@ -239,20 +337,139 @@ mod tests {
   fn get_caption() {
     let mut entity = build_entity();
 
-    assert_eq!(entity.caption(), "The Caption");
+    assert_eq!(entity.caption(CaptionPreference::default()), "The Caption");
 
     entity._source.caption = String::new();
 
-    assert_eq!(entity.caption(), "The Name");
+    assert_eq!(entity.caption(CaptionPreference::default()), "The Name");
 
     entity._source.properties.remove("name");
     entity._source.properties.insert("email".to_string(), vec!["bob@example.com".to_string()]);
 
-    assert_eq!(entity.caption(), "bob@example.com");
+    assert_eq!(entity.caption(CaptionPreference::default()), "bob@example.com");
 
     entity._source.properties.insert("lastName".to_string(), vec!["The Builder".to_string()]);
 
-    assert_eq!(entity.caption(), "The Builder");
+    assert_eq!(entity.caption(CaptionPreference::default()), "The Builder");
+  }
+
+  #[test]
+  fn get_caption_fallback() {
+    let mut entity = build_entity();
+
+    entity._source.caption = String::new();
+    entity._source.properties.clear();
+    entity._source.properties.insert("sourceUrl".to_string(), vec!["https://example.com/entity".to_string()]);
+    entity._source.properties.insert("notes".to_string(), vec!["Listed in 2022".to_string()]);
+
+    assert_eq!(entity.caption(CaptionPreference::default()), "id");
+
+    entity._source.schema = Schema::from("UnknownSchema");
+
+    assert_eq!(entity.caption(CaptionPreference::default()), "id");
+  }
+
+  #[test]
+  fn get_caption_heuristic() {
+    let mut entity = build_entity();
+
+    entity._source.schema = Schema::from("Organization");
+    entity._source.caption = String::new();
+    entity._source.properties.insert(
+      "name".to_string(),
+      vec![
+        "GAZPROM".to_string(),
+        "Gazprom PJSC".to_string(),
+        "Gazprom Public Joint Stock Co.".to_string(),
+        "Gazprom Public Joint Stock Company".to_string(),
+      ],
+    );
+
+    assert_eq!(entity.caption(CaptionPreference::First), "GAZPROM");
+    assert_eq!(entity.caption(CaptionPreference::Longest), "Gazprom Public Joint Stock Company");
+
+    entity._source.properties.insert("name".to_string(), vec!["ACME".to_string(), "Acme".to_string()]);
+
+    assert_eq!(entity.caption(CaptionPreference::Longest), "Acme");
+  }
+
+  #[test]
+  fn get_caption_complete() {
+    let mut entity = build_entity();
+
+    entity._source.caption = "Putin".to_string();
+    entity._source.properties.insert("name".to_string(), vec!["Putin".to_string(), "V. V. Putin".to_string()]);
+    entity._source.properties.insert("alias".to_string(), vec!["Vladimir Vladimirovich Putin".to_string()]);
+
+    assert_eq!(entity.caption(CaptionPreference::Longest), "Putin");
+    assert_eq!(entity.caption(CaptionPreference::Complete), "Vladimir Vladimirovich Putin");
+
+    entity
+      ._source
+      .properties
+      .insert("name".to_string(), vec!["Putin".to_string(), "Vladimir Vladimirovich Putin".to_string()]);
+
+    let result = entity.into_entity(CaptionPreference::Complete);
+
+    assert_eq!(result.caption, "Vladimir Vladimirovich Putin");
+    assert_eq!(result.props(&["name"]).as_ref(), ["Vladimir Vladimirovich Putin", "Putin"]);
+  }
+
+  #[test]
+  fn get_caption_preferred_script() {
+    let mut entity = build_entity();
+
+    entity._source.caption = "Vladimir Putin".to_string();
+    entity
+      ._source
+      .properties
+      .insert("name".to_string(), vec!["Vladimir Putin".to_string(), "Владимир Владимирович Путин".to_string()]);
+
+    assert_eq!(entity.caption(CaptionPreference::Script(Script::Latin)), "Vladimir Putin");
+    assert_eq!(entity.caption(CaptionPreference::Script(Script::Cyrillic)), "Владимир Владимирович Путин");
+    assert_eq!(entity.caption(CaptionPreference::Script(Script::Arabic)), "Vladimir Putin");
+
+    entity._source.caption = String::new();
+
+    assert_eq!(entity.caption(CaptionPreference::Script(Script::Arabic)), "Владимир Владимирович Путин");
+  }
+
+  #[test]
+  fn es_doc_to_entity_preferred_script() {
+    let mut entity = build_entity();
+
+    entity._source.properties.insert(
+      "name".to_string(),
+      vec!["Vladimir Putin".to_string(), "Владимир Путин".to_string(), "Vladimir Vladimirovich Putin".to_string()],
+    );
+
+    let cyrillic = entity.clone().into_entity(CaptionPreference::Script(Script::Cyrillic));
+
+    assert_eq!(cyrillic.props(&["name"]).first().unwrap(), "Владимир Путин");
+
+    let latin = entity.into_entity(CaptionPreference::Script(Script::Latin));
+
+    assert_eq!(latin.props(&["name"]).as_ref(), ["Vladimir Putin", "Vladimir Vladimirovich Putin", "Владимир Путин"]);
+  }
+
+  #[test]
+  fn es_doc_to_entity_caption_preference_does_not_affect_score() {
+    use bumpalo::Bump;
+
+    use crate::{SearchEntity, matching::Feature, matching::matchers::caption::CaptionExactMatch};
+
+    let mut entity = build_entity();
+
+    entity._source.caption = "Vladimir Putin".to_string();
+    entity._source.properties.insert("name".to_string(), vec!["Vladimir Putin".to_string(), "Владимир Путин".to_string()]);
+
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let default = entity.clone().into_entity(CaptionPreference::default());
+    let cyrillic = entity.into_entity(CaptionPreference::Script(Script::Cyrillic));
+
+    assert_eq!(cyrillic.caption, "Владимир Путин");
+    assert_eq!(CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &default), 1.0);
+    assert_eq!(CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &cyrillic), 1.0);
   }
 
   #[test]
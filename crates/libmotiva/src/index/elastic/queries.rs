@@ -1,11 +1,12 @@
 use std::{
+  borrow::Cow,
   collections::{HashMap, HashSet},
   sync::{Arc, PoisonError},
 };
 
 use ahash::RandomState;
 use anyhow::Context;
-use elasticsearch::{SearchParts, cluster::ClusterHealthParts, indices::IndicesGetAliasParts, params::SearchType};
+use elasticsearch::{CountParts, SearchParts, cluster::ClusterHealthParts, indices::IndicesGetAliasParts, params::SearchType as EsSearchType};
 use itertools::Itertools;
 use metrics::{counter, histogram};
 use opentelemetry::global;
@@ -14,22 +15,30 @@ use reqwest::StatusCode;
 use serde_json::json;
 use tokio::sync::RwLock;
 use tracing::instrument;
-use unicode_normalization::UnicodeNormalization;
 
 use crate::{
   Catalog,
   error::MotivaError,
   index::{
     EntityHandle, IndexProvider,
-    elastic::{EsEntity, EsErrorResponse, EsHealth, EsResponse, config::IndexVersion},
+    elastic::{EsCountResponse, EsEntity, EsErrorResponse, EsHealth, EsResponse, config::IndexVersion},
   },
-  matching::{MatchParams, extractors},
-  model::{Entity, ResolveSchemaLevel, SearchEntity},
+  matching::{MatchParams, SearchType, extractors},
+  model::{Entity, HasProperties, ResolveSchemaLevel, Schema, SearchEntity},
   prelude::ElasticsearchProvider,
   schemas::SCHEMAS,
   symbols::tagger::{ORG_TAGGER, PERSON_TAGGER},
 };
 
+impl From<SearchType> for EsSearchType {
+  fn from(value: SearchType) -> Self {
+    match value {
+      SearchType::DfsQueryThenFetch => EsSearchType::DfsQueryThenFetch,
+      SearchType::QueryThenFetch => EsSearchType::QueryThenFetch,
+    }
+  }
+}
+
 impl IndexProvider for ElasticsearchProvider {
   fn after_init(&self) {
     let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
@@ -93,7 +102,7 @@ impl IndexProvider for ElasticsearchProvider {
       .search(SearchParts::Index(&[index_name.as_ref()]))
       .from(0)
       .size(params.candidate_limit(params.match_candidates) as i64)
-      .search_type(SearchType::DfsQueryThenFetch)
+      .search_type(params.search_type.unwrap_or_default().into())
       .body(query)
       .send()
       .await?;
@@ -101,7 +110,7 @@ impl IndexProvider for ElasticsearchProvider {
     if response.status_code() != StatusCode::OK {
       let body: EsErrorResponse = response.json().await?;
 
-      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason)));
+      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason().to_string())));
     }
 
     let body: EsResponse = response.json().await?;
@@ -116,13 +125,52 @@ impl IndexProvider for ElasticsearchProvider {
         global::meter("motiva").u64_histogram("index_hits").build().record(hits.len() as u64, &[]);
         global::meter("motiva").u64_histogram("index_latency").build().record(body.took, &[]);
 
-        Ok(hits.into_iter().map(Entity::from).collect())
+        let preference = params.caption_preference.unwrap_or_default();
+
+        Ok(
+          hits
+            .into_iter()
+            .map(|hit| {
+              let score = hit.score;
+              let mut entity = hit.into_entity(preference);
+
+              entity.index_score = score;
+              entity
+            })
+            .collect(),
+        )
       }
 
       None => Err(MotivaError::OtherError(anyhow::anyhow!("invalid response from elasticsearch"))),
     }
   }
 
+  /// Count the entities matching the filters a search would apply in a scope,
+  /// without scoring them.
+  #[instrument(skip_all)]
+  async fn count(&self, catalog: &Arc<RwLock<Catalog>>, scope: &str, params: &MatchParams) -> Result<u64, MotivaError> {
+    if !self.ready() {
+      return Err(MotivaError::IndexUnavailable);
+    }
+
+    let index_name = self.index_name(params.index_type);
+    let query = build_count_query(catalog, &index_name, scope, params).await;
+
+    tracing::trace!(%query, "running count query");
+
+    let response = self.es.count(CountParts::Index(&[index_name.as_ref()])).body(query).send().await?;
+
+    if response.status_code() != StatusCode::OK {
+      let body: EsErrorResponse = response.json().await?;
+
+      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason().to_string())));
+    }
+
+    let body: EsCountResponse = response.json().await?;
+
+    Ok(body.count)
+  }
+
   /// Get an entity from its ID.
   ///
   /// This will only return the requested entity, without recursing to nested
@@ -155,7 +203,7 @@ impl IndexProvider for ElasticsearchProvider {
     if response.status_code() != StatusCode::OK {
       let body: EsErrorResponse = response.json().await?;
 
-      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason)));
+      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason().to_string())));
     }
 
     let body: EsResponse = response.json().await?;
@@ -218,7 +266,7 @@ impl IndexProvider for ElasticsearchProvider {
     if response.status_code() != StatusCode::OK {
       let body: EsErrorResponse = response.json().await?;
 
-      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason)));
+      return Err(MotivaError::OtherError(anyhow::anyhow!(body.error.reason().to_string())));
     }
 
     let body = response.json::<serde_json::Value>().await?;
@@ -310,17 +358,99 @@ fn parse_index_dataset_versions(index_name: &str, indices: HashMap<String, serde
 }
 
 async fn build_query(catalog: &Arc<RwLock<Catalog>>, index_version: IndexVersion, index_name: &str, entity: &SearchEntity, params: &MatchParams) -> Result<serde_json::Value, MotivaError> {
-  Ok(json!({
+  let params = canonical_datasets(catalog, params).await;
+  let params = params.as_ref();
+  let mut shoulds = group_name_parts(
+    build_shoulds(index_version, entity, params.name_sample_size, params.seed, &params.field_mapping)?,
+    params.name_parts_should_match,
+  );
+
+  if params.explain {
+    name_queries(&mut shoulds);
+  }
+
+  let mut query = json!({
       "query": {
           "bool": {
               "filter": build_filters(catalog, entity, params).await?,
               "must": build_musts(index_name, params),
-              "should": build_shoulds(index_version, entity, params.name_sample_size)?,
+              "should": shoulds,
               "must_not": build_must_nots(params),
               "minimum_should_match": 1,
           }
       }
-  }))
+  });
+
+  if let Some(min_score) = params.min_index_score {
+    query["min_score"] = json!(min_score);
+  }
+
+  Ok(query)
+}
+
+/// Query counting the entities a search in `scope` could return, applying the
+/// request's dataset, topic and exclusion filters but no entity-specific clause.
+async fn build_count_query(catalog: &Arc<RwLock<Catalog>>, index_name: &str, scope: &str, params: &MatchParams) -> serde_json::Value {
+  let params = MatchParams {
+    scope: scope.to_string(),
+    ..params.clone()
+  };
+  let params = canonical_datasets(catalog, &params).await.into_owned();
+
+  let mut filters = Vec::<serde_json::Value>::new();
+
+  build_datasets(catalog, &mut filters, &params).await;
+
+  if let Some(topics) = &params.topics
+    && !topics.is_empty()
+  {
+    filters.push(json!({ "terms": { "topics": topics } }));
+  }
+
+  if let Some(since) = params.changed_since {
+    filters.push(json!({"range": { "last_change": { "gt": since } } }));
+  }
+
+  json!({
+      "query": {
+          "bool": {
+              "filter": filters,
+              "must": build_musts(index_name, &params),
+              "must_not": build_must_nots(&params),
+          }
+      }
+  })
+}
+
+/// Group the name-part clauses (`name_parts` terms on v4 indices, per-part
+/// `dis_max` groups on v5 indices) in their own boolean query when a ratio is
+/// provided.
+///
+/// The ratio is applied to the number of name-part clauses and rounded up, so
+/// that long names require more of their parts to match. Grouping them keeps
+/// the other clauses (full names, weak aliases, identifiers, etc.) from
+/// counting towards that number.
+fn group_name_parts(shoulds: Vec<serde_json::Value>, ratio: Option<f64>) -> Vec<serde_json::Value> {
+  let Some(ratio) = ratio else {
+    return shoulds;
+  };
+
+  let (name_parts, mut shoulds): (Vec<_>, Vec<_>) = shoulds.into_iter().partition(|clause| clause.pointer("/term/name_parts").is_some() || clause.get("dis_max").is_some());
+
+  if name_parts.is_empty() {
+    return shoulds;
+  }
+
+  let minimum_should_match = ((name_parts.len() as f64 * ratio.clamp(0.0, 1.0)).ceil() as usize).max(1);
+
+  shoulds.push(json!({
+      "bool": {
+          "should": name_parts,
+          "minimum_should_match": minimum_should_match,
+      }
+  }));
+
+  shoulds
 }
 
 fn build_musts(index_name: &str, params: &MatchParams) -> Vec<serde_json::Value> {
@@ -334,7 +464,7 @@ fn build_musts(index_name: &str, params: &MatchParams) -> Vec<serde_json::Value>
 async fn build_filters(catalog: &Arc<RwLock<Catalog>>, entity: &SearchEntity, params: &MatchParams) -> Result<Vec<serde_json::Value>, MotivaError> {
   let mut filters = Vec::<serde_json::Value>::new();
 
-  build_schemas(entity, &mut filters)?;
+  build_schemas(entity, params, &mut filters)?;
   build_datasets(catalog, &mut filters, params).await;
   build_topics(entity, params, &mut filters);
   build_arbitrary_terms(entity, &mut filters);
@@ -361,14 +491,43 @@ fn build_must_nots(params: &MatchParams) -> Vec<serde_json::Value> {
   filters
 }
 
-fn build_schemas(entity: &SearchEntity, filters: &mut Vec<serde_json::Value>) -> Result<(), MotivaError> {
-  let schemas = entity.schema.matchable_schemas(ResolveSchemaLevel::Root);
+/// With `filter_schemas`, only the schemas the query can be scored against are
+/// requested, so the candidates dropped after the search do not take up slots.
+fn build_schemas(entity: &SearchEntity, params: &MatchParams, filters: &mut Vec<serde_json::Value>) -> Result<(), MotivaError> {
+  let schemas = match params.filter_schemas {
+    true => SCHEMAS.keys().filter(|name| entity.can_match(&Schema::from(name))).sorted().cloned().collect::<Vec<_>>(),
+    false => entity.all_schemas().flat_map(|schema| schema.matchable_schemas(ResolveSchemaLevel::Root)).unique().collect::<Vec<_>>(),
+  };
 
   filters.push(json!({ "terms": { "schema": schemas } }));
 
   Ok(())
 }
 
+/// Replace the scope and dataset names of the request with their canonical
+/// catalog names, so they can be given regardless of case and accents.
+///
+/// Names that are not in the catalog are kept as provided.
+async fn canonical_datasets<'p>(catalog: &Arc<RwLock<Catalog>>, params: &'p MatchParams) -> Cow<'p, MatchParams> {
+  let guard = catalog.read().await;
+  let canonical = |name: &String| guard.resolve_dataset_name(name).map(ToOwned::to_owned).unwrap_or_else(|| name.clone());
+
+  let scope = canonical(&params.scope);
+  let include_dataset = params.include_dataset.iter().map(canonical).collect::<Vec<_>>();
+  let exclude_dataset = params.exclude_dataset.iter().map(canonical).collect::<Vec<_>>();
+
+  if scope == params.scope && include_dataset == params.include_dataset && exclude_dataset == params.exclude_dataset {
+    return Cow::Borrowed(params);
+  }
+
+  Cow::Owned(MatchParams {
+    scope,
+    include_dataset,
+    exclude_dataset,
+    ..params.clone()
+  })
+}
+
 async fn build_datasets(catalog: &Arc<RwLock<Catalog>>, filters: &mut Vec<serde_json::Value>, params: &MatchParams) {
   let scope = {
     let guard = catalog.read().await;
@@ -428,10 +587,31 @@ fn build_arbitrary_terms(lhs: &SearchEntity, filters: &mut Vec<serde_json::Value
   }
 }
 
-fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usize) -> anyhow::Result<Vec<serde_json::Value>> {
+/// Identifier properties that single out an entity, queried with a higher
+/// boost so identifier-only queries retrieve the entities carrying them.
+const STRONG_IDENTIFIERS: &[&str] = &["leiCode", "isin", "innCode", "ogrnCode", "bicCode", "imoNumber", "mmsi"];
+const STRONG_IDENTIFIER_BOOST: f64 = 3.0;
+/// Boost given to the entity designated by the query's ID, either directly or
+/// through its referents.
+///
+/// It outweighs the name clauses (boosted `3.0`) so the designated entity is
+/// ranked within the candidate limit even if its names changed since it was
+/// matched. It only affects retrieval, since candidates are scored on their
+/// properties alone.
+const REFERENT_BOOST: f64 = 10.0;
+/// Boost of the full-text clauses for the query's weak aliases, lower than the
+/// one of its names since weak aliases are often ambiguous.
+const WEAK_ALIAS_BOOST: f64 = 1.0;
+
+fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usize, seed: Option<u64>, field_mapping: &HashMap<String, String>) -> Result<Vec<serde_json::Value>, MotivaError> {
   let mut should = Vec::<serde_json::Value>::new();
 
-  let names = entity.pick_names(sample).iter().map(|s| s.nfc().collect::<String>()).collect::<Vec<_>>();
+  if let Some(id) = &entity.id {
+    should.push(json!({ "ids": { "values": [id], "boost": REFERENT_BOOST } }));
+    add_term(&mut should, "referents", id, REFERENT_BOOST);
+  }
+
+  let names = entity.pick_names(sample, seed).iter().map(|s| extractors::normalize_name(s)).collect::<Vec<_>>();
 
   for name in &names {
     should.push(json!({
@@ -447,6 +627,28 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
     }));
   }
 
+  // Weak aliases are not matchable, so they are not part of the names above,
+  // but can still help retrieve candidates when the client knows them.
+  let weak_aliases = entity.props(&["weakAlias"]);
+
+  for alias in weak_aliases.iter().map(|s| extractors::normalize_name(s)).unique() {
+    if names.contains(&alias) {
+      continue;
+    }
+
+    should.push(json!({
+        "match": {
+            "names": {
+                "query": alias,
+                "operator": "AND",
+                "boost": WEAK_ALIAS_BOOST,
+                "fuzziness": "AUTO",
+                "max_expansions": 200,
+            }
+        }
+    }));
+  }
+
   if index_version == IndexVersion::V4 {
     for name in extractors::index_name_keys(names.iter()) {
       add_term(&mut should, "name_keys", &name, 4.0);
@@ -454,7 +656,7 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
     for name in extractors::index_name_parts(names.iter()) {
       add_term(&mut should, "name_parts", &name, 1.0);
     }
-    for name in extractors::phonetic_name(names.iter()) {
+    for name in extractors::phonetic_name(names.iter(), entity.phonetic_algorithm) {
       add_term(&mut should, "name_phonetic", &name, 0.8);
     }
   }
@@ -487,7 +689,7 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
         for name in extractors::index_name_parts([name_part.to_owned()].iter()) {
           add_term(&mut dis_max, "name_parts", &name, boost);
         }
-        for name in extractors::phonetic_name([name_part.to_owned()].iter()) {
+        for name in extractors::phonetic_name([name_part.to_owned()].iter(), entity.phonetic_algorithm) {
           add_term(&mut dis_max, "name_phonetic", &name, boost * 0.5);
         }
 
@@ -522,7 +724,7 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
     }
   }
 
-  let schema = SCHEMAS.get(entity.schema.as_str()).ok_or(anyhow::anyhow!("unknown schema"))?;
+  let schema = SCHEMAS.get(entity.schema.as_str()).ok_or_else(|| MotivaError::InvalidSchema(entity.schema.as_str().to_string()))?;
   let properties = schema.properties(&SCHEMAS);
 
   for (property, values) in &entity.properties {
@@ -530,6 +732,18 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
       continue;
     };
 
+    if STRONG_IDENTIFIERS.contains(&property.as_str()) {
+      // Identifiers are indexed as provided by the source, so the normalized
+      // form is also queried in case the query was formatted differently.
+      let normalized = extractors::normalize_identifiers(values.iter()).collect::<Vec<_>>();
+
+      for value in values.iter().chain(normalized.iter()).unique() {
+        add_term(&mut should, mapped_field(field_mapping, "identifier", "identifiers"), value, STRONG_IDENTIFIER_BOOST);
+      }
+
+      continue;
+    }
+
     if property == "name" || !prop.matchable {
       continue;
     }
@@ -550,6 +764,8 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
       _ => continue,
     };
 
+    let lhs = mapped_field(field_mapping, &prop._type, lhs);
+
     for value in values {
       match op {
         Match => {
@@ -574,6 +790,65 @@ fn build_shoulds(index_version: IndexVersion, entity: &SearchEntity, sample: usi
   Ok(should)
 }
 
+/// Tag each should clause with the index field it queries, through Elasticsearch
+/// named queries, so hits report which clauses retrieved them.
+///
+/// Clauses grouped under a `dis_max` are named individually.
+fn name_queries(shoulds: &mut [serde_json::Value]) {
+  for clause in shoulds {
+    let Some(clause) = clause.as_object_mut() else {
+      continue;
+    };
+
+    for (kind, query) in clause.iter_mut() {
+      match kind.as_str() {
+        "dis_max" => {
+          if let Some(queries) = query.get_mut("queries").and_then(serde_json::Value::as_array_mut) {
+            name_queries(queries);
+          }
+        }
+
+        "bool" => {
+          if let Some(queries) = query.get_mut("should").and_then(serde_json::Value::as_array_mut) {
+            name_queries(queries);
+          }
+        }
+
+        "ids" => {
+          if let Some(query) = query.as_object_mut() {
+            query.insert("_name".to_string(), json!("ids"));
+          }
+        }
+
+        "term" | "match" => {
+          let Some((field, value)) = query.as_object_mut().and_then(|query| query.iter_mut().next()) else {
+            continue;
+          };
+
+          // Short-form clauses (`{ "match": { "field": "value" } }`) are
+          // expanded so they can carry a name.
+          if !value.is_object() {
+            let key = if kind == "term" { "value" } else { "query" };
+
+            *value = json!({ key: value.take() });
+          }
+
+          if let Some(value) = value.as_object_mut() {
+            value.insert("_name".to_string(), json!(field));
+          }
+        }
+
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Index field to query for a property type, honoring the request's overrides.
+fn mapped_field<'f>(field_mapping: &'f HashMap<String, String>, prop_type: &str, default: &'f str) -> &'f str {
+  field_mapping.get(prop_type).map(String::as_str).unwrap_or(default)
+}
+
 fn add_term(queries: &mut Vec<serde_json::Value>, key: &str, name: &str, boost: f64) {
   queries.push(json!({
       "term": {
@@ -588,6 +863,7 @@ fn add_term(queries: &mut Vec<serde_json::Value>, key: &str, name: &str, boost:
 #[cfg(test)]
 mod tests {
   use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
     sync::Arc,
   };
@@ -596,7 +872,14 @@ mod tests {
   use serde_json_assert::{assert_json_contains, assert_json_eq, assert_json_include};
   use tokio::sync::RwLock;
 
-  use crate::{Catalog, catalog::CatalogDataset, index::elastic::config::IndexVersion, model::SearchEntity, prelude::MatchParams};
+  use crate::{
+    Catalog,
+    catalog::CatalogDataset,
+    index::elastic::config::IndexVersion,
+    matching::extractors,
+    model::{Schema, SearchEntity},
+    prelude::MatchParams,
+  };
 
   fn fake_catalog() -> Arc<RwLock<Catalog>> {
     Arc::new(RwLock::new({
@@ -640,12 +923,44 @@ mod tests {
     let entity = SearchEntity::builder("Person").properties(&[]).build();
     let mut schemas = Vec::new();
 
-    super::build_schemas(&entity, &mut schemas).unwrap();
+    super::build_schemas(&entity, &MatchParams::default(), &mut schemas).unwrap();
 
     assert_eq!(schemas.len(), 1);
     assert_json_eq!(schemas[0], json!({ "terms": { "schema": ["Person", "LegalEntity"] } }));
   }
 
+  #[test]
+  fn build_schemas_filtered() {
+    let entity = SearchEntity::builder("Person").properties(&[]).build();
+    let mut schemas = Vec::new();
+
+    let params = MatchParams {
+      filter_schemas: true,
+      ..Default::default()
+    };
+
+    super::build_schemas(&entity, &params, &mut schemas).unwrap();
+
+    let requested = schemas[0]["terms"]["schema"].as_array().unwrap().iter().map(|schema| schema.as_str().unwrap()).collect::<Vec<_>>();
+
+    assert!(requested.contains(&"Person"));
+    assert!(requested.contains(&"LegalEntity"));
+    assert!(!requested.contains(&"Company"));
+    assert!(!requested.contains(&"Vessel"));
+    assert!(requested.iter().all(|schema| entity.can_match(&Schema::from(schema))));
+  }
+
+  #[test]
+  fn build_schemas_union() {
+    let entity = SearchEntity::builder("Person").schemas(&["Company"]).properties(&[]).build();
+    let mut schemas = Vec::new();
+
+    super::build_schemas(&entity, &MatchParams::default(), &mut schemas).unwrap();
+
+    assert_eq!(schemas.len(), 1);
+    assert_json_eq!(schemas[0], json!({ "terms": { "schema": ["Person", "LegalEntity", "Company", "Organization"] } }));
+  }
+
   #[test]
   fn build_must_nots() {
     let params = MatchParams {
@@ -684,6 +999,65 @@ mod tests {
     super::build_query(&fake_catalog(), IndexVersion::V4, "yente-entities", &entity, &MatchParams::default()).await.unwrap();
   }
 
+  #[tokio::test]
+  async fn build_query_referent_id() {
+    let entity = SearchEntity::builder("Person").id("ofac-35096").properties(&[("name", &["V. Putin"])]).build();
+
+    let query = super::build_query(&fake_catalog(), IndexVersion::V4, "yente-entities", &entity, &MatchParams::default()).await.unwrap();
+
+    assert_json_contains!(
+      container: query["query"]["bool"]["should"],
+      contained: json!([
+          { "ids": { "values": ["ofac-35096"], "boost": 10.0 } },
+          { "term": { "referents": { "value": "ofac-35096", "boost": 10.0 } } }
+      ]),
+    );
+  }
+
+  #[tokio::test]
+  async fn build_query_min_index_score() {
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let query = super::build_query(&fake_catalog(), IndexVersion::V4, "yente-entities", &entity, &MatchParams::default()).await.unwrap();
+
+    assert!(query.get("min_score").is_none());
+
+    let params = MatchParams {
+      min_index_score: Some(12.5),
+      ..Default::default()
+    };
+
+    let query = super::build_query(&fake_catalog(), IndexVersion::V4, "yente-entities", &entity, &params).await.unwrap();
+
+    assert_eq!(query["min_score"], json!(12.5));
+  }
+
+  #[tokio::test]
+  async fn build_query_field_mapping() {
+    let entity = SearchEntity::builder("Company")
+      .properties(&[
+        ("name", &["Google LLC"]),
+        ("address", &["1600 Amphitheatre Parkway"]),
+        ("leiCode", &["529900T8BM49AURSDO55"]),
+        ("country", &["us"]),
+      ])
+      .build();
+
+    let params = MatchParams {
+      field_mapping: HashMap::from([("address".to_string(), "address_full".to_string()), ("identifier".to_string(), "registration_ids".to_string())]),
+      ..Default::default()
+    };
+
+    let query = super::build_query(&fake_catalog(), IndexVersion::V4, "yente-entities", &entity, &params).await.unwrap();
+    let shoulds = query.pointer("/query/bool/should").unwrap();
+
+    assert_json_contains!(container: shoulds, contained: json!([{ "match": { "address_full": "1600 Amphitheatre Parkway" } }]));
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "registration_ids": { "value": "529900T8BM49AURSDO55", "boost": 3.0 } } }]));
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "countries": { "value": "us", "boost": 1.0 } } }]));
+
+    assert!(shoulds.as_array().unwrap().iter().all(|clause| clause.pointer("/match/addresses").is_none()));
+  }
+
   #[test]
   fn build_should_v4() {
     let entity = SearchEntity::builder("Person")
@@ -695,7 +1069,7 @@ mod tests {
       ])
       .build();
 
-    let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5).unwrap();
+    let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap();
 
     assert_json_contains!(
         container: shoulds,
@@ -732,6 +1106,153 @@ mod tests {
     assert_json_contains!(container: shoulds, contained: json!([{ "term": { "identifiers": { "value": "1234", "boost": 1.0 } } }]));
   }
 
+  #[test]
+  fn build_should_aliases() {
+    let entity = SearchEntity::builder("Person")
+      .properties(&[("name", &["Vladimir Putin"]), ("alias", &["Vova"]), ("weakAlias", &["Vlad", "Vova"])])
+      .build();
+
+    for version in [IndexVersion::V4, IndexVersion::V5] {
+      let shoulds = super::build_shoulds(version, &entity, 5, None, &HashMap::default()).unwrap();
+
+      assert_json_contains!(
+          container: shoulds,
+          contained: json!([{ "match": { "names": { "boost": 3.0, "fuzziness": "AUTO", "operator": "AND", "query": "Vova" } } }]),
+      );
+
+      assert_json_contains!(
+          container: shoulds,
+          contained: json!([{ "match": { "names": { "boost": 1.0, "fuzziness": "AUTO", "operator": "AND", "query": "Vlad" } } }]),
+      );
+
+      let queries = shoulds.iter().filter_map(|clause| clause.pointer("/match/names/query")?.as_str()).collect::<Vec<_>>();
+
+      assert_eq!(queries.iter().filter(|query| **query == "Vova").count(), 1);
+    }
+
+    let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "name_parts": { "boost": 1.0, "value": "vova" } } }]));
+    assert!(shoulds.iter().all(|clause| clause.pointer("/term/name_parts/value") != Some(&json!("vlad"))));
+  }
+
+  #[test]
+  fn build_should_normalized_whitespace() {
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir\u{a0}\u{a0}Pu\u{200b}tin "])]).build();
+
+    let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert_json_contains!(
+        container: shoulds,
+        contained: json!([{ "match": { "names": { "boost": 3.0, "fuzziness": "AUTO", "operator": "AND", "query": "Vladimir Putin" } } }]),
+    );
+
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "name_keys": { "boost": 4.0, "value": "putinvladimir" } } }]));
+  }
+
+  #[test]
+  fn query_and_scoring_share_name_normalization() {
+    // Composed and decomposed forms of the same multi-script names.
+    let names = [
+      ("José Müller", "Jose\u{301} Mu\u{308}ller"),
+      ("Slobodan Milošević", "Slobodan Milos\u{30c}evic\u{301}"),
+      ("Сергей Лавров", "Серге\u{438}\u{306} Лавров"),
+      ("Ιωάννης Παπαδόπουλος", "Ιωα\u{301}ννης Παπαδο\u{301}πουλος"),
+      ("김정은", "\u{1100}\u{1175}\u{11b7}\u{110c}\u{1165}\u{11bc}\u{110b}\u{1173}\u{11ab}"),
+    ];
+
+    for (composed, decomposed) in names {
+      let mut lhs = SearchEntity::builder("Person").properties(&[("name", &[composed])]).build();
+      let mut rhs = SearchEntity::builder("Person").properties(&[("name", &[decomposed])]).build();
+
+      lhs.precompute();
+      rhs.precompute();
+
+      // Both forms yield the same query terms...
+      for version in [IndexVersion::V4, IndexVersion::V5] {
+        assert_eq!(
+          super::build_shoulds(version, &lhs, 5, None, &HashMap::default()).unwrap(),
+          super::build_shoulds(version, &rhs, 5, None, &HashMap::default()).unwrap(),
+          "query terms differ for {composed}"
+        );
+      }
+
+      // ... and the same scoring artifacts.
+      assert_eq!(lhs.clean_names, rhs.clean_names, "clean names differ for {composed}");
+      assert_eq!(lhs.name_parts, rhs.name_parts, "name parts differ for {composed}");
+      assert_eq!(lhs.name_parts_flat, rhs.name_parts_flat, "name parts differ for {composed}");
+      assert_eq!(lhs.phonetic_names, rhs.phonetic_names, "phonetic names differ for {composed}");
+      assert_eq!(lhs.name_fingerprints, rhs.name_fingerprints, "fingerprints differ for {composed}");
+
+      // Name parts queried from the index are the ones scored against, for scripts that are latinized on both sides.
+      if extractors::is_modern_alphabet(composed) {
+        let shoulds = super::build_shoulds(IndexVersion::V4, &rhs, 5, None, &HashMap::default()).unwrap();
+
+        for part in shoulds.iter().filter_map(|clause| clause.pointer("/term/name_parts/value").and_then(|value| value.as_str())) {
+          assert!(rhs.name_parts_flat.contains(part), "query term {part} is not scored for {composed}");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn build_should_identifier_only() {
+    let entity = SearchEntity::builder("Company").properties(&[("leiCode", &["5299-00T8BM49AURSDO55"])]).build();
+
+    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert_eq!(shoulds.len(), 2);
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "identifiers": { "value": "5299-00T8BM49AURSDO55", "boost": 3.0 } } }]));
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "identifiers": { "value": "529900T8BM49AURSDO55", "boost": 3.0 } } }]));
+
+    let entity = SearchEntity::builder("Company").properties(&[("leiCode", &["529900T8BM49AURSDO55"])]).build();
+
+    let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert_json_eq!(shoulds, json!([{ "term": { "identifiers": { "value": "529900T8BM49AURSDO55", "boost": 3.0 } } }]));
+  }
+
+  #[test]
+  fn build_should_referent_id() {
+    let entity = SearchEntity::builder("Person").id("ofac-35096").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert_json_contains!(container: shoulds, contained: json!([{ "ids": { "values": ["ofac-35096"], "boost": 10.0 } }]));
+    assert_json_contains!(container: shoulds, contained: json!([{ "term": { "referents": { "value": "ofac-35096", "boost": 10.0 } } }]));
+
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert!(shoulds.iter().all(|clause| clause.get("ids").is_none() && clause.pointer("/term/referents").is_none()));
+  }
+
+  #[test]
+  fn build_should_phonetic_algorithm() {
+    use crate::matching::PhoneticAlgorithm;
+
+    let entity = SearchEntity::builder("Person")
+      .properties(&[("name", &["Vladimir Putin"])])
+      .phonetic_algorithm(PhoneticAlgorithm::Soundex)
+      .build();
+
+    let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert_json_contains!(
+      container: shoulds,
+      contained: json!([{ "term": { "name_phonetic": { "boost": 0.8, "value": "V435" } } }]),
+    );
+
+    assert_json_contains!(
+      container: shoulds,
+      contained: json!([{ "term": { "name_phonetic": { "boost": 0.8, "value": "P350" } } }]),
+    );
+
+    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5, None, &HashMap::default()).unwrap();
+
+    assert!(serde_json::to_string(&shoulds).unwrap().contains(r#""value":"P350""#));
+  }
+
   #[test]
   fn build_should_v5() {
     let entity = SearchEntity::builder("Person")
@@ -745,7 +1266,7 @@ mod tests {
       ])
       .build();
 
-    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5).unwrap();
+    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5, None, &HashMap::default()).unwrap();
 
     assert_json_contains!(
         container: shoulds,
@@ -818,7 +1339,7 @@ mod tests {
   #[test]
   fn build_should_v5_org() {
     let entity = SearchEntity::builder("Company").properties(&[("name", &["Coca-Cola France Inc."])]).build();
-    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5).unwrap();
+    let shoulds = super::build_shoulds(IndexVersion::V5, &entity, 5, None, &HashMap::default()).unwrap();
 
     assert_json_contains!(
         container: shoulds,
@@ -872,6 +1393,47 @@ mod tests {
     assert_json_eq!(datasets[0], json!({ "terms": { "datasets": ["realdataset"] } }));
   }
 
+  #[tokio::test]
+  async fn canonical_datasets() {
+    let catalog = fake_catalog();
+
+    let params = MatchParams {
+      scope: "MYSCOPE".to_string(),
+      include_dataset: vec!["RealDataset".to_string(), "fakedataset".to_string()],
+      exclude_dataset: vec!["OTHERDATASET".to_string()],
+      ..Default::default()
+    };
+
+    let params = super::canonical_datasets(&catalog, &params).await;
+
+    assert_eq!(params.scope, "myscope");
+    assert_eq!(params.include_dataset, vec!["realdataset", "fakedataset"]);
+    assert_eq!(params.exclude_dataset, vec!["otherdataset"]);
+
+    let params = MatchParams {
+      scope: "myscope".to_string(),
+      ..Default::default()
+    };
+
+    assert!(matches!(super::canonical_datasets(&catalog, &params).await, Cow::Borrowed(_)));
+
+    let params = MatchParams {
+      scope: "MyScope".to_string(),
+      ..Default::default()
+    };
+    let query = super::build_query(
+      &catalog,
+      IndexVersion::V4,
+      "yente-entities",
+      &SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build(),
+      &params,
+    )
+    .await
+    .unwrap();
+
+    assert_json_contains!(container: query["query"]["bool"]["filter"], contained: json!([{ "terms": { "datasets": ["realdataset"] } }]));
+  }
+
   #[tokio::test]
   async fn build_datasets_bare() {
     let catalog = fake_catalog();
@@ -920,6 +1482,32 @@ mod tests {
     assert_json_include!(actual: filters, expected: json!([{}, {}, { "range": { "last_change": { "gt": "1970-01-01T00:00:00Z" } } }]));
   }
 
+  #[test]
+  fn minimum_should_match() {
+    fn name_parts_should_match(name: &str, ratio: Option<f64>) -> Option<u64> {
+      let entity = SearchEntity::builder("Person").properties(&[("name", &[name]), ("weakAlias", &["Abdul"])]).build();
+      let shoulds = super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap();
+      let shoulds = super::group_name_parts(shoulds, ratio);
+
+      shoulds.iter().find_map(|clause| clause.pointer("/bool/minimum_should_match")).and_then(serde_json::Value::as_u64)
+    }
+
+    assert_eq!(name_parts_should_match("Abdul Rahman Ahmed Karim", None), None);
+    assert_eq!(name_parts_should_match("Abdul Rahman Ahmed Karim", Some(0.5)), Some(2));
+    assert_eq!(name_parts_should_match("Abdul Rahman Ahmed Karim", Some(0.6)), Some(3));
+    assert_eq!(name_parts_should_match("Abdul Rahman Ahmed Karim", Some(1.0)), Some(4));
+    assert_eq!(name_parts_should_match("Abdul Rahman Ahmed Karim", Some(0.0)), Some(1));
+    assert_eq!(name_parts_should_match("Putin", Some(0.5)), Some(1));
+
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Abdul Rahman Ahmed Karim"])]).build();
+    let shoulds = super::group_name_parts(super::build_shoulds(IndexVersion::V4, &entity, 5, None, &HashMap::default()).unwrap(), Some(0.5));
+
+    assert_json_include!(
+      actual: shoulds.last().unwrap(),
+      expected: json!({ "bool": { "should": [{ "term": { "name_parts": {} } }, {}, {}, {}], "minimum_should_match": 2 } })
+    );
+  }
+
   #[test]
   fn add_term() {
     let mut terms = Vec::new();
@@ -948,6 +1536,227 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn search_uses_requested_search_type() {
+    use elasticsearch::{
+      Elasticsearch,
+      http::{
+        Url,
+        transport::{SingleNodeConnectionPool, TransportBuilder},
+      },
+    };
+    use wiremock::{
+      Mock, MockServer, ResponseTemplate,
+      matchers::{method, path, query_param},
+    };
+
+    use crate::{SearchType, index::IndexProvider, index::elastic::IndexState, prelude::ElasticsearchProvider};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+      .and(path("/yente-entities/_search"))
+      .and(query_param("search_type", "query_then_fetch"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "took": 1, "hits": { "total": { "value": 0 }, "hits": [] } })))
+      .expect(1)
+      .mount(&server)
+      .await;
+
+    let url = Url::parse(&server.uri()).unwrap();
+    let transport = TransportBuilder::new(SingleNodeConnectionPool::new(url)).build().unwrap();
+
+    let provider = ElasticsearchProvider {
+      es: Elasticsearch::new(transport),
+      index_prefix: "yente".to_string(),
+      main_index: "yente-entities".to_string(),
+      state: Arc::new(std::sync::RwLock::new(IndexState {
+        ready: true,
+        index_version: IndexVersion::V4,
+        scoped_index: None,
+      })),
+    };
+
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let params = MatchParams {
+      search_type: Some(SearchType::QueryThenFetch),
+      ..Default::default()
+    };
+
+    let hits = provider.search(&fake_catalog(), &entity, &params).await.unwrap();
+
+    assert!(hits.is_empty());
+  }
+
+  async fn opensearch_fixture_provider(route: (&str, &str), fixture: &str, status: u16) -> (wiremock::MockServer, crate::ElasticsearchProvider) {
+    use elasticsearch::{
+      Elasticsearch,
+      http::{
+        Url,
+        transport::{SingleNodeConnectionPool, TransportBuilder},
+      },
+    };
+    use wiremock::{
+      Mock, MockServer, ResponseTemplate,
+      matchers::{method, path},
+    };
+
+    use crate::{index::elastic::IndexState, prelude::ElasticsearchProvider};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method(route.0))
+      .and(path(route.1))
+      .respond_with(ResponseTemplate::new(status).set_body_raw(fixture.to_string(), "application/json"))
+      .mount(&server)
+      .await;
+
+    let url = Url::parse(&server.uri()).unwrap();
+    let transport = TransportBuilder::new(SingleNodeConnectionPool::new(url)).build().unwrap();
+
+    let provider = ElasticsearchProvider {
+      es: Elasticsearch::new(transport),
+      index_prefix: "yente".to_string(),
+      main_index: "yente-entities".to_string(),
+      state: Arc::new(std::sync::RwLock::new(IndexState {
+        ready: true,
+        index_version: IndexVersion::V4,
+        scoped_index: None,
+      })),
+    };
+
+    (server, provider)
+  }
+
+  #[tokio::test]
+  async fn search_returns_matched_queries() {
+    use crate::index::IndexProvider;
+
+    let response = json!({
+      "took": 1,
+      "hits": {
+        "total": { "value": 1 },
+        "hits": [{
+          "_id": "Q7747",
+          "_source": { "caption": "Vladimir Putin", "schema": "Person", "datasets": [], "referents": [], "properties": { "name": ["Vladimir Putin"] } },
+          "matched_queries": ["names", "name_phonetic", "names"]
+        }]
+      }
+    });
+
+    let (server, provider) = opensearch_fixture_provider(("POST", "/yente-entities/_search"), &response.to_string(), 200).await;
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"]), ("country", &["ru"])]).build();
+    let params = MatchParams { explain: true, ..Default::default() };
+
+    let hits = provider.search(&fake_catalog(), &entity, &params).await.unwrap();
+
+    assert_eq!(hits[0].matched_queries, vec!["name_phonetic", "names"]);
+
+    let requests = server.received_requests().await.unwrap();
+    let body = requests[0].body_json::<serde_json::Value>().unwrap();
+    let shoulds = body["query"]["bool"]["should"].as_array().unwrap();
+
+    assert!(shoulds.iter().any(|clause| clause["match"]["names"]["_name"] == "names"));
+    assert!(shoulds.iter().any(|clause| clause["term"]["name_phonetic"]["_name"] == "name_phonetic"));
+    assert!(shoulds.iter().any(|clause| clause["term"]["countries"]["_name"] == "countries"));
+
+    let hits = provider.search(&fake_catalog(), &entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits[0].matched_queries, vec!["name_phonetic", "names"]);
+
+    let requests = server.received_requests().await.unwrap();
+    let body = requests[1].body_json::<serde_json::Value>().unwrap();
+
+    assert!(!body["query"].to_string().contains("_name"));
+  }
+
+  #[tokio::test]
+  async fn count_uses_search_filters() {
+    use crate::index::IndexProvider;
+
+    let (server, provider) = opensearch_fixture_provider(("POST", "/yente-entities/_count"), r#"{ "count": 42 }"#, 200).await;
+    let params = MatchParams {
+      exclude_schema: vec!["Vessel".to_string()],
+      ..Default::default()
+    };
+
+    let count = provider.count(&fake_catalog(), "myscope", &params).await.unwrap();
+
+    assert_eq!(count, 42);
+
+    let requests = server.received_requests().await.unwrap();
+    let body = requests[0].body_json::<serde_json::Value>().unwrap();
+
+    assert_json_eq!(
+      body,
+      json!({
+        "query": {
+          "bool": {
+            "filter": [{ "terms": { "datasets": ["realdataset"] } }],
+            "must": [],
+            "must_not": [{ "terms": { "schema": ["Vessel"] } }],
+          }
+        }
+      })
+    );
+  }
+
+  #[test]
+  fn name_queries() {
+    let mut shoulds = vec![
+      json!({ "ids": { "values": ["Q7747"], "boost": 10.0 } }),
+      json!({ "match": { "addresses": "Moscow" } }),
+      json!({ "dis_max": { "queries": [{ "term": { "name_parts": { "value": "putin", "boost": 1.0 } } }], "tie_breaker": 0.2 } }),
+    ];
+
+    super::name_queries(&mut shoulds);
+
+    assert_json_eq!(
+      shoulds,
+      json!([
+        { "ids": { "values": ["Q7747"], "boost": 10.0, "_name": "ids" } },
+        { "match": { "addresses": { "query": "Moscow", "_name": "addresses" } } },
+        { "dis_max": { "queries": [{ "term": { "name_parts": { "value": "putin", "boost": 1.0, "_name": "name_parts" } } }], "tie_breaker": 0.2 } },
+      ])
+    );
+  }
+
+  #[tokio::test]
+  async fn opensearch_search_response() {
+    use crate::index::IndexProvider;
+
+    let (_server, provider) = opensearch_fixture_provider(("POST", "/yente-entities/_search"), include_str!("fixtures/opensearch/search.json"), 200).await;
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let hits = provider.search(&fake_catalog(), &entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, "Q7747");
+    assert_eq!(hits[0].caption, "Vladimir Putin");
+    assert_eq!(hits[0].referents, vec!["ofac-35096"]);
+    assert_eq!(hits[0].index_score, Some(42.17));
+  }
+
+  #[tokio::test]
+  async fn opensearch_error_response() {
+    use crate::index::IndexProvider;
+
+    let (_server, provider) = opensearch_fixture_provider(("POST", "/yente-entities/_search"), include_str!("fixtures/opensearch/error.json"), 403).await;
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let error = provider.search(&fake_catalog(), &entity, &MatchParams::default()).await.unwrap_err();
+
+    assert!(error.to_string().starts_with("no permissions for [indices:data/read/search]"));
+  }
+
+  #[tokio::test]
+  async fn opensearch_health_response() {
+    use crate::index::IndexProvider;
+
+    let (_server, provider) = opensearch_fixture_provider(("GET", "/_cluster/health/yente-entities"), include_str!("fixtures/opensearch/health.json"), 200).await;
+
+    assert!(provider.health().await.unwrap());
+  }
+
   #[tokio::test]
   async fn queries_are_unavailable_when_not_ready() {
     use crate::{
@@ -0,0 +1,209 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use ahash::RandomState;
+use tokio::sync::RwLock;
+
+use crate::{
+  Catalog,
+  error::MotivaError,
+  index::{EntityHandle, IndexProvider, elastic::config::IndexVersion},
+  matching::MatchParams,
+  model::{Entity, SearchEntity},
+};
+
+/// Index provider reading from a primary index, and falling back to a
+/// secondary one when the primary returns an error.
+///
+/// This is meant to be used with two replicated clusters. The index version
+/// is the one of the primary provider, so both clusters are expected to run
+/// the same version of the indexer.
+#[derive(Clone)]
+pub struct FailoverIndexProvider<P: IndexProvider, S: IndexProvider> {
+  primary: P,
+  secondary: S,
+}
+
+impl<P: IndexProvider, S: IndexProvider> FailoverIndexProvider<P, S> {
+  pub fn new(primary: P, secondary: S) -> Self {
+    Self { primary, secondary }
+  }
+}
+
+/// Run an operation on the primary provider, and on the secondary provider if
+/// it failed.
+///
+/// The secondary future is only awaited when falling back.
+async fn failover<T>(operation: &'static str, primary: impl Future<Output = Result<T, MotivaError>>, secondary: impl Future<Output = Result<T, MotivaError>>) -> Result<T, MotivaError> {
+  match primary.await {
+    Ok(result) => Ok(result),
+
+    // A missing entity is a valid answer, not a failure of the index.
+    Err(MotivaError::ResourceNotFound) => Err(MotivaError::ResourceNotFound),
+
+    Err(err) => {
+      tracing::warn!(operation, error = err.to_string(), "primary index failed, falling back to secondary index");
+
+      secondary.await
+    }
+  }
+}
+
+impl<P: IndexProvider, S: IndexProvider> IndexProvider for FailoverIndexProvider<P, S> {
+  fn after_init(&self) {
+    self.primary.after_init();
+    self.secondary.after_init();
+  }
+
+  fn ready(&self) -> bool {
+    self.primary.ready() || self.secondary.ready()
+  }
+
+  async fn refresh(&self) {
+    self.primary.refresh().await;
+    self.secondary.refresh().await;
+  }
+
+  fn index_version(&self) -> IndexVersion {
+    self.primary.index_version()
+  }
+
+  /// Whether at least one of the providers is healthy.
+  async fn health(&self) -> Result<bool, MotivaError> {
+    let primary = self.primary.health().await;
+
+    if let Ok(true) = primary {
+      return Ok(true);
+    }
+
+    match (primary, self.secondary.health().await) {
+      (Ok(true), _) | (_, Ok(true)) => Ok(true),
+      (Ok(false), _) | (_, Ok(false)) => Ok(false),
+      (Err(err), Err(_)) => Err(err),
+    }
+  }
+
+  async fn get_entity(&self, id: &str) -> Result<EntityHandle, MotivaError> {
+    failover("get_entity", self.primary.get_entity(id), self.secondary.get_entity(id)).await
+  }
+
+  async fn get_related_entities(&self, root: Option<&String>, values: &[String], negatives: &HashSet<String, RandomState>, limit: usize) -> Result<Vec<Entity>, MotivaError> {
+    failover(
+      "get_related_entities",
+      self.primary.get_related_entities(root, values, negatives, limit),
+      self.secondary.get_related_entities(root, values, negatives, limit),
+    )
+    .await
+  }
+
+  async fn search(&self, catalog: &Arc<RwLock<Catalog>>, entity: &SearchEntity, params: &MatchParams) -> Result<Vec<Entity>, MotivaError> {
+    failover("search", self.primary.search(catalog, entity, params), self.secondary.search(catalog, entity, params)).await
+  }
+
+  async fn count(&self, catalog: &Arc<RwLock<Catalog>>, scope: &str, params: &MatchParams) -> Result<u64, MotivaError> {
+    failover("count", self.primary.count(catalog, scope, params), self.secondary.count(catalog, scope, params)).await
+  }
+
+  async fn list_indices(&self) -> Result<Vec<(String, String)>, MotivaError> {
+    failover("list_indices", self.primary.list_indices(), self.secondary.list_indices()).await
+  }
+
+  async fn list_field_values(&self, fields: &[&str], query: Option<serde_json::Value>) -> Result<HashMap<String, Vec<String>>, MotivaError> {
+    failover(
+      "list_field_values",
+      self.primary.list_field_values(fields, query.clone()),
+      self.secondary.list_field_values(fields, query),
+    )
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use tokio::sync::RwLock;
+
+  use crate::{Catalog, Entity, EntityHandle, IndexProvider, MatchParams, MockedElasticsearch, SearchEntity, index::failover::FailoverIndexProvider};
+
+  #[tokio::test]
+  async fn failover_search() {
+    let primary = MockedElasticsearch::builder().failing(true).entities(vec![Entity::builder("Person").id("primary").build()]).build();
+    let secondary = MockedElasticsearch::builder().entities(vec![Entity::builder("Person").id("secondary").build()]).build();
+
+    let index = FailoverIndexProvider::new(primary.clone(), secondary.clone());
+    let catalog = Arc::new(RwLock::new(Catalog::default()));
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["John Doe"])]).build();
+
+    let hits = index.search(&catalog, &entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, "secondary");
+    assert_eq!(primary.calls().search.len(), 1);
+    assert_eq!(secondary.calls().search.len(), 1);
+  }
+
+  #[tokio::test]
+  async fn failover_primary_healthy() {
+    let primary = MockedElasticsearch::builder().entities(vec![Entity::builder("Person").id("primary").build()]).build();
+    let secondary = MockedElasticsearch::builder().failing(true).build();
+
+    let index = FailoverIndexProvider::new(primary, secondary.clone());
+    let catalog = Arc::new(RwLock::new(Catalog::default()));
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["John Doe"])]).build();
+
+    let hits = index.search(&catalog, &entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits[0].id, "primary");
+    assert!(secondary.calls().search.is_empty());
+  }
+
+  #[tokio::test]
+  async fn failover_get_entity() {
+    let primary = MockedElasticsearch::builder().failing(true).build();
+    let secondary = MockedElasticsearch::builder().entity(EntityHandle::Referent("Q7747".to_string())).build();
+
+    let index = FailoverIndexProvider::new(primary, secondary);
+
+    assert!(matches!(index.get_entity("Q7747").await, Ok(EntityHandle::Referent(id)) if id == "Q7747"));
+
+    let primary = MockedElasticsearch::default();
+    let secondary = MockedElasticsearch::builder().entity(EntityHandle::Referent("Q7747".to_string())).build();
+
+    let index = FailoverIndexProvider::new(primary, secondary);
+
+    assert!(index.get_entity("Q7747").await.is_err());
+  }
+
+  #[tokio::test]
+  async fn failover_health() {
+    let index = FailoverIndexProvider::new(MockedElasticsearch::default(), MockedElasticsearch::builder().healthy(true).build());
+
+    assert!(index.health().await.unwrap());
+
+    let index = FailoverIndexProvider::new(MockedElasticsearch::builder().healthy(true).build(), MockedElasticsearch::builder().healthy(false).build());
+
+    assert!(index.health().await.unwrap());
+
+    let index = FailoverIndexProvider::new(MockedElasticsearch::builder().healthy(false).build(), MockedElasticsearch::default());
+
+    assert!(!index.health().await.unwrap());
+
+    let index = FailoverIndexProvider::new(MockedElasticsearch::default(), MockedElasticsearch::default());
+
+    assert!(index.health().await.is_err());
+  }
+
+  #[tokio::test]
+  async fn failover_ready() {
+    let index = FailoverIndexProvider::new(MockedElasticsearch::builder().ready(false).build(), MockedElasticsearch::builder().ready(true).build());
+
+    assert!(index.ready());
+
+    let index = FailoverIndexProvider::new(MockedElasticsearch::builder().ready(false).build(), MockedElasticsearch::builder().ready(false).build());
+
+    assert!(!index.ready());
+  }
+}
@@ -1,6 +1,7 @@
 use std::{
   collections::{HashMap, HashSet},
-  sync::Arc,
+  sync::{Arc, Mutex},
+  time::Duration,
 };
 
 use ahash::RandomState;
@@ -22,6 +23,11 @@ pub struct MockedElasticsearch {
   healthy: Option<bool>,
   ready: Option<bool>,
   indexing_done: Option<bool>,
+  /// Make every query to the index return an error.
+  #[builder(default)]
+  failing: bool,
+  /// Delay every search by this duration, to simulate a slow index.
+  delay: Option<Duration>,
 
   #[builder(default)]
   entities: Vec<Entity>,
@@ -30,6 +36,35 @@ pub struct MockedElasticsearch {
   indices: Vec<(String, String)>,
   #[builder(default)]
   related_entitites: Vec<((Option<String>, Vec<String>, HashSet<String>), Vec<Entity>)>,
+
+  /// Calls made to the mock, shared between its clones.
+  #[builder(skip)]
+  calls: Arc<Mutex<MockedCalls>>,
+}
+
+/// Root, entity IDs, negative IDs and limit of a related entities lookup.
+type RelatedEntitiesCall = (Option<String>, Vec<String>, HashSet<String>, usize);
+
+/// Arguments the mocked index was called with, in call order.
+#[doc(hidden)]
+#[derive(Clone, Debug, Default)]
+pub struct MockedCalls {
+  pub search: Vec<(SearchEntity, MatchParams)>,
+  pub get_related_entities: Vec<RelatedEntitiesCall>,
+}
+
+impl MockedElasticsearch {
+  /// Return the calls recorded so far.
+  pub fn calls(&self) -> MockedCalls {
+    self.calls.lock().unwrap().clone()
+  }
+
+  fn check_failing(&self) -> Result<(), MotivaError> {
+    match self.failing {
+      true => Err(MotivaError::OtherError(anyhow::anyhow!("index query failed"))),
+      false => Ok(()),
+    }
+  }
 }
 
 impl IndexProvider for MockedElasticsearch {
@@ -48,20 +83,69 @@ impl IndexProvider for MockedElasticsearch {
     }
   }
 
-  async fn search(&self, _: &Arc<RwLock<Catalog>>, _: &SearchEntity, _: &MatchParams) -> Result<Vec<Entity>, MotivaError> {
-    Ok(self.entities.clone())
+  /// Return the configured entities, truncated to the number of candidates a
+  /// real index would be asked for. Like the index query, `filter_schemas`
+  /// is honored before truncating.
+  async fn search(&self, _: &Arc<RwLock<Catalog>>, entity: &SearchEntity, params: &MatchParams) -> Result<Vec<Entity>, MotivaError> {
+    self.calls.lock().unwrap().search.push((entity.clone(), params.clone()));
+    self.check_failing()?;
+
+    if let Some(delay) = self.delay {
+      tokio::time::sleep(delay).await;
+    }
+
+    Ok(
+      self
+        .entities
+        .iter()
+        .filter(|hit| !params.filter_schemas || entity.can_match(&hit.schema))
+        .take(params.candidate_limit(params.match_candidates))
+        .cloned()
+        .collect(),
+    )
+  }
+
+  /// Count the configured entities honoring the dataset, schema and ID
+  /// filters. Dataset filters are applied as-is, without resolving the scope.
+  async fn count(&self, _: &Arc<RwLock<Catalog>>, _scope: &str, params: &MatchParams) -> Result<u64, MotivaError> {
+    self.check_failing()?;
+
+    let count = self
+      .entities
+      .iter()
+      .filter(|entity| params.include_dataset.is_empty() || entity.datasets.iter().any(|dataset| params.include_dataset.contains(dataset)))
+      .filter(|entity| !entity.datasets.iter().any(|dataset| params.exclude_dataset.contains(dataset)))
+      .filter(|entity| !params.exclude_schema.iter().any(|schema| entity.schema.as_str() == schema))
+      .filter(|entity| !params.exclude_entity_ids.contains(&entity.id))
+      .count();
+
+    Ok(count as u64)
   }
 
-  async fn get_entity(&self, _: &str) -> Result<EntityHandle, MotivaError> {
+  async fn get_entity(&self, id: &str) -> Result<EntityHandle, MotivaError> {
+    self.check_failing()?;
+
     match &self.entity {
       Some(entity) => Ok(entity.clone()),
-      None => Err(MotivaError::ResourceNotFound),
+      None => match self.entities.iter().find(|entity| entity.id == id) {
+        Some(entity) => Ok(EntityHandle::Nominal(Box::new(entity.clone()))),
+        None => Err(MotivaError::ResourceNotFound),
+      },
     }
   }
 
-  async fn get_related_entities(&self, root: Option<&String>, ids: &[String], negatives: &HashSet<String, RandomState>, _limit: usize) -> Result<Vec<Entity>, MotivaError> {
+  async fn get_related_entities(&self, root: Option<&String>, ids: &[String], negatives: &HashSet<String, RandomState>, limit: usize) -> Result<Vec<Entity>, MotivaError> {
     let negatives = HashSet::from_iter(negatives.iter().map(|id| id.to_owned()));
 
+    self
+      .calls
+      .lock()
+      .unwrap()
+      .get_related_entities
+      .push((root.map(|id| id.to_owned()), ids.to_vec(), negatives.clone(), limit));
+
+    self.check_failing()?;
+
     for (args, entities) in &self.related_entitites {
       if args == &(root.map(|id| id.to_owned()), ids.to_vec(), negatives.to_owned()) {
         return Ok(entities.clone());
@@ -72,6 +156,8 @@ impl IndexProvider for MockedElasticsearch {
   }
 
   async fn list_indices(&self) -> Result<Vec<(String, String)>, MotivaError> {
+    self.check_failing()?;
+
     if !self.indexing_done.unwrap_or(true) {
       return Err(MotivaError::OtherError(anyhow::anyhow!("indexing is not done")));
     }
@@ -83,3 +169,79 @@ impl IndexProvider for MockedElasticsearch {
     unimplemented!()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use tokio::sync::RwLock;
+
+  use crate::{Catalog, Entity, IndexProvider, MatchParams, MockedElasticsearch, SearchEntity};
+
+  #[tokio::test]
+  async fn search_honors_candidate_limit() {
+    let entities = (0..30).map(|i| Entity::builder("Person").id(&format!("person-{i}")).build()).collect::<Vec<_>>();
+    let index = MockedElasticsearch::builder().entities(entities).build();
+    let catalog = Arc::new(RwLock::new(Catalog::default()));
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["John Doe"])]).build();
+
+    let hits = index.search(&catalog, &entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits.len(), 20);
+    assert_eq!(hits[0].id, "person-0");
+    assert_eq!(hits[19].id, "person-19");
+
+    let params = MatchParams {
+      limit: 5,
+      candidate_factor: 5,
+      ..Default::default()
+    };
+
+    let hits = index.search(&catalog, &entity, &params).await.unwrap();
+
+    assert_eq!(hits.len(), 25);
+
+    let params = MatchParams {
+      match_candidates: 100,
+      ..Default::default()
+    };
+
+    let hits = index.search(&catalog, &entity, &params).await.unwrap();
+
+    assert_eq!(hits.len(), 30);
+  }
+
+  #[tokio::test]
+  async fn search_calls_are_recorded() {
+    use crate::{Motiva, MotivaConfig, SearchType};
+
+    let index = MockedElasticsearch::default();
+    let config = MotivaConfig {
+      search_type: SearchType::QueryThenFetch,
+      ..Default::default()
+    };
+
+    let motiva = Motiva::test(index.clone()).config(config).build().await.unwrap();
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["John Doe"])]).build();
+
+    let params = MatchParams {
+      limit: 3,
+      exclude_entity_ids: vec!["Q7747".to_string()],
+      ..Default::default()
+    };
+
+    motiva.search(&entity, &params).await.unwrap();
+
+    let calls = index.calls();
+
+    assert_eq!(calls.search.len(), 1);
+    assert!(calls.get_related_entities.is_empty());
+
+    let (query, params) = &calls.search[0];
+
+    assert_eq!(query.schema.as_str(), "Person");
+    assert_eq!(params.limit, 3);
+    assert_eq!(params.exclude_entity_ids, vec!["Q7747"]);
+    assert_eq!(params.search_type, Some(SearchType::QueryThenFetch));
+  }
+}
@@ -1,4 +1,5 @@
 pub mod elastic;
+pub mod failover;
 pub mod mock;
 
 use std::{
@@ -34,6 +35,7 @@ pub trait IndexProvider: Clone + Send + Sync + 'static {
   fn get_entity(&self, id: &str) -> impl Future<Output = Result<EntityHandle, MotivaError>> + Send;
   fn get_related_entities(&self, root: Option<&String>, values: &[String], negatives: &HashSet<String, RandomState>, limit: usize) -> impl Future<Output = Result<Vec<Entity>, MotivaError>> + Send;
   fn search(&self, catalog: &Arc<RwLock<Catalog>>, entity: &SearchEntity, params: &MatchParams) -> impl Future<Output = Result<Vec<Entity>, MotivaError>> + Send;
+  fn count(&self, catalog: &Arc<RwLock<Catalog>>, scope: &str, params: &MatchParams) -> impl Future<Output = Result<u64, MotivaError>> + Send;
   fn list_indices(&self) -> impl Future<Output = Result<Vec<(String, String)>, MotivaError>> + Send;
 
   fn list_field_values(&self, fields: &[&str], query: Option<serde_json::Value>) -> impl Future<Output = Result<HashMap<String, Vec<String>>, MotivaError>> + Send;
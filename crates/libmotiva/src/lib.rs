@@ -21,6 +21,7 @@ pub(crate) fn init() {
   let _ = *crate::matching::replacers::company_types::ORG_TYPES;
   let _ = *crate::matching::replacers::addresses::ADDRESS_FORMS;
   let _ = *crate::matching::replacers::ordinals::ORDINALS;
+  let _ = *crate::matching::replacers::ordinals::GENERATIONAL_SUFFIXES;
 
   let _ = *crate::symbols::tagger::ORG_TAGGER;
   let _ = *crate::symbols::tagger::PERSON_TAGGER;
@@ -28,27 +29,39 @@ pub(crate) fn init() {
 
 /// Module including most features needed to use the library.
 pub mod prelude {
-  pub use crate::catalog::{Catalog, CatalogDataset};
-  pub use crate::fetcher::{CatalogFetcher, HttpCatalogFetcher};
-  pub use crate::motiva::{GetEntityBehavior, GetEntityLimits, Motiva, MotivaConfig};
+  pub use crate::catalog::{Catalog, CatalogDataset, CatalogDiff, DatasetVersionChange};
+  pub use crate::fetcher::{CatalogFetcher, FetchError, HttpCatalogFetcher};
+  pub use crate::motiva::{EdgeExpansion, GetEntityBehavior, GetEntityLimits, Motiva, MotivaConfig, SchemaDefaults};
 
   pub use crate::error::MotivaError;
   pub use crate::index::{
     EntityHandle, IndexProvider,
-    elastic::{ElasticsearchProvider, builder::EsAuthMethod, builder::EsTlsVerification, config::EsOptions, scoped::create_scoped_index},
+    elastic::{ElasticsearchProvider, builder::EsAuthMethod, builder::EsTlsVerification, config::EsFlavor, config::EsOptions, scoped::create_scoped_index},
+    failover::FailoverIndexProvider,
   };
-  pub use crate::matching::{Algorithm, Feature, MatchParams, MatchingAlgorithm, logic_v1::LogicV1, marble_v0::MarbleV0, name_based::NameBased, name_qualified::NameQualified};
-  pub use crate::model::{Entity, HasProperties, SearchEntity, format_score};
+  pub use crate::matching::intermediates::{NameAlignment, NameIntermediates, ScoreIntermediates, score_intermediates};
+  pub use crate::matching::reasons::{match_reasons, provenance_reason};
+  pub use crate::matching::registry::{DynMatchingAlgorithm, ScoringAlgorithm};
+  pub use crate::matching::{
+    Algorithm, CaptionPreference, Detail, Explanation, Feature, FeaturesConfig, IndexType, MAPPED_PROPERTY_TYPES, MatchParams, MatchingAlgorithm, NameReplacer, NameReplacers, PhoneticAlgorithm,
+    QueryNormalization, ScoreResult, SearchType, composite::FeatureAlgorithm, is_match, logic_v1::LogicV1, marble_v0::MarbleV0, name_based::NameBased, name_qualified::NameQualified, run_features,
+  };
+  pub use crate::model::{Entity, HasProperties, SCHEMA_HINT, SearchEntity, format_score, round_score};
   pub use crate::scoring::ScoringOptions;
 }
 
 #[doc(inline)]
 pub use self::prelude::*;
 
+#[doc(hidden)]
+pub use crate::catalog::Manifest;
 #[doc(hidden)]
 pub use crate::fetcher::TestFetcher;
 #[doc(hidden)]
 pub use crate::index::mock::MockedElasticsearch;
+#[doc(hidden)]
+#[cfg(feature = "benchmarks")]
+pub use crate::tests::fixtures;
 
 #[cfg(test)]
 mod testing {
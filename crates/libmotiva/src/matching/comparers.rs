@@ -4,6 +4,8 @@ use ahash::{HashMap, HashSet, RandomState};
 use itertools::Itertools;
 use strsim::{jaro_winkler, levenshtein};
 
+use crate::matching::replacers::ordinals::is_generational_suffix;
+
 #[inline]
 pub(crate) fn is_disjoint<'s, S>(lhs: &[S], rhs: &[S]) -> bool
 where
@@ -95,7 +97,13 @@ pub(crate) fn levenshtein_similarity(lhs: &str, rhs: &str, max_edits: usize) ->
   1.0 - (distance / lhs.len().max(rhs.len()) as f64)
 }
 
-pub(crate) fn align_name_parts<'s, S>(query: &[S], result: &[S]) -> f64
+/// Align the parts of two names, pairing each query part with its most similar
+/// result part.
+///
+/// Generational suffixes are only dropped from both sides when
+/// `ignore_generational_suffixes` is set, since nomenklatura aligns them as any
+/// other part.
+pub(crate) fn align_name_parts<'s, S>(query: &'s [S], result: &'s [S], ignore_generational_suffixes: bool) -> f64
 where
   S: Borrow<str> + 's,
 {
@@ -103,8 +111,13 @@ where
     return 0.0;
   }
 
-  let mut query_counts = count_parts(query);
-  let mut result_counts = count_parts(result);
+  let (query, result) = match ignore_generational_suffixes {
+    true => (strip_generational_suffixes(query), strip_generational_suffixes(result)),
+    false => (query.iter().map(|part| part.borrow()).collect(), result.iter().map(|part| part.borrow()).collect()),
+  };
+
+  let mut query_counts = count_parts(&query);
+  let mut result_counts = count_parts(&result);
 
   let mut scores = query_counts
     .iter()
@@ -151,6 +164,17 @@ where
   final_score
 }
 
+/// Drop generational suffixes (e.g. `jr` or `iii`) from name parts, so they
+/// do not prevent aligning the core of the names, unless nothing else is left.
+fn strip_generational_suffixes<'s, S: Borrow<str> + 's>(parts: &'s [S]) -> Vec<&'s str> {
+  let core = parts.iter().map(|part| part.borrow()).filter(|part| !is_generational_suffix(part)).collect::<Vec<_>>();
+
+  match core.is_empty() {
+    true => parts.iter().map(|part| part.borrow()).collect(),
+    false => core,
+  }
+}
+
 #[inline(always)]
 fn count_parts<'s, S: Borrow<str> + 's>(parts: &'s [S]) -> Vec<(&'s str, usize)> {
   let mut map: HashMap<&str, usize> = HashMap::with_capacity_and_hasher(parts.len(), RandomState::default());
@@ -187,6 +211,20 @@ mod tests {
     assert!(counts.contains(&("c", 2)));
   }
 
+  #[test]
+  fn align_name_parts_generational_suffixes() {
+    assert_eq!(super::align_name_parts(&["john", "smith", "jr"], &["john", "smith"], true), 1.0);
+    assert_eq!(super::align_name_parts(&["john", "smith"], &["smith", "john", "sr"], true), 1.0);
+    assert_eq!(super::align_name_parts(&["john", "smith", "ii"], &["john", "smith"], true), 1.0);
+    assert_eq!(super::align_name_parts(&["john", "smith", "iii"], &["john", "smith", "jr"], true), 1.0);
+    assert_eq!(super::align_name_parts(&["jr"], &["jr"], true), 1.0);
+
+    assert_eq!(super::align_name_parts(&["john", "smith", "jones"], &["john", "smith"], true), 0.0);
+
+    assert_eq!(super::align_name_parts(&["john", "smith", "jr"], &["john", "smith"], false), 0.0);
+    assert_eq!(super::align_name_parts(&["john", "smith", "ii"], &["john", "smith"], false), 0.0);
+  }
+
   #[test]
   fn is_levenshtein_plausible() {
     assert!(super::is_levenshtein_plausible("Martin", "Jardin"));
@@ -201,10 +239,13 @@ mod tests {
     let data: &[(&[&str], &[&str])] = &[
       (&["vladimir", "putin"], &["vladimir", "vladimirovich", "putin"]),
       (&["mohamed", "laha"], &["khalil", "ibrahim", "mohamed", "achar", "foudail", "taha"]),
+      (&["john", "smith", "jr"], &["john", "smith"]),
+      (&["john", "smith"], &["smith", "john", "sr"]),
+      (&["john", "smith", "iii"], &["john", "smith", "jr"]),
     ];
 
     for (lhs, rhs) in data {
-      let score = super::align_name_parts(lhs, rhs);
+      let score = super::align_name_parts(lhs, rhs, false);
       let nscore = nomenklatura_str_list("compare.names", "_align_name_parts", lhs, rhs).unwrap();
 
       assert_approx_eq!(f64, score, nscore, epsilon = 0.01);
@@ -0,0 +1,147 @@
+use bumpalo::Bump;
+
+use crate::{
+  matching::{Explanation, Feature, FeaturesConfig, registry::DynMatchingAlgorithm, run_features},
+  model::{Entity, SearchEntity},
+  scoring::ScoringOptions,
+};
+
+/// Matching algorithm assembled from a list of weighted [`Feature`]s.
+///
+/// This allows library users to compose their own features into an algorithm
+/// without implementing the scoring loop themselves. The score is the sum of
+/// the weighted feature scores, clamped between 0 and 1, and weights can be
+/// overridden per query, by feature name, like those of built-in algorithms.
+///
+/// ```rust
+/// # use bumpalo::Bump;
+/// # use libmotiva::prelude::*;
+/// struct SameSchema;
+///
+/// impl Feature for SameSchema {
+///   fn name(&self) -> &'static str {
+///     "same_schema"
+///   }
+///
+///   fn score(&self, _: &Bump, lhs: &SearchEntity, rhs: &Entity, _: bool) -> ScoreResult {
+///     ((lhs.schema.as_str() == rhs.schema.as_str()) as u8 as f64).into()
+///   }
+/// }
+///
+/// let algorithm = FeatureAlgorithm::new("my-algorithm").feature(SameSchema, 0.2);
+/// ```
+pub struct FeatureAlgorithm {
+  name: String,
+  features: Vec<(Box<dyn Feature>, f64)>,
+}
+
+impl FeatureAlgorithm {
+  pub fn new(name: &str) -> Self {
+    FeatureAlgorithm {
+      name: name.to_string(),
+      features: Vec::new(),
+    }
+  }
+
+  /// Add a feature to the algorithm, with its default weight.
+  ///
+  /// Negative weights can be used to penalize a candidate. Features are run in
+  /// the order they were added.
+  pub fn feature(mut self, feature: impl Feature + 'static, weight: f64) -> Self {
+    self.features.push((Box::new(feature), weight));
+    self
+  }
+}
+
+impl DynMatchingAlgorithm for FeatureAlgorithm {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, options: &ScoringOptions) -> (f64, Vec<Explanation>) {
+    let features = self.features.iter().map(|(feature, weight)| (feature.as_ref(), *weight)).collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(features.len());
+    let score = run_features(bump, lhs, rhs, 0.0, FeaturesConfig::summed_features(&features, options), &mut results);
+
+    (score.clamp(0.0, 1.0), results)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use bumpalo::Bump;
+  use float_cmp::approx_eq;
+
+  use crate::{
+    DynMatchingAlgorithm, Feature, MockedElasticsearch, Motiva, ScoringOptions,
+    matching::{ScoreResult, composite::FeatureAlgorithm},
+    model::{Entity, SearchEntity},
+  };
+
+  struct SameSchema;
+
+  impl Feature for SameSchema {
+    fn name(&self) -> &'static str {
+      "same_schema"
+    }
+
+    fn score(&self, _: &Bump, lhs: &SearchEntity, rhs: &Entity, _: bool) -> ScoreResult {
+      match lhs.schema.as_str() == rhs.schema.as_str() {
+        true => 1.0.into(),
+        false => 0.0.into(),
+      }
+    }
+  }
+
+  struct Penalty;
+
+  impl Feature for Penalty {
+    fn name(&self) -> &'static str {
+      "penalty"
+    }
+
+    fn score(&self, _: &Bump, _: &SearchEntity, _: &Entity, _: bool) -> ScoreResult {
+      1.0.into()
+    }
+  }
+
+  #[test]
+  fn composed_features() {
+    let algorithm = FeatureAlgorithm::new("composed").feature(SameSchema, 0.8).feature(Penalty, -0.3);
+
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let (score, explanations) = algorithm.score(&Bump::new(), &lhs, &rhs, &Default::default());
+
+    assert!(approx_eq!(f64, score, 0.5));
+    assert_eq!(explanations.iter().map(|e| (e.name, e.weighted)).collect::<Vec<_>>(), vec![("same_schema", 0.8), ("penalty", -0.3)]);
+
+    let options = ScoringOptions {
+      weights: HashMap::from([("penalty".to_string(), 0.0)]),
+      ..Default::default()
+    };
+
+    let (score, _) = algorithm.score(&Bump::new(), &lhs, &rhs, &options);
+
+    assert!(approx_eq!(f64, score, 0.8));
+  }
+
+  #[tokio::test]
+  async fn composed_features_registered() {
+    let motiva = Motiva::test(MockedElasticsearch::default()).build().await.unwrap();
+
+    motiva.register_algorithm(FeatureAlgorithm::new("composed").feature(SameSchema, 0.8));
+
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let hits = vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()];
+
+    let results = motiva.score_algorithm(&motiva.custom_algorithm("composed").unwrap(), &entity, hits, &Default::default()).unwrap();
+
+    assert!(approx_eq!(f64, results[0].1, 0.8));
+    assert_eq!(results[0].0.features, vec![("same_schema", 1.0)]);
+  }
+}
@@ -1,15 +1,24 @@
-use std::{borrow::Borrow, sync::LazyLock};
+use std::{
+  borrow::{Borrow, Cow},
+  sync::LazyLock,
+};
 
 use any_ascii::any_ascii;
 use itertools::Itertools;
 use regex::Regex;
-use rphonetic::{Encoder, Metaphone};
+use rphonetic::{DoubleMetaphone, Encoder, Metaphone, Soundex};
 use unicode_general_category::{GeneralCategory, get_general_category};
+use unicode_normalization::UnicodeNormalization;
 use whatlang::Script;
 
-use crate::matching::latinize::latinize;
+use crate::{
+  matching::{PhoneticAlgorithm, latinize::latinize},
+  model::{HasProperties, PropertyFilter},
+};
 
 static METAPHONE: LazyLock<Metaphone> = LazyLock::new(|| Metaphone::new(None));
+static DOUBLE_METAPHONE: LazyLock<DoubleMetaphone> = LazyLock::new(DoubleMetaphone::default);
+static SOUNDEX: LazyLock<Soundex> = LazyLock::new(Soundex::default);
 
 const SEPARATOR_CATEGORIES: &[GeneralCategory] = {
   use GeneralCategory::*;
@@ -41,7 +50,7 @@ fn is_name_separator(c: char) -> bool {
   SEPARATOR_CATEGORIES.iter().contains(&get_general_category(c))
 }
 
-fn is_modern_alphabet(input: &str) -> bool {
+pub(crate) fn is_modern_alphabet(input: &str) -> bool {
   let Some(info) = whatlang::detect(input) else {
     return true;
   };
@@ -65,6 +74,24 @@ where
   })
 }
 
+/// Strip zero-width and control characters, and collapse runs of whitespace
+/// (including non-breaking spaces) into a single space.
+pub(crate) fn normalize_whitespace(input: &str) -> String {
+  input
+    .chars()
+    .filter(|c| c.is_whitespace() || !matches!(get_general_category(*c), GeneralCategory::Format | GeneralCategory::Control))
+    .collect::<String>()
+    .split_whitespace()
+    .join(" ")
+}
+
+/// Normalization applied to names both when building index queries and when
+/// precomputing scoring artifacts, so both sides derive their tokens from the
+/// same string.
+pub(crate) fn normalize_name(name: &str) -> String {
+  normalize_whitespace(name).nfc().collect()
+}
+
 #[inline(always)]
 pub(crate) fn clean_names<'s, I, S>(names: I) -> impl Iterator<Item = String> + Clone
 where
@@ -73,13 +100,14 @@ where
 {
   names
     .map(|s| {
-      latinize(s.borrow())
+      latinize(&normalize_whitespace(s.borrow()))
         .to_lowercase()
         .chars()
         .filter(|c| !is_ignored_separator(*c))
         .join("")
         .split(is_name_separator)
         .map(|s| s.chars().filter(|c| c.is_alphanumeric() || c.is_whitespace()).collect::<String>())
+        .filter(|s| !s.is_empty())
         .join(" ")
     })
     .unique()
@@ -93,13 +121,14 @@ where
 {
   names
     .map(|s| {
-      s.borrow()
+      normalize_whitespace(s.borrow())
         .to_lowercase()
         .chars()
         .filter(|c| !is_ignored_separator(*c))
         .join("")
         .split(is_name_separator)
         .map(|s| s.chars().filter(|c| c.is_alphanumeric() || c.is_whitespace()).collect::<String>())
+        .filter(|s| !s.is_empty())
         .join(" ")
     })
     .unique()
@@ -161,17 +190,29 @@ where
     .unique()
 }
 
-pub(crate) fn phonetic_name<'s, I, S>(names: I) -> impl Iterator<Item = String>
+pub(crate) fn phonetic_encode(algorithm: PhoneticAlgorithm, value: &str) -> String {
+  match algorithm {
+    PhoneticAlgorithm::Metaphone => METAPHONE.encode(value),
+    PhoneticAlgorithm::DoubleMetaphone => DOUBLE_METAPHONE.encode(value),
+    PhoneticAlgorithm::Soundex => SOUNDEX.encode(value),
+  }
+}
+
+pub(crate) fn phonetic_name<'s, I, S>(names: I, algorithm: PhoneticAlgorithm) -> impl Iterator<Item = String>
 where
   S: Borrow<str> + 's,
   I: Iterator<Item = &'s S> + 's,
 {
   tokenize_names(names)
-    .flat_map(|s| s.into_iter().filter(|s| is_modern_alphabet(s) && s.chars().count() >= 3).map(|s| METAPHONE.encode(&any_ascii(&s))))
+    .flat_map(move |s| {
+      s.into_iter()
+        .filter(|s| is_modern_alphabet(s) && s.chars().count() >= 3)
+        .map(move |s| phonetic_encode(algorithm, &any_ascii(&s)))
+    })
     .filter(|phoneme| phoneme.len() > 2)
 }
 
-pub(crate) fn phonetic_names_tuples<'s, I, S>(names: I) -> Vec<Vec<(String, Option<String>)>>
+pub(crate) fn phonetic_names_tuples<'s, I, S>(names: I, algorithm: PhoneticAlgorithm) -> Vec<Vec<(String, Option<String>)>>
 where
   S: Borrow<str> + 's,
   I: Iterator<Item = &'s S> + 's,
@@ -181,7 +222,7 @@ where
       s.into_iter()
         .filter(|name| name.len() >= 2)
         .map(|s| {
-          let phoneme = METAPHONE.encode(&s);
+          let phoneme = phonetic_encode(algorithm, &s);
 
           (s, { if phoneme.len() < 3 { None } else { Some(phoneme) } })
         })
@@ -269,6 +310,88 @@ where
   haystack.flat_map(|value| NUMBERS_REGEX.find_iter(value.borrow()).map(|number| number.as_str()))
 }
 
+/// Multilingual gender terms and their canonical FtM value.
+const GENDERS: &[(&str, &[&str])] = &[
+  (
+    "male",
+    &[
+      "male",
+      "m",
+      "man",
+      "masculine",
+      "männlich",
+      "maennlich",
+      "mann",
+      "herr",
+      "homme",
+      "masculin",
+      "hombre",
+      "masculino",
+      "maschile",
+      "uomo",
+      "мужской",
+      "муж",
+      "м",
+      "mężczyzna",
+      "męski",
+    ],
+  ),
+  (
+    "female",
+    &[
+      "female",
+      "f",
+      "woman",
+      "feminine",
+      "weiblich",
+      "w",
+      "frau",
+      "femme",
+      "féminin",
+      "mujer",
+      "femenino",
+      "femminile",
+      "donna",
+      "женский",
+      "жен",
+      "ж",
+      "kobieta",
+      "żeński",
+    ],
+  ),
+  ("other", &["other", "diverse", "divers", "d", "x", "non-binary", "nonbinary"]),
+];
+
+/// Map gender values to their canonical form (`male`, `female` or `other`).
+///
+/// Unknown values are kept, lowercased, so they can still be compared.
+pub(crate) fn normalize_genders<'s, I, S>(genders: I) -> impl Iterator<Item = String>
+where
+  S: Borrow<str> + 's,
+  I: Iterator<Item = &'s S> + 's,
+{
+  genders
+    .map(|gender| gender.borrow().trim().to_lowercase())
+    .filter(|gender| !gender.is_empty())
+    .map(|gender| match GENDERS.iter().find(|(_, terms)| terms.contains(&gender.as_str())) {
+      Some((canonical, _)) => canonical.to_string(),
+      None => gender,
+    })
+    .unique()
+}
+
+/// Normalized genders of an entity, see [`normalize_genders`].
+pub(crate) fn genders(entity: &dyn HasProperties) -> Cow<'_, [String]> {
+  Cow::Owned(normalize_genders(entity.props(&["gender"]).iter()).collect())
+}
+
+/// Normalized values of all matchable identifier properties of an entity, see [`normalize_identifiers`].
+///
+/// Gathering the whole group lets an identifier match across fields (e.g. `registrationNumber` against `taxNumber`).
+pub(crate) fn identifiers(entity: &dyn HasProperties) -> Cow<'_, [String]> {
+  Cow::Owned(normalize_identifiers(entity.prop_group("identifier", PropertyFilter::Matchable).iter()).collect())
+}
+
 #[cfg(test)]
 mod tests {
   use std::collections::HashSet;
@@ -308,6 +431,17 @@ mod tests {
     assert_eq!(super::clean_names(["Владимир Владимирович Путин"].iter()).collect::<Vec<_>>(), vec!["vladimir vladimirovich putin"]);
   }
 
+  #[test]
+  fn clean_names_whitespace() {
+    assert_eq!(super::normalize_whitespace(" Vladimir\u{a0}\u{a0}\tPutin\u{200b} "), "Vladimir Putin");
+    assert_eq!(super::normalize_whitespace("Vla\u{200b}di\u{200d}mir\u{feff}"), "Vladimir");
+
+    assert_eq!(super::clean_names(["Vladimir\u{a0}Putin"].iter()).collect::<Vec<_>>(), vec!["vladimir putin"]);
+    assert_eq!(super::clean_names(["Vla\u{200b}dimir   Pu\u{200d}tin"].iter()).collect::<Vec<_>>(), vec!["vladimir putin"]);
+    assert_eq!(super::clean_names(["Jean - Paul"].iter()).collect::<Vec<_>>(), vec!["jean paul"]);
+    assert_eq!(super::clean_names_light(["Vladimir\u{a0}\u{200b} Putin"].iter()).collect::<Vec<_>>(), vec!["vladimir putin"]);
+  }
+
   #[test]
   fn clean_names_light() {
     assert_eq!(super::clean_names_light(["Vladimir Putin Jr."].iter()).collect::<Vec<_>>(), vec!["vladimir putin jr"]);
@@ -338,7 +472,7 @@ mod tests {
 
   #[test]
   fn phonetic_name() {
-    let names = super::phonetic_name(["Vladimir Putin", "Saddam Hussein", "Barack Hussein Obama"].iter()).collect::<Vec<_>>();
+    let names = super::phonetic_name(["Vladimir Putin", "Saddam Hussein", "Barack Hussein Obama"].iter(), Default::default()).collect::<Vec<_>>();
 
     assert_eq!(names, vec!["FLTMR", "PTN", "STM", "HSN", "BRK", "HSN", "OBM"]);
   }
@@ -362,4 +496,14 @@ mod tests {
       HashSet::from_iter(["vladimir", "vladimorovich", "putin", "barack", "hussein", "obama"].into_iter().map(str::to_string))
     );
   }
+
+  #[test]
+  fn normalize_genders() {
+    let genders = |values: &[&str]| super::normalize_genders(values.iter()).collect::<Vec<_>>();
+
+    assert_eq!(genders(&["Male", "female", "Other"]), vec!["male", "female", "other"]);
+    assert_eq!(genders(&["männlich", "Weiblich", "divers"]), vec!["male", "female", "other"]);
+    assert_eq!(genders(&["M", "f", " W "]), vec!["male", "female"]);
+    assert_eq!(genders(&["Unknown", ""]), vec!["unknown"]);
+  }
 }
@@ -0,0 +1,93 @@
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+  matching::{extractors, matchers::jaro_winkler::align_jaro_name_parts},
+  model::{Entity, HasProperties, PropertyFilter, SearchEntity},
+};
+
+/// Intermediate values computed by the name features while scoring a result.
+///
+/// This is meant to debug score differences (with Yente, for example), and is
+/// fairly verbose.
+#[derive(Debug, Serialize)]
+pub struct ScoreIntermediates {
+  pub query: NameIntermediates,
+  pub result: NameIntermediates,
+  /// Best matching result name part for each query name part, as aligned by
+  /// the `jaro_name_parts` feature.
+  pub alignments: Vec<NameAlignment>,
+}
+
+/// Names of one side of the comparison, as seen by the name features.
+#[derive(Debug, Serialize)]
+pub struct NameIntermediates {
+  /// Cleaned full names.
+  pub names: Vec<String>,
+  /// Cleaned name parts.
+  pub tokens: Vec<String>,
+  /// Name parts along with their phonetic code, if long enough to have one.
+  pub phonemes: Vec<(String, Option<String>)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NameAlignment {
+  pub query: String,
+  pub result: String,
+  /// Jaro-Winkler similarity between both parts.
+  pub similarity: f64,
+}
+
+/// Compute the intermediate values used by the name features for a pair.
+pub fn score_intermediates(lhs: &SearchEntity, rhs: &Entity) -> ScoreIntermediates {
+  let rhs_names = rhs.prop_group("name", PropertyFilter::All);
+
+  let query = NameIntermediates {
+    names: lhs.clean_names.clone(),
+    tokens: lhs.name_parts_flat.iter().sorted().cloned().collect(),
+    phonemes: lhs.phonetic_names.iter().flatten().cloned().collect(),
+  };
+
+  let result_names = extractors::clean_names(rhs_names.iter()).collect::<Vec<_>>();
+
+  let result = NameIntermediates {
+    phonemes: extractors::phonetic_names_tuples(result_names.iter(), lhs.phonetic_algorithm).into_iter().flatten().collect(),
+    tokens: extractors::name_parts_flat(rhs_names.iter()).unique().sorted().collect(),
+    names: result_names,
+  };
+
+  let alignments = align_jaro_name_parts(&query.tokens, &result.tokens)
+    .filter_map(|(part, other, similarity)| {
+      other.map(|other| NameAlignment {
+        query: part.to_string(),
+        result: other.to_string(),
+        similarity,
+      })
+    })
+    .collect();
+
+  ScoreIntermediates { query, result, alignments }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::model::{Entity, SearchEntity};
+
+  #[test]
+  fn score_intermediates() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["PUTIN, Vladimir Vladimirovich"])]).build();
+
+    let intermediates = super::score_intermediates(&lhs, &rhs);
+
+    assert_eq!(intermediates.query.names, vec!["vladimir putin"]);
+    assert_eq!(intermediates.query.tokens, vec!["putin", "vladimir"]);
+    assert_eq!(intermediates.result.tokens, vec!["putin", "vladimir", "vladimirovich"]);
+    assert!(intermediates.query.phonemes.contains(&("putin".to_string(), Some("PTN".to_string()))));
+
+    assert_eq!(intermediates.alignments.len(), 2);
+    assert_eq!(intermediates.alignments[0].query, "putin");
+    assert_eq!(intermediates.alignments[0].result, "putin");
+    assert_eq!(intermediates.alignments[0].similarity, 1.0);
+  }
+}
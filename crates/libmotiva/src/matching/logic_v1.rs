@@ -5,18 +5,28 @@ use tracing::instrument;
 
 use crate::{
   matching::{
-    Explanation, Feature, FeaturesConfig, MatchingAlgorithm,
+    Detail, Explanation, Feature, FeaturesConfig, MatchingAlgorithm, extractors,
     matchers::{
+      acronym::{AcronymMatch, OrgInitialsMatch},
       address::AddressEntityMatch,
+      birth_place::BirthPlaceMatch,
+      caption::CaptionExactMatch,
       crypto_wallet::CryptoWalletMatch,
       identifier::IdentifierMatch,
       jaro_winkler::PersonNameJaroWinkler,
+      jurisdiction::JurisdictionMatch,
       match_::{SimpleMatch, WeakAliasMatch},
-      mismatch::{NumbersMismatch, SimpleMismatch, dob_day_disjoint, dob_year_disjoint},
+      mismatch::{NumbersMismatch, SimpleMismatch, StatusMismatch, dob_day_disjoint, dob_year_disjoint},
       name_fingerprint_levenshtein::NameFingerprintLevenshtein,
       name_literal_match::NameLiteralMatch,
+      nationality::NationalityMatch,
+      org_dates::{IncorporationDateMatch, OrgDatesDisjoint},
       orgid_mismatch::OrgIdMismatch,
+      phone::PhoneMatch,
       phonetic::PersonNamePhoneticMatch,
+      vessel::VesselAttributesMatch,
+      weak_corroboration::WeakCorroboration,
+      wikidata::WikidataIdMatch,
     },
     run_features,
     validators::{validate_bic, validate_imo_mmsi, validate_inn, validate_isin, validate_ogrn},
@@ -30,7 +40,9 @@ pub struct LogicV1;
 
 static FEATURES: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new(|| {
   vec![
-    (&NameLiteralMatch, 1.0),
+    (NameLiteralMatch::EXACT, 1.0),
+    (NameLiteralMatch::CONTAINMENT, 0.0), // Motiva-specific, disabled by default
+    (&CaptionExactMatch, 0.95),           // Motiva-specific
     (&PersonNameJaroWinkler, 0.8),
     (&PersonNamePhoneticMatch, 0.9),
     (&NameFingerprintLevenshtein, 0.9),
@@ -48,21 +60,37 @@ static FEATURES: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new(|| {
     (IdentifierMatch::new("vessel_imo_mmsi_match", &["imoNumber", "mmsi"], Some(validate_imo_mmsi)), 0.95),
     (IdentifierMatch::new("inn_code_match", &["innCode"], Some(validate_inn)), 0.95),
     (IdentifierMatch::new("bic_code_match", &["bicCode"], Some(validate_bic)), 0.95),
-    (SimpleMatch::new("identifier_match", &|e| e.prop_group("identifier", PropertyFilter::Matchable)), 0.85), // TODO: add cleaning
+    // Motiva-specific, disabled by default: tolerate OCR confusions on numeric identifiers
+    (IdentifierMatch::fuzzy("vessel_imo_mmsi_fuzzy_match", &["imoNumber", "mmsi"], Some(validate_imo_mmsi)), 0.0),
+    (IdentifierMatch::fuzzy("inn_code_fuzzy_match", &["innCode"], Some(validate_inn)), 0.0),
+    (IdentifierMatch::fuzzy("ogrn_code_fuzzy_match", &["ogrnCode"], Some(validate_ogrn)), 0.0),
+    (SimpleMatch::new("identifier_match", &|e| extractors::identifiers(e)), 0.85),
+    (&WikidataIdMatch, 0.0), // Motiva-specific, disabled by default
+    (&PhoneMatch, 0.0),      // Motiva-specific, disabled by default
     (&WeakAliasMatch, 0.8),
+    (&AcronymMatch, 0.0),     // Motiva-specific, disabled by default
+    (&OrgInitialsMatch, 0.0), // Motiva-specific, disabled by default
   ]
 });
 
 static QUALIFIERS: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new(|| {
   vec![
+    (&BirthPlaceMatch, 0.1),        // Motiva-specific
+    (&NationalityMatch, 0.0),       // Motiva-specific, disabled by default
+    (&JurisdictionMatch, 0.05),     // Motiva-specific
+    (&IncorporationDateMatch, 0.0), // Motiva-specific, disabled by default
+    (&WeakCorroboration, 0.0),      // Motiva-specific, disabled by default
+    (&VesselAttributesMatch, 0.0),  // Motiva-specific, disabled by default
     (SimpleMismatch::new("country_mismatch", &|e| e.prop_group("country", PropertyFilter::Matchable), None), -0.2),
     (SimpleMismatch::new("last_name_mismatch", &|e| e.props(&["lastName"]), None), -0.2),
     (SimpleMismatch::new("dob_year_disjoint", &|e| e.props(&["birthDate"]), Some(dob_year_disjoint)), -0.15),
     (SimpleMismatch::new("dob_day_disjoint", &|e| e.props(&["birthDate"]), Some(dob_day_disjoint)), -0.2),
-    (SimpleMismatch::new("gender_mismatch", &|e| e.props(&["gender"]), None), -0.2),
+    (SimpleMismatch::new("gender_mismatch", &|e| extractors::genders(e), None), -0.2),
     (SimpleMismatch::new("identifier_mismatch", &|e| e.prop_group("identifier", PropertyFilter::Matchable), None), 0.0), // Motiva-specific, disabled by default
     (&OrgIdMismatch, -0.2),
+    (&OrgDatesDisjoint, 0.0), // Motiva-specific, disabled by default
     (&NumbersMismatch, -0.1),
+    (&StatusMismatch, 0.0), // Motiva-specific, disabled by default
   ]
 });
 
@@ -75,7 +103,7 @@ pub(crate) fn logic_v1(
   qualifiers: &[(&'static dyn Feature, f64)],
   disqualifiers: &[(&'static dyn Feature, f64)],
 ) -> (f64, Vec<Explanation>) {
-  if !rhs.schema.can_match(lhs.schema.as_str()) {
+  if !lhs.can_match(&rhs.schema) {
     return (0.0, vec![]);
   }
 
@@ -83,11 +111,42 @@ pub(crate) fn logic_v1(
 
   let score = run_features(bump, lhs, rhs, 0.0, FeaturesConfig::highest_features(features, options), &mut results);
   let score = run_features(bump, lhs, rhs, score, FeaturesConfig::summed_features(qualifiers, options), &mut results);
+
+  let start = results.len();
   let score = run_features(bump, lhs, rhs, score, FeaturesConfig::disqualifiers(disqualifiers, options), &mut results);
+  let score = escalate_mismatches(score, options, &mut results, start);
 
   (score.clamp(0.0, 1.0), results)
 }
 
+/// Apply an additional penalty when enough disqualifiers, starting at `start`
+/// in `results`, reported a mismatch.
+fn escalate_mismatches(score: f64, options: &ScoringOptions, results: &mut Vec<Explanation>, start: usize) -> f64 {
+  let Some(threshold) = options.escalate_mismatches.filter(|n| *n > 0) else {
+    return score;
+  };
+
+  let (count, penalty) = results[start..]
+    .iter()
+    .filter(|e| e.weighted < 0.0)
+    .fold((0, 0.0), |(count, penalty), e| (count + 1, penalty + e.weighted));
+
+  if count < threshold {
+    return score;
+  }
+
+  let factor = (count - threshold + 1) as f64;
+
+  results.push(Explanation {
+    name: "mismatch_escalation",
+    score: factor,
+    weighted: penalty * factor,
+    detail: Detail::Note("several mismatches escalated"),
+  });
+
+  score + penalty * factor
+}
+
 impl MatchingAlgorithm for LogicV1 {
   fn name() -> &'static str {
     "logic-v1"
@@ -154,6 +213,53 @@ mod tests {
     assert!(features.iter().any(|e| e.name == "ogrn_code_match" && e.score == 1.0));
   }
 
+  #[test]
+  fn logic_v1_identifier_cross_field() {
+    let lhs = SearchEntity::builder("Company").properties(&[("registrationNumber", &["12-345.678"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("taxNumber", &["12345678"])]).build();
+
+    let (_, features) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &ScoringOptions::new(0.0));
+
+    assert!(features.iter().any(|e| e.name == "identifier_match" && e.score == 1.0));
+
+    let rhs = Entity::builder("Company").properties(&[("taxNumber", &["87654321"])]).build();
+    let (_, features) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &ScoringOptions::new(0.0));
+
+    assert!(!features.iter().any(|e| e.name == "identifier_match" && e.score > 0.0));
+  }
+
+  #[test]
+  fn logic_v1_escalate_mismatches() {
+    let lhs = SearchEntity::builder("Person")
+      .properties(&[("name", &["Ivan Petrov"]), ("country", &["ru"]), ("gender", &["male"]), ("birthDate", &["1970-01-01"])])
+      .build();
+    let rhs = Entity::builder("Person")
+      .properties(&[("name", &["Ivan Petrov"]), ("country", &["bg"]), ("gender", &["female"]), ("birthDate", &["1970-05-12"])])
+      .build();
+
+    let (score, features) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &ScoringOptions::new(0.0));
+
+    assert!(score > 0.0);
+    assert!(!features.iter().any(|e| e.name == "mismatch_escalation"));
+
+    let options = ScoringOptions {
+      escalate_mismatches: Some(3),
+      ..Default::default()
+    };
+    let (score, features) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &options);
+
+    assert_eq!(score, 0.0);
+    assert!(features.iter().any(|e| e.name == "mismatch_escalation" && e.score == 1.0));
+
+    let rhs = Entity::builder("Person")
+      .properties(&[("name", &["Ivan Petrov"]), ("country", &["bg"]), ("gender", &["female"]), ("birthDate", &["1970-01-01"])])
+      .build();
+    let (escalated, _) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &options);
+    let (unescalated, _) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &ScoringOptions::new(0.0));
+
+    assert_eq!(escalated, unescalated);
+  }
+
   #[test]
   fn logic_v1_vessel() {
     let lhs = SearchEntity::builder("Vessel").properties(&[("mmsi", &["366123456"])]).build();
@@ -165,6 +271,19 @@ mod tests {
     assert!(features.iter().any(|e| e.name == "vessel_imo_mmsi_match" && e.score == 1.0));
   }
 
+  #[test]
+  fn logic_v1_birth_place() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Ivan Petrov"]), ("birthPlace", &["Omsk, Russia"])]).build();
+    let same = Entity::builder("Person").properties(&[("name", &["Ivan Petrov"]), ("birthPlace", &["Omsk"])]).build();
+    let other = Entity::builder("Person").properties(&[("name", &["Ivan Petrov"]), ("birthPlace", &["Sofia, Bulgaria"])]).build();
+
+    let (same_score, features) = super::LogicV1::score(&Bump::new(), &lhs, &same, &ScoringOptions::new(0.0));
+    let (other_score, _) = super::LogicV1::score(&Bump::new(), &lhs, &other, &ScoringOptions::new(0.0));
+
+    assert!(features.iter().any(|e| e.name == "birth_place_match" && e.score == 1.0));
+    assert!(same_score >= other_score);
+  }
+
   #[test]
   fn person_name_jaro_winkler() {
     let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
@@ -185,6 +304,19 @@ mod tests {
     assert!(approx_eq!(f64, super::PersonNamePhoneticMatch.score_scalar(&Bump::new(), &lhs, &rhs), 2.0 / 3.0));
   }
 
+  #[test]
+  fn alias_scores_like_name() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let by_name = Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let by_alias = Entity::builder("Person").properties(&[("name", &["Putin V. V."]), ("alias", &["Vladimir Putin"])]).build();
+
+    let (name_score, _) = super::LogicV1::score(&Bump::new(), &lhs, &by_name, &ScoringOptions::default());
+    let (alias_score, features) = super::LogicV1::score(&Bump::new(), &lhs, &by_alias, &ScoringOptions::default());
+
+    assert!(approx_eq!(f64, alias_score, name_score));
+    assert!(features.iter().any(|e| e.name == "name_literal_match" && e.score == 1.0));
+  }
+
   #[test]
   fn weighted() {
     let lhs = SearchEntity::builder("Person").properties(&[("name", &["Exact Match"])]).build();
@@ -195,7 +327,11 @@ mod tests {
     weights.insert("person_name_jaro_winkler".into(), 0.2);
     weights.insert("person_name_phonetic_match".into(), 0.2);
 
-    let options = ScoringOptions { weights, cutoff: 0.0, explain: false };
+    let options = ScoringOptions {
+      weights,
+      cutoff: 0.0,
+      ..Default::default()
+    };
     let (score, features) = super::LogicV1::score(&Bump::new(), &lhs, &rhs, &options);
 
     assert!(features.iter().any(|e| e.name == "name_literal_match" && e.score == 1.0));
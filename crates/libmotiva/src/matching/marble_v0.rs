@@ -5,7 +5,7 @@ use tracing::instrument;
 
 use crate::{
   matching::{
-    Explanation, Feature, MatchingAlgorithm,
+    Explanation, Feature, MatchingAlgorithm, extractors,
     logic_v1::logic_v1,
     matchers::{
       address::AddressEntityMatch,
@@ -32,7 +32,7 @@ pub struct MarbleV0;
 
 static FEATURES: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new(|| {
   vec![
-    (&NameLiteralMatch, 1.0),
+    (NameLiteralMatch::EXACT, 1.0),
     (&PersonNameJaroWinkler, 0.8),
     (&PersonNamePhoneticMatch, 0.9),
     (&NameFingerprintLevenshtein, 0.9),
@@ -51,7 +51,7 @@ static FEATURES: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new(|| {
     (IdentifierMatch::new("vessel_imo_mmsi_match", &["imoNumber", "mmsi"], Some(validate_imo_mmsi)), 0.95),
     (IdentifierMatch::new("inn_code_match", &["innCode"], Some(validate_inn)), 0.95),
     (IdentifierMatch::new("bic_code_match", &["bicCode"], Some(validate_bic)), 0.95),
-    (SimpleMatch::new("identifier_match", &|e| e.prop_group("identifier", PropertyFilter::Matchable)), 0.85), // TODO: add cleaning
+    (SimpleMatch::new("identifier_match", &|e| extractors::identifiers(e)), 0.85),
     (&WeakAliasMatch, 0.8),
   ]
 });
@@ -67,7 +67,7 @@ static DISQUALIFIERS: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new
   vec![
     (SimpleMismatch::new("country_mismatch", &|e| e.prop_group("country", PropertyFilter::All), None), -0.2),
     (SimpleMismatch::new("last_name_mismatch", &|e| e.props(&["lastName"]), None), -0.2),
-    (SimpleMismatch::new("gender_mismatch", &|e| e.props(&["gender"]), None), -0.2),
+    (SimpleMismatch::new("gender_mismatch", &|e| extractors::genders(e), None), -0.2),
     (SimpleMismatch::new("identifier_mismatch", &|e| e.prop_group("identifier", PropertyFilter::Matchable), None), -0.3),
     (&OrgIdMismatch, -0.2),
     (&NumbersMismatch, -0.1),
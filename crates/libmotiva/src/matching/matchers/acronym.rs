@@ -0,0 +1,188 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use compact_str::CompactString;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{
+    Detail, Feature, ScoreResult, extractors,
+    replacers::{self, company_types::ORG_TYPES},
+  },
+  model::{Entity, HasProperties, PropertyFilter, SearchEntity},
+};
+
+/// Connecting words that are usually left out of acronyms.
+const ACRONYM_STOPWORDS: &[&str] = &["a", "an", "and", "at", "de", "der", "des", "du", "for", "in", "la", "le", "of", "on", "the", "und"];
+
+/// Generate an acronym from the first letter of each significant word of a name.
+///
+/// Company types (e.g. "Corporation") and connecting words are ignored. No
+/// acronym is generated for names with fewer than two significant words.
+pub(crate) fn acronym(name: &str) -> Option<String> {
+  let name = replacers::remove(&ORG_TYPES.0, &name.to_lowercase());
+
+  let initials = name
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|word| !word.is_empty() && !ACRONYM_STOPWORDS.contains(word))
+    .filter_map(|word| word.chars().next())
+    .collect::<String>();
+
+  (initials.chars().count() >= 2).then_some(initials)
+}
+
+#[scoring_feature(AcronymMatch, name = "acronym_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  if lhs.schema.is_a("Person") || rhs.schema.is_a("Person") {
+    return (0.0, explain.then_some(Detail::Note("not an organization"))).into();
+  }
+
+  let lhs_names = lhs.prop_group("name", PropertyFilter::All);
+  let rhs_names = rhs.prop_group("name", PropertyFilter::All);
+
+  // Names compared against acronyms, with punctuation and spaces removed, so
+  // that "I.B.M." or "I B M" are considered acronyms as well.
+  let compact = |names: &[String]| {
+    extractors::clean_literal_names(names.iter())
+      .map(|name| name.chars().filter(|c| !c.is_whitespace()).collect::<String>())
+      .filter(|name| name.chars().count() >= 2)
+      .collect_in::<Vec<_>>(bump)
+  };
+
+  let (lhs_compact, rhs_compact) = (compact(lhs_names.as_ref()), compact(rhs_names.as_ref()));
+
+  let matched = lhs_names
+    .iter()
+    .filter_map(|name| acronym(name).map(|acronym| (name, acronym)))
+    .find(|(_, acronym)| rhs_compact.contains(acronym))
+    .or_else(|| {
+      rhs_names
+        .iter()
+        .filter_map(|name| acronym(name).map(|acronym| (name, acronym)))
+        .find(|(_, acronym)| lhs_compact.contains(acronym))
+    });
+
+  match matched {
+    Some((name, acronym)) => (1.0, explain.then(|| Detail::Equal(CompactString::from(name.as_str()), CompactString::from(acronym.to_uppercase())))).into(),
+    None => (0.0, explain.then_some(Detail::Note("no matching acronym"))).into(),
+  }
+}
+
+/// Highest score given to a query made only of initials, which are much more
+/// ambiguous than a full name.
+const INITIALS_MAX_SCORE: f64 = 0.7;
+
+/// Extract the initials from a name written only with initials (e.g. `GE`,
+/// `I.B.M.` or `I B M`), lowercased.
+pub(crate) fn initials(name: &str) -> Option<String> {
+  let letters = name.chars().filter(|c| !c.is_whitespace() && *c != '.').collect::<String>();
+  let count = letters.chars().count();
+
+  ((2..=6).contains(&count) && letters.chars().all(|c| c.is_alphabetic() && c.is_uppercase())).then(|| letters.to_lowercase())
+}
+
+#[scoring_feature(OrgInitialsMatch, name = "org_initials_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  if lhs.schema.is_a("Person") || rhs.schema.is_a("Person") {
+    return (0.0, explain.then_some(Detail::Note("not an organization"))).into();
+  }
+
+  let lhs_initials = lhs.prop_group("name", PropertyFilter::All).iter().filter_map(|name| initials(name)).collect_in::<Vec<_>>(bump);
+
+  if lhs_initials.is_empty() {
+    return (0.0, explain.then_some(Detail::Note("query is not made of initials"))).into();
+  }
+
+  let matched = rhs
+    .prop_group("name", PropertyFilter::All)
+    .iter()
+    .filter(|name| initials(name).is_none())
+    .find_map(|name| acronym(name).filter(|acronym| lhs_initials.contains(acronym)).map(|acronym| (name.clone(), acronym)));
+
+  match matched {
+    Some((name, acronym)) => (
+      INITIALS_MAX_SCORE,
+      explain.then(|| Detail::Equal(CompactString::from(acronym.to_uppercase()), CompactString::from(name))),
+    )
+      .into(),
+    None => (0.0, explain.then_some(Detail::Note("no name matching the initials"))).into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn acronym() {
+    assert_eq!(super::acronym("General Electric"), Some("ge".to_string()));
+    assert_eq!(super::acronym("International Business Machines"), Some("ibm".to_string()));
+    assert_eq!(super::acronym("International Business Machines Corporation"), Some("ibm".to_string()));
+    assert_eq!(super::acronym("Siemens"), None);
+  }
+
+  #[test]
+  fn acronym_match() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["IBM"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["International Business Machines Corporation"])]).build();
+
+    assert_eq!(super::AcronymMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["General Electric"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["G.E."])]).build();
+
+    assert_eq!(super::AcronymMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let rhs = Entity::builder("Company").properties(&[("name", &["General Motors"])]).build();
+
+    assert_eq!(super::AcronymMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn initials() {
+    assert_eq!(super::initials("GE").as_deref(), Some("ge"));
+    assert_eq!(super::initials("I.B.M.").as_deref(), Some("ibm"));
+    assert_eq!(super::initials("I B M").as_deref(), Some("ibm"));
+    assert_eq!(super::initials("Ge"), None);
+    assert_eq!(super::initials("G"), None);
+    assert_eq!(super::initials("GAZPROM NEFT"), None);
+  }
+
+  #[test]
+  fn org_initials_match() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["GE"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["General Electric Company"])]).build();
+
+    assert_eq!(super::OrgInitialsMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.7);
+    assert_eq!(super::OrgInitialsMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "GE == General Electric Company");
+
+    let rhs = Entity::builder("Company").properties(&[("name", &["General Motors Company"])]).build();
+
+    assert_eq!(super::OrgInitialsMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    // Only the query carrying initials is considered.
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["General Electric Company"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["GE"])]).build();
+
+    assert_eq!(super::OrgInitialsMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["GE"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["George Eliot"])]).build();
+
+    assert_eq!(super::OrgInitialsMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn acronym_match_persons() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["JD"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["John Doe"])]).build();
+
+    assert_eq!(super::AcronymMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+}
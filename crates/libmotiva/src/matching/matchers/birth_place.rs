@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use ahash::RandomState;
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use itertools::Itertools;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, extractors, matchers::NO_DATA},
+  model::{Entity, HasProperties, SearchEntity},
+};
+
+const BIRTH_PLACE_PROPERTIES: &[&str] = &["birthPlace", "placeOfBirth"];
+
+#[scoring_feature(BirthPlaceMatch, name = "birth_place_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let lhs_props = lhs.props(BIRTH_PLACE_PROPERTIES);
+  let rhs_props = rhs.props(BIRTH_PLACE_PROPERTIES);
+
+  let lhs_places = extractors::clean_address_parts(lhs_props.iter())
+    .map(|place| place.split_whitespace().map(str::to_string).collect::<HashSet<_, RandomState>>())
+    .filter(|tokens| !tokens.is_empty())
+    .collect_in::<Vec<_>>(bump);
+
+  let rhs_places = extractors::clean_address_parts(rhs_props.iter())
+    .map(|place| place.split_whitespace().map(str::to_string).collect::<HashSet<_, RandomState>>())
+    .filter(|tokens| !tokens.is_empty())
+    .collect_in::<Vec<_>>(bump);
+
+  if lhs_places.is_empty() || rhs_places.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  let mut max_score = 0.0f64;
+  let mut best_overlap: Option<std::vec::Vec<&String>> = None;
+
+  for (lhs, rhs) in lhs_places.iter().cartesian_product(rhs_places.iter()) {
+    let overlap = lhs.intersection(rhs).sorted().collect::<std::vec::Vec<_>>();
+
+    // A birth place is often more or less precise on either side ("Moscow" vs.
+    // "Moscow, Russia"), so we score the overlap against the shortest one.
+    let score = overlap.len() as f64 / lhs.len().min(rhs.len()) as f64;
+
+    if score > max_score {
+      max_score = score;
+      best_overlap = Some(overlap);
+    }
+  }
+
+  let detail = explain.then(|| match best_overlap {
+    Some(overlap) => Detail::Labeled("birth place overlap", overlap.iter().join(", ").into()),
+    None => Detail::Note("no birth place overlap"),
+  });
+
+  (max_score, detail).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+  use float_cmp::approx_eq;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn birth_place_match() {
+    let lhs = SearchEntity::builder("Person").properties(&[("birthPlace", &["Leningrad, USSR"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("birthPlace", &["leningrad"])]).build();
+
+    assert_eq!(super::BirthPlaceMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let lhs = SearchEntity::builder("Person").properties(&[("placeOfBirth", &["Saint Petersburg"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("birthPlace", &["Saint-Denis"])]).build();
+
+    assert!(approx_eq!(f64, super::BirthPlaceMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.5));
+  }
+
+  #[test]
+  fn birth_place_disjoint() {
+    let lhs = SearchEntity::builder("Person").properties(&[("birthPlace", &["Leningrad, USSR"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("birthPlace", &["Buenos Aires, Argentina"])]).build();
+
+    assert_eq!(super::BirthPlaceMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let rhs = Entity::builder("Person").properties(&[]).build();
+
+    assert_eq!(super::BirthPlaceMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn birth_place_match_details() {
+    fn detail(lhs: &str, rhs: &str) -> String {
+      let lhs = SearchEntity::builder("Person").properties(&[("birthPlace", &[lhs])]).build();
+      let rhs = Entity::builder("Person").properties(&[("birthPlace", &[rhs])]).build();
+
+      super::BirthPlaceMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string()
+    }
+
+    assert_eq!(detail("Moscow, Russia", "Moscow"), "birth place overlap: moscow");
+    assert_eq!(detail("Moscow", "Paris"), "no birth place overlap");
+  }
+}
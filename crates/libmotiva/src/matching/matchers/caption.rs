@@ -0,0 +1,74 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use compact_str::CompactString;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, extractors, matchers::NO_DATA},
+  model::{Entity, HasProperties, PropertyFilter, SearchEntity},
+};
+
+/// Whether one of the query's names, including those composed from name
+/// parts, is literally the candidate's caption once cleaned.
+///
+/// This does not depend on how name parts align, and is a strong signal on
+/// its own since the caption is the name the source deems canonical. The
+/// caption picked for display from the request's preference is not used, so
+/// that it cannot change the score.
+#[scoring_feature(CaptionExactMatch, name = "caption_exact_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let Some(caption) = extractors::clean_literal_names(std::iter::once(&rhs.scoring_caption())).find(|caption| !caption.trim().is_empty()) else {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  };
+
+  let lhs_names = extractors::clean_literal_names(lhs.prop_group("name", PropertyFilter::All).iter()).collect_in::<Vec<_>>(bump);
+
+  if let Some(name) = lhs_names.iter().find(|name| name.split_whitespace().eq(caption.split_whitespace())) {
+    return (1.0, explain.then(|| Detail::Equal(CompactString::from(name.as_str()), CompactString::from(rhs.scoring_caption())))).into();
+  }
+
+  (0.0, explain.then_some(Detail::Note("caption differs from query names"))).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  fn with_caption(caption: &str) -> Entity {
+    let mut entity = Entity::builder("Person").properties(&[("name", &["Putin Vladimir Vladimirovich"])]).build();
+
+    entity.caption = caption.to_string();
+    entity
+  }
+
+  #[test]
+  fn caption_exact_match() {
+    let lhs = SearchEntity::builder("Person").properties(&[("firstName", &["Vladimir"]), ("lastName", &["Putin"])]).build();
+
+    assert_eq!(super::CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &with_caption("Vladimir Putin")), 1.0);
+    assert_eq!(super::CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &with_caption("VLADIMIR  PUTIN.")), 1.0);
+
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    assert_eq!(
+      super::CaptionExactMatch.score(&Bump::new(), &lhs, &with_caption("Vladimir Putin"), true).1.unwrap().to_string(),
+      "vladimir putin == Vladimir Putin"
+    );
+  }
+
+  #[test]
+  fn caption_near_miss() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    assert_eq!(super::CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &with_caption("Vladimir Putin Jr")), 0.0);
+    assert_eq!(super::CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &with_caption("Vladimir Poutine")), 0.0);
+    assert_eq!(super::CaptionExactMatch.score_scalar(&Bump::new(), &lhs, &with_caption("")), 0.0);
+  }
+}
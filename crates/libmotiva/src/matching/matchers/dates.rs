@@ -56,7 +56,7 @@ impl DobUnit {
   }
 }
 
-struct DobMatch {
+pub(crate) struct DobMatch {
   lhs: CompactString,
   rhs: CompactString,
   unit: DobUnit,
@@ -65,7 +65,7 @@ struct DobMatch {
 }
 
 impl DobMatch {
-  fn into_detail(self) -> Detail {
+  pub(crate) fn into_detail(self) -> Detail {
     if self.diff == 0 && !self.swapped {
       return Detail::Equal(self.lhs, self.rhs);
     }
@@ -80,7 +80,7 @@ impl DobMatch {
   }
 }
 
-fn dob_progressive<S: AsRef<str>>(lhs: &[S], rhs: &[S], explain: bool) -> (f64, Option<DobMatch>) {
+pub(crate) fn dob_progressive<S: AsRef<str>>(lhs: &[S], rhs: &[S], explain: bool) -> (f64, Option<DobMatch>) {
   const YEAR_EPSILON: u64 = 1;
   const MONTH_EPSILON: i32 = 3;
   const DAY_THRESHOLDS: (i32, i32) = (1, 60);
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use bumpalo::{
   Bump,
   collections::{CollectIn, Vec},
@@ -12,15 +14,57 @@ use crate::{
   schemas::{FtmProperty, SCHEMAS},
 };
 
+/// How different two codes can be while still designating the same identifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum IdentifierTolerance {
+  /// Codes must be identical.
+  Exact,
+  /// Case, whitespace and punctuation are ignored, and characters commonly
+  /// confused by OCR are considered equal (e.g. `O` and `0`).
+  Ocr,
+}
+
 pub(crate) struct IdentifierMatch<'p> {
   name: &'static str,
   properties: &'p [&'p str],
   validator: Option<fn(&str) -> bool>,
+  tolerance: IdentifierTolerance,
 }
 
 impl<'p> IdentifierMatch<'p> {
   pub(crate) fn new(name: &'static str, properties: &'p [&'p str], validator: Option<fn(&str) -> bool>) -> &'static Self {
-    Box::leak(Box::new(Self { name, properties, validator }))
+    Self::with_tolerance(name, properties, validator, IdentifierTolerance::Exact)
+  }
+
+  /// Same as [`IdentifierMatch::new`], but tolerating characters commonly
+  /// confused by OCR (e.g. `O` and `0`). This raises false positives, so it
+  /// should only be used for numeric identifiers.
+  pub(crate) fn fuzzy(name: &'static str, properties: &'p [&'p str], validator: Option<fn(&str) -> bool>) -> &'static Self {
+    Self::with_tolerance(name, properties, validator, IdentifierTolerance::Ocr)
+  }
+
+  /// Build a feature comparing codes with the given tolerance.
+  ///
+  /// With a validator, tolerance only applies to codes that do not validate as
+  /// given: validators check checksums or strict formats, so a near-variant of
+  /// a valid code is likely another valid code, designating another entity.
+  pub(crate) fn with_tolerance(name: &'static str, properties: &'p [&'p str], validator: Option<fn(&str) -> bool>, tolerance: IdentifierTolerance) -> &'static Self {
+    Box::leak(Box::new(Self {
+      name,
+      properties,
+      validator,
+      tolerance,
+    }))
+  }
+
+  fn canonicalize<'c>(&self, code: &'c str) -> Cow<'c, str> {
+    if self.tolerance == IdentifierTolerance::Exact || self.validator.is_some_and(|validator| validator(code)) {
+      return Cow::Borrowed(code);
+    }
+
+    let code = code.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_uppercase();
+
+    Cow::Owned(canonicalize_ocr(&code))
   }
 
   fn match_property(&self, bump: &Bump, schema: &Schema, lhs: &impl HasProperties, rhs: &impl HasProperties, property: &str) -> Option<CompactString> {
@@ -31,7 +75,7 @@ impl<'p> IdentifierMatch<'p> {
     }
 
     if let Some(validator) = self.validator
-      && lhs_values.iter().any(|code| !(validator)(code))
+      && lhs_values.iter().any(|code| !(validator)(&self.canonicalize(code)))
     {
       return None;
     }
@@ -75,16 +119,40 @@ impl<'p> IdentifierMatch<'p> {
       .props(&properties)
       .into_owned()
       .into_iter()
+      .map(|code| self.canonicalize(&code).into_owned())
       .filter(|code| self.validator.map(|v| v(code)).unwrap_or(true))
       .collect_in::<Vec<_>>(bump);
 
     lhs_values
       .iter()
-      .find(|code| rhs_values.iter().any(|other| other == *code))
+      .find(|code| rhs_values.iter().any(|other| *other == self.canonicalize(code)))
       .map(|code| CompactString::from(code.as_str()))
   }
 }
 
+/// Replace characters OCR usually mistakes for digits with those digits.
+///
+/// A leading `IMO` prefix is kept as is, since IMO numbers are validated with
+/// it.
+fn canonicalize_ocr(code: &str) -> String {
+  let (prefix, code) = match code.get(..3) {
+    Some(prefix) if prefix.eq_ignore_ascii_case("IMO") => code.split_at(3),
+    _ => ("", code),
+  };
+
+  prefix
+    .chars()
+    .chain(code.chars().map(|c| match c.to_ascii_uppercase() {
+      'O' | 'Q' => '0',
+      'I' | 'L' => '1',
+      'Z' => '2',
+      'S' => '5',
+      'B' => '8',
+      other => other,
+    }))
+    .collect()
+}
+
 impl<'p> Feature for IdentifierMatch<'p> {
   fn name(&self) -> &'static str {
     self.name
@@ -110,7 +178,7 @@ mod tests {
   use bumpalo::Bump;
 
   use crate::{
-    matching::{Feature, matchers::identifier::IdentifierMatch},
+    matching::{Feature, matchers::identifier::IdentifierMatch, validators::validate_imo_mmsi},
     model::{Entity, SearchEntity},
   };
 
@@ -128,4 +196,77 @@ mod tests {
     let rhs = Entity::builder("Company").properties(&[("leiCode", &["XYZ789"])]).build();
     assert_eq!(feature.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no match on identifiers");
   }
+
+  #[test]
+  fn identifier_match_fuzzy() {
+    let strict = IdentifierMatch::new("t", &["innCode"], None);
+    let fuzzy = IdentifierMatch::fuzzy("t", &["innCode"], None);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("innCode", &["O12B45"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("innCode", &["012845"])]).build();
+
+    assert_eq!(strict.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(fuzzy.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+    assert_eq!(fuzzy.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "matched identifier: O12B45");
+
+    let rhs = Entity::builder("Company").properties(&[("innCode", &["012846"])]).build();
+
+    assert_eq!(fuzzy.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn identifier_match_lei_strict() {
+    let feature = IdentifierMatch::new("lei_code_match", &["leiCode"], Some(lei::validate));
+
+    let lhs = SearchEntity::builder("Company").properties(&[("leiCode", &["529900T8BM49AURSDO55"])]).build();
+
+    for variant in ["529900T8BM49AURSD055", "5299 00T8 BM49 AURS DO55", "529900t8bm49aursdo55"] {
+      let rhs = Entity::builder("Company").properties(&[("leiCode", &[variant])]).build();
+
+      assert_eq!(feature.score_scalar(&Bump::new(), &lhs, &rhs), 0.0, "{variant}");
+    }
+
+    let rhs = Entity::builder("Company").properties(&[("leiCode", &["529900T8BM49AURSDO55"])]).build();
+
+    assert_eq!(feature.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
+
+  #[test]
+  fn identifier_match_valid_codes_strict() {
+    fn validate(code: &str) -> bool {
+      code.len() == 6 && code.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+
+    let fuzzy = IdentifierMatch::fuzzy("t", &["innCode"], Some(validate));
+
+    // Both codes are valid, so they are compared as given.
+    let lhs = SearchEntity::builder("Company").properties(&[("innCode", &["O12B45"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("innCode", &["012845"])]).build();
+
+    assert_eq!(fuzzy.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    // Only the query's code is malformed, so it is made tolerant.
+    let lhs = SearchEntity::builder("Company").properties(&[("innCode", &["O12-B45"])]).build();
+
+    assert_eq!(fuzzy.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
+
+  #[test]
+  fn canonicalize_ocr() {
+    assert_eq!(super::canonicalize_ocr("O12B45"), "012845");
+    assert_eq!(super::canonicalize_ocr("iso9oo1"), "1509001");
+    assert_eq!(super::canonicalize_ocr("123456"), "123456");
+    assert_eq!(super::canonicalize_ocr("IMO9I876Z9"), "IMO9187629");
+    assert_eq!(super::canonicalize_ocr("imoO187629"), "imo0187629");
+  }
+
+  #[test]
+  fn identifier_match_fuzzy_imo() {
+    let feature = IdentifierMatch::fuzzy("t", &["imoNumber"], Some(validate_imo_mmsi));
+
+    let lhs = SearchEntity::builder("Vessel").properties(&[("imoNumber", &["IMO 9I876Z9"])]).build();
+    let rhs = Entity::builder("Vessel").properties(&[("imoNumber", &["IMO9187629"])]).build();
+
+    assert_eq!(feature.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
 }
@@ -32,32 +32,14 @@ fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) ->
   let mut similarities = Vec::with_capacity_in(lhs.name_parts_flat.len(), bump);
   let mut details: Option<(CompactString, CompactString, f64)> = None;
 
-  for part in &lhs.name_parts_flat {
-    let mut best = 0.0f64;
-    let mut best_other = None;
-
-    for other in &rhs_parts {
-      let similarity = jaro_winkler(part, other);
-
-      if similarity > 0.6 && similarity > best {
-        best = similarity;
-
-        if explain {
-          best_other = Some(other);
-        }
-
-        if best >= 1.0 {
-          break;
-        }
-      }
-    }
-
+  for (part, other, best) in align_jaro_name_parts(&lhs.name_parts_flat, &rhs_parts) {
     similarities.push(best);
 
-    if let Some(other) = best_other
+    if explain
+      && let Some(other) = other
       && details.as_ref().is_none_or(|(_, _, best_so_far)| best > *best_so_far)
     {
-      details = Some((part.as_str().into(), other.as_str().into(), best));
+      details = Some((part.into(), other.into(), best));
     }
   }
 
@@ -76,6 +58,36 @@ fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) ->
   (score, detail).into()
 }
 
+/// Align each query name part with its most similar result name part, as done
+/// by `jaro_name_parts`.
+///
+/// Parts without a result part similar enough are aligned with nothing and a
+/// similarity of zero.
+pub(crate) fn align_jaro_name_parts<'q, 'r, Q>(query: Q, result: &'r [String]) -> impl Iterator<Item = (&'q str, Option<&'r str>, f64)>
+where
+  Q: IntoIterator<Item = &'q String>,
+{
+  query.into_iter().map(move |part| {
+    let mut best = 0.0f64;
+    let mut best_other = None;
+
+    for other in result {
+      let similarity = jaro_winkler(part, other);
+
+      if similarity > 0.6 && similarity > best {
+        best = similarity;
+        best_other = Some(other.as_str());
+
+        if best >= 1.0 {
+          break;
+        }
+      }
+    }
+
+    (part.as_str(), best_other, best)
+  })
+}
+
 pub struct PersonNameJaroWinkler;
 
 impl Feature for PersonNameJaroWinkler {
@@ -114,7 +126,7 @@ impl Feature for PersonNameJaroWinkler {
         }
       }
 
-      pair_score = pair_score.max(align_name_parts(lhs_parts, rhs_parts));
+      pair_score = pair_score.max(align_name_parts(lhs_parts, rhs_parts, lhs.ignore_generational_suffixes));
 
       if pair_score > score {
         score = pair_score;
@@ -0,0 +1,79 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use itertools::Itertools;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, matchers::NO_DATA},
+  model::{Entity, HasProperties, SearchEntity},
+};
+
+/// Only the `jurisdiction` property is compared: `country` is already covered
+/// by `country_mismatch`, and counting it here would skew scores away from
+/// nomenklatura's.
+const JURISDICTION_PROPERTIES: &[&str] = &["jurisdiction"];
+
+#[scoring_feature(JurisdictionMatch, name = "jurisdiction_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  if !lhs.schema.is_a("Organization") || !rhs.schema.is_a("Organization") {
+    return (0.0, explain.then_some(Detail::Note("not an organization"))).into();
+  }
+
+  let lhs_countries = lhs.props(JURISDICTION_PROPERTIES).iter().map(|c| c.to_lowercase()).unique().collect_in::<Vec<_>>(bump);
+  let rhs_countries = rhs.props(JURISDICTION_PROPERTIES).iter().map(|c| c.to_lowercase()).unique().collect_in::<Vec<_>>(bump);
+
+  if lhs_countries.is_empty() || rhs_countries.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  let shared = lhs_countries.iter().filter(|country| rhs_countries.contains(country)).collect_in::<Vec<_>>(bump);
+
+  if shared.is_empty() {
+    return (0.0, explain.then_some(Detail::Note("no shared jurisdiction"))).into();
+  }
+
+  (1.0, explain.then(|| Detail::Labeled("shared jurisdictions", shared.iter().sorted().join(", ").into()))).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn jurisdiction_match() {
+    let lhs = SearchEntity::builder("Company").properties(&[("jurisdiction", &["GB"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("jurisdiction", &["gb", "us"])]).build();
+
+    assert_eq!(super::JurisdictionMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let rhs = Entity::builder("Company").properties(&[("country", &["gb"])]).build();
+
+    assert_eq!(super::JurisdictionMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let rhs = Entity::builder("Company").properties(&[("jurisdiction", &["us"])]).build();
+
+    assert_eq!(super::JurisdictionMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::JurisdictionMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no shared jurisdiction");
+  }
+
+  #[test]
+  fn jurisdiction_absent() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["ACME Ltd"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("jurisdiction", &["gb"])]).build();
+
+    assert_eq!(super::JurisdictionMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::JurisdictionMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no data to match against");
+
+    let lhs = SearchEntity::builder("Person").properties(&[("country", &["gb"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("country", &["gb"])]).build();
+
+    assert_eq!(super::JurisdictionMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+}
@@ -4,36 +4,26 @@ use libmotiva_macros::scoring_feature;
 
 use crate::{
   Entity, HasProperties, SearchEntity,
-  matching::{
-    Detail, Feature, ScoreResult, extractors,
-    replacers::{self, company_types::ORG_TYPES, stopwords::STOPWORDS},
-  },
+  matching::{Detail, Feature, NameReplacers, ScoreResult, extractors},
   model::{PropertyFilter, format_score},
 };
 
-fn fingerprint_name(name: &str) -> String {
-  let output = replacers::replace(&STOPWORDS.0, &STOPWORDS.1, name);
-  let output = replacers::replace(&ORG_TYPES.0, &ORG_TYPES.1, &output);
-
-  output.trim().to_string()
+pub(crate) fn fingerprint_name(name: &str, replacers: &NameReplacers) -> String {
+  replacers.apply(name).trim().to_string()
 }
 
 #[scoring_feature(LongestCommonSubsequence, name = "longest_common_subsequence")]
 fn score(&self, _bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
-  let lhs_names = lhs.prop_group("name", PropertyFilter::All);
+  let lhs_names = &lhs.name_fingerprints;
   let rhs_names = rhs.prop_group("name", PropertyFilter::All);
 
-  let lhs_names = extractors::index_name_keys(lhs_names.iter())
-    .map(|name| fingerprint_name(&name).chars().collect::<Vec<char>>())
-    .collect::<Vec<_>>();
-
   let mut max = 0.0f64;
   let mut best: Option<(CompactString, CompactString, CompactString)> = None;
 
   for rhs_name in extractors::index_name_keys(rhs_names.iter()) {
-    let rname = fingerprint_name(&rhs_name).chars().collect::<Vec<char>>();
+    let rname = fingerprint_name(&rhs_name, &lhs.name_replacers).chars().collect::<Vec<char>>();
 
-    for lname in &lhs_names {
+    for lname in lhs_names {
       let longest = lname.len().max(rname.len());
 
       if longest == 0 {
@@ -9,7 +9,7 @@ use libmotiva_macros::scoring_feature;
 
 use crate::{
   matching::{Detail, Feature, ScoreResult, extractors, matchers::NO_DATA},
-  model::{Entity, HasProperties, PropertyFilter, SearchEntity},
+  model::{Entity, HasProperties, SearchEntity},
 };
 
 pub(crate) type MatchExtractor<'e> = &'e (dyn Fn(&'_ dyn HasProperties) -> Cow<[String]> + Send + Sync);
@@ -56,7 +56,7 @@ impl<'e> Feature for SimpleMatch<'e> {
 
 #[scoring_feature(WeakAliasMatch, name = "weak_alias_match")]
 fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
-  let lhs_names = extractors::clean_names_light(lhs.prop_group("name", PropertyFilter::All).iter()).collect_in::<Vec<_>>(bump);
+  let lhs_names = &lhs.light_names;
   let rhs_names = extractors::clean_names_light(rhs.props(&["weakAlias", "abbreviation"]).iter()).collect_in::<Vec<_>>(bump);
 
   if lhs_names.is_empty() || rhs_names.is_empty() {
@@ -65,6 +65,10 @@ impl<'e> Feature for SimpleMismatch<'e> {
 
     (score, detail).into()
   }
+
+  fn missing_data(&self, lhs: &SearchEntity, rhs: &Entity) -> bool {
+    !(self.extractor)(lhs).is_empty() && (self.extractor)(rhs).is_empty()
+  }
 }
 
 #[scoring_feature(NumbersMismatch, name = "numbers_mismatch")]
@@ -100,6 +104,26 @@ fn score(&self, _bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -
   (score, detail).into()
 }
 
+/// Compare the status of both entities (e.g. `deceased` against `active`).
+///
+/// Entities missing a status on either side are not penalized.
+#[scoring_feature(StatusMismatch, name = "status_mismatch")]
+fn score(&self, _bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let normalize = |values: &[String]| values.iter().map(|value| value.trim().to_lowercase()).filter(|value| !value.is_empty()).collect::<std::vec::Vec<_>>();
+
+  let lhs_status = normalize(lhs.props(&["status"]).as_ref());
+  let rhs_status = normalize(rhs.props(&["status"]).as_ref());
+
+  if lhs_status.is_empty() || rhs_status.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  match is_disjoint(&lhs_status, &rhs_status) {
+    true => (1.0, explain.then(|| Detail::Labeled("status", rhs_status.join(", ").into()))).into(),
+    false => (0.0, explain.then_some(Detail::Note("no mismatch"))).into(),
+  }
+}
+
 pub(crate) fn dob_year_disjoint<S: AsRef<str>>(bump: &Bump, lhs: &[S], rhs: &[S]) -> f64 {
   // A date of birth is intrinsically invalid if it is not plain ASCII; such
   // values are skipped so they neither match nor trigger a mismatch penalty.
@@ -158,11 +182,13 @@ fn extract_month_day<S: AsRef<str>>(date: S) -> std::vec::Vec<char> {
 #[cfg(test)]
 mod tests {
   use crate::{
-    matching::Feature,
+    ScoringOptions,
+    matching::{Feature, FeaturesConfig, run_features},
     model::{Entity, SearchEntity},
   };
 
   use bumpalo::Bump;
+  use float_cmp::approx_eq;
 
   #[test]
   fn dob_year_disjoint() {
@@ -228,4 +254,75 @@ mod tests {
     assert_eq!(detail("Route 66", "Highway 66"), "all numbers matched");
     assert_eq!(detail("Route 66", "Highway 77"), "unmatched numbers: 66");
   }
+
+  #[test]
+  fn status_mismatch_unspecified() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Osama bin Laden"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["Osama bin Laden"]), ("status", &["deceased"])]).build();
+
+    assert_eq!(super::StatusMismatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn status_mismatch_active() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Osama bin Laden"]), ("status", &["Active"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["Osama bin Laden"]), ("status", &["deceased"])]).build();
+
+    assert_eq!(super::StatusMismatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let rhs = Entity::builder("Person").properties(&[("name", &["Osama bin Laden"]), ("status", &["active"])]).build();
+
+    assert_eq!(super::StatusMismatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  fn gender_missing_score(penalize_missing: Vec<String>, lhs: &[(&str, &[&str])], rhs: &[(&str, &[&str])]) -> f64 {
+    let features: std::vec::Vec<(&'static dyn Feature, f64)> = vec![(super::SimpleMismatch::new("gender_mismatch", &|e| crate::matching::extractors::genders(e), None), -0.2)];
+    let options = ScoringOptions {
+      penalize_missing,
+      ..Default::default()
+    };
+
+    let lhs = SearchEntity::builder("Person").properties(lhs).build();
+    let rhs = Entity::builder("Person").properties(rhs).build();
+
+    run_features(&Bump::new(), &lhs, &rhs, 0.8, FeaturesConfig::summed_features(&features, &options), &mut vec![])
+  }
+
+  #[test]
+  fn gender_missing_neutral() {
+    let query: &[(&str, &[&str])] = &[("name", &["John Doe"]), ("gender", &["male"])];
+    let candidate: &[(&str, &[&str])] = &[("name", &["John Doe"])];
+
+    assert!(approx_eq!(f64, gender_missing_score(vec![], query, candidate), 0.8));
+    assert!(approx_eq!(f64, gender_missing_score(vec!["gender_mismatch".to_string()], candidate, query), 0.8));
+    assert!(approx_eq!(f64, gender_missing_score(vec!["gender_mismatch".to_string()], candidate, candidate), 0.8));
+  }
+
+  #[test]
+  fn gender_missing_penalized() {
+    let query: &[(&str, &[&str])] = &[("name", &["John Doe"]), ("gender", &["male"])];
+    let candidate: &[(&str, &[&str])] = &[("name", &["John Doe"])];
+
+    assert!(approx_eq!(f64, gender_missing_score(vec!["gender_mismatch".to_string()], query, candidate), 0.7));
+    assert!(approx_eq!(f64, gender_missing_score(vec!["country_mismatch".to_string()], query, candidate), 0.8));
+  }
+
+  #[test]
+  fn gender_mismatch_normalized() {
+    let mismatch = super::SimpleMismatch::new("gender_mismatch", &|e| crate::matching::extractors::genders(e), None);
+
+    let score = |lhs: &str, rhs: &str| {
+      let lhs = SearchEntity::builder("Person").properties(&[("gender", &[lhs])]).build();
+      let rhs = Entity::builder("Person").properties(&[("gender", &[rhs])]).build();
+
+      mismatch.score_scalar(&Bump::new(), &lhs, &rhs)
+    };
+
+    assert_eq!(score("Male", "male"), 0.0);
+    assert_eq!(score("männlich", "male"), 0.0);
+    assert_eq!(score("M", "Male"), 0.0);
+    assert_eq!(score("weiblich", "F"), 0.0);
+    assert_eq!(score("männlich", "female"), 1.0);
+    assert_eq!(score("m", "w"), 1.0);
+  }
 }
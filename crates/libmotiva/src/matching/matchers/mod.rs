@@ -1,15 +1,25 @@
+pub(crate) mod acronym;
 pub(crate) mod address;
+pub(crate) mod birth_place;
+pub(crate) mod caption;
 pub(crate) mod crypto_wallet;
 pub(crate) mod dates;
 pub(crate) mod identifier;
 pub(crate) mod jaro_winkler;
+pub(crate) mod jurisdiction;
 pub(crate) mod marble;
 pub(crate) mod match_;
 pub(crate) mod mismatch;
 pub(crate) mod name_fingerprint_levenshtein;
 pub(crate) mod name_literal_match;
+pub(crate) mod nationality;
+pub(crate) mod org_dates;
 pub(crate) mod orgid_mismatch;
+pub(crate) mod phone;
 pub(crate) mod phonetic;
 pub(crate) mod soundex;
+pub(crate) mod vessel;
+pub(crate) mod weak_corroboration;
+pub(crate) mod wikidata;
 
 pub(crate) const NO_DATA: &str = "no data to match against";
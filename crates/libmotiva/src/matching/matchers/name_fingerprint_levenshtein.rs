@@ -5,10 +5,9 @@ use libmotiva_macros::scoring_feature;
 
 use crate::{
   matching::{
-    Detail, Feature, ScoreResult,
+    Detail, Feature, NameReplacers, ScoreResult,
     comparers::{default_levenshtein_similarity, levenshtein_similarity},
     extractors::{clean_names, tokenize_clean_names},
-    replacers::{self, company_types::ORG_TYPES, stopwords::STOPWORDS},
   },
   model::{Entity, HasProperties, PropertyFilter, SearchEntity, format_score},
 };
@@ -27,17 +26,14 @@ fn score(&self, _bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -
   (score, detail).into()
 }
 
-fn fingerprint_name(name: &str) -> String {
-  let output = replacers::replace(&STOPWORDS.0, &STOPWORDS.1, name);
-  let output = replacers::replace(&ORG_TYPES.0, &ORG_TYPES.1, &output);
-
-  output.trim().to_string()
+fn fingerprint_name(name: &str, replacers: &NameReplacers) -> String {
+  replacers.apply(name).trim().to_string()
 }
 
-fn pair_score(qn: &str, rn: &str) -> f64 {
+fn pair_score(qn: &str, rn: &str, replacers: &NameReplacers) -> f64 {
   let mut score = default_levenshtein_similarity(qn, rn);
 
-  let (qfp, rfp) = (fingerprint_name(qn), fingerprint_name(rn));
+  let (qfp, rfp) = (fingerprint_name(qn, replacers), fingerprint_name(rn, replacers));
 
   if qfp.chars().any(|c| !c.is_whitespace()) && rfp.chars().any(|c| !c.is_whitespace()) {
     let qfp_no_spaces = qfp.chars().filter(|c| !c.is_whitespace()).collect::<String>();
@@ -90,17 +86,16 @@ pub(crate) fn name_fingerprint_levenshtein(lhs: &SearchEntity, rhs: &Entity, exp
     return (0.0, None);
   }
 
-  let qiter = lhs.prop_group("name", PropertyFilter::All);
   let riter = rhs.prop_group("name", PropertyFilter::All);
 
-  let query_names = clean_names(qiter.iter()).filter(|word| word.len() >= 2);
+  let query_names = lhs.clean_names.iter().filter(|word| word.len() >= 2);
   let result_names = clean_names(riter.iter()).filter(|word| word.len() >= 2);
 
   let mut max = 0.0f64;
   let mut best: Option<(CompactString, CompactString)> = None;
 
   for (qn, rn) in query_names.cartesian_product(result_names) {
-    let score = pair_score(&qn, &rn);
+    let score = pair_score(qn, &rn, &lhs.name_replacers);
 
     if score > max {
       max = score;
@@ -127,7 +122,7 @@ mod tests {
   #[test]
   fn fingerprint_name() {
     assert_eq!(
-      super::fingerprint_name("ACME Inc. Comandita por Acciones General Partnership Anything Free Zone Co. andelslag"),
+      super::fingerprint_name("ACME Inc. Comandita por Acciones General Partnership Anything Free Zone Co. andelslag", &Default::default()),
       "ACME Inc. sca  Partnership Anything Free Zone Co. anl"
     );
   }
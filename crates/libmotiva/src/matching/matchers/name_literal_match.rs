@@ -13,28 +13,72 @@ use crate::{
   model::{Entity, HasProperties, PropertyFilter, SearchEntity},
 };
 
-pub struct NameLiteralMatch;
+/// Raw score given when a name is contained in the other, rather than equal.
+const CONTAINMENT_SCORE: f64 = 0.8;
+
+/// Compares cleaned names literally.
+///
+/// In containment mode, a name appearing in another name as a whole sequence
+/// of words (e.g. "IBM" in "International Business Machines (IBM)") also counts,
+/// with a score capped below that of an exact match.
+pub struct NameLiteralMatch {
+  containment: bool,
+}
 
 impl NameLiteralMatch {
+  pub const EXACT: &'static NameLiteralMatch = &NameLiteralMatch { containment: false };
+  pub const CONTAINMENT: &'static NameLiteralMatch = &NameLiteralMatch { containment: true };
+
   fn shared_name<'a>(lhs_names: &'a [String], rhs_names: &[String]) -> Option<&'a String> {
     lhs_names.iter().find(|name| rhs_names.contains(name))
   }
+
+  fn contained_name<'a>(lhs_names: &'a [String], rhs_names: &'a [String]) -> Option<(&'a String, &'a String)> {
+    lhs_names
+      .iter()
+      .cartesian_product(rhs_names.iter())
+      .find(|(lhs, rhs)| contains_words(lhs, rhs) || contains_words(rhs, lhs))
+  }
+}
+
+/// Whether all words of `needle` appear in `haystack`, contiguously and in order.
+fn contains_words(haystack: &str, needle: &str) -> bool {
+  let haystack = haystack.split_whitespace().collect::<std::vec::Vec<_>>();
+  let needle = needle.split_whitespace().collect::<std::vec::Vec<_>>();
+
+  !needle.is_empty() && needle.len() < haystack.len() && haystack.windows(needle.len()).any(|window| window == needle.as_slice())
 }
 
 impl Feature for NameLiteralMatch {
   fn name(&self) -> &'static str {
-    "name_literal_match"
+    match self.containment {
+      false => "name_literal_match",
+      true => "name_literal_containment",
+    }
   }
 
-  #[tracing::instrument(level = "trace", name = "name_literal_match", skip_all, fields(feature = "name_literal_match", entity_id = rhs.id))]
+  #[tracing::instrument(level = "trace", name = "name_literal_match", skip_all, fields(feature = self.name(), entity_id = rhs.id))]
   fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
     let lhs_names = extractors::clean_literal_names(lhs.prop_group("name", PropertyFilter::All).iter()).unique().collect_in::<Vec<_>>(bump);
     let rhs_names = extractors::clean_literal_names(rhs.prop_group("name", PropertyFilter::All).iter()).unique().collect_in::<Vec<_>>(bump);
 
-    match Self::shared_name(&lhs_names, &rhs_names) {
-      Some(name) => (1.0, explain.then(|| Detail::Equal(CompactString::from(name.as_str()), CompactString::from(name.as_str())))).into(),
-      None => (0.0, explain.then_some(Detail::Note("no literal name match"))).into(),
+    if let Some(name) = Self::shared_name(&lhs_names, &rhs_names) {
+      return (1.0, explain.then(|| Detail::Equal(CompactString::from(name.as_str()), CompactString::from(name.as_str())))).into();
     }
+
+    if self.containment
+      && let Some((lhs, rhs)) = Self::contained_name(&lhs_names, &rhs_names)
+    {
+      let detail = explain.then(|| Detail::Approximate {
+        lhs: CompactString::from(lhs.as_str()),
+        rhs: CompactString::from(rhs.as_str()),
+        note: CompactString::const_new("contained"),
+      });
+
+      return (CONTAINMENT_SCORE, detail).into();
+    }
+
+    (0.0, explain.then_some(Detail::Note("no literal name match"))).into()
   }
 }
 
@@ -51,11 +95,42 @@ mod tests {
     let lhs = SearchEntity::builder("Person").properties(&[("name", &["Donald Trump"]), ("alias", &["Orange man"])]).build();
     let rhs = Entity::builder("Person").properties(&[("name", &["Donald Trump"]), ("alias", &["Orange man"])]).build();
 
-    assert_eq!(super::NameLiteralMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+    assert_eq!(super::NameLiteralMatch::EXACT.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
 
     let lhs = SearchEntity::builder("Person").properties(&[("name", &["Donald Trump"]), ("alias", &["Orange man"])]).build();
     let rhs = Entity::builder("Person").properties(&[("name", &["Donald Duck"]), ("alias", &["POTUS"])]).build();
 
-    assert_eq!(super::NameLiteralMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::NameLiteralMatch::EXACT.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn name_literal_containment() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["IBM"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["International Business Machines (IBM)"])]).build();
+
+    assert_eq!(super::NameLiteralMatch::EXACT.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::NameLiteralMatch::CONTAINMENT.score_scalar(&Bump::new(), &lhs, &rhs), super::CONTAINMENT_SCORE);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["International Business Machines (IBM)"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["IBM"])]).build();
+
+    assert_eq!(super::NameLiteralMatch::CONTAINMENT.score_scalar(&Bump::new(), &lhs, &rhs), super::CONTAINMENT_SCORE);
+
+    let rhs = Entity::builder("Company").properties(&[("name", &["International Business Machines (IBM)"])]).build();
+
+    assert_eq!(super::NameLiteralMatch::CONTAINMENT.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
+
+  #[test]
+  fn name_literal_containment_whole_words() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["IBM"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["IBMX Holdings"])]).build();
+
+    assert_eq!(super::NameLiteralMatch::CONTAINMENT.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["Business International"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("name", &["International Business Machines"])]).build();
+
+    assert_eq!(super::NameLiteralMatch::CONTAINMENT.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
   }
 }
@@ -0,0 +1,72 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use itertools::Itertools;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, matchers::NO_DATA},
+  model::{Entity, HasProperties, SearchEntity},
+};
+
+const NATIONALITY_PROPERTIES: &[&str] = &["nationality", "citizenship", "country"];
+
+#[scoring_feature(NationalityMatch, name = "nationality_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let lhs_countries = lhs.props(NATIONALITY_PROPERTIES).iter().map(|c| c.to_lowercase()).unique().collect_in::<Vec<_>>(bump);
+  let rhs_countries = rhs.props(NATIONALITY_PROPERTIES).iter().map(|c| c.to_lowercase()).unique().collect_in::<Vec<_>>(bump);
+
+  if lhs_countries.is_empty() || rhs_countries.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  let shared = lhs_countries.iter().filter(|country| rhs_countries.contains(country)).collect_in::<Vec<_>>(bump);
+
+  if shared.is_empty() {
+    return (0.0, explain.then_some(Detail::Note("no shared nationality"))).into();
+  }
+
+  // Either side may list more nationalities than the other, so the overlap is
+  // graded against the shortest list.
+  let score = shared.len() as f64 / lhs_countries.len().min(rhs_countries.len()) as f64;
+
+  (score, explain.then(|| Detail::Labeled("shared nationalities", shared.iter().sorted().join(", ").into()))).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+  use float_cmp::approx_eq;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn nationality_match() {
+    let lhs = SearchEntity::builder("Person").properties(&[("nationality", &["ru"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("citizenship", &["ru", "ua"])]).build();
+
+    assert_eq!(super::NationalityMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let lhs = SearchEntity::builder("Person").properties(&[("nationality", &["ru", "fr"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("country", &["ru", "ua"])]).build();
+
+    assert!(approx_eq!(f64, super::NationalityMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.5));
+  }
+
+  #[test]
+  fn nationality_disjoint() {
+    let lhs = SearchEntity::builder("Person").properties(&[("nationality", &["ru"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("nationality", &["us"])]).build();
+
+    assert_eq!(super::NationalityMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::NationalityMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no shared nationality");
+
+    let rhs = Entity::builder("Person").properties(&[]).build();
+
+    assert_eq!(super::NationalityMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no data to match against");
+  }
+}
@@ -0,0 +1,129 @@
+use bumpalo::Bump;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{
+    Detail, Feature, ScoreResult,
+    matchers::{NO_DATA, dates::dob_progressive, mismatch::dob_year_disjoint},
+  },
+  model::{Entity, HasProperties, SearchEntity},
+};
+
+/// Compares incorporation dates of organizations progressively, like birth
+/// dates are for people. Only agreement is scored; disagreement is left to
+/// [`OrgDatesDisjoint`].
+#[scoring_feature(IncorporationDateMatch, name = "incorporation_date_match")]
+fn score(&self, _bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  if !lhs.schema.is_a("Organization") || !rhs.schema.is_a("Organization") {
+    return (0.0, explain.then_some(Detail::Note("not an organization"))).into();
+  }
+
+  let lhs_dates = lhs.props(&["incorporationDate"]);
+  let rhs_dates = rhs.props(&["incorporationDate"]);
+
+  if lhs_dates.is_empty() || rhs_dates.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  let (score, best) = dob_progressive(&lhs_dates[..], &rhs_dates[..], explain);
+
+  if score <= 0.0 {
+    return (0.0, explain.then_some(Detail::Note("incorporation dates differ"))).into();
+  }
+
+  (score, explain.then(|| best.map(|best| best.into_detail()).unwrap_or(Detail::Note("incorporation dates agree")))).into()
+}
+
+/// Penalizes organizations whose incorporation or dissolution years do not
+/// overlap.
+#[scoring_feature(OrgDatesDisjoint, name = "org_dates_disjoint")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  if !lhs.schema.is_a("Organization") || !rhs.schema.is_a("Organization") {
+    return (0.0, explain.then_some(Detail::Note("not an organization"))).into();
+  }
+
+  let mut compared = false;
+
+  for (property, note) in [("incorporationDate", "incorporation years differ"), ("dissolutionDate", "dissolution years differ")] {
+    let lhs_dates = lhs.props(&[property]);
+    let rhs_dates = rhs.props(&[property]);
+
+    if lhs_dates.is_empty() || rhs_dates.is_empty() {
+      continue;
+    }
+
+    compared = true;
+
+    if dob_year_disjoint(bump, &lhs_dates[..], &rhs_dates[..]) == 1.0 {
+      return (1.0, explain.then_some(Detail::Note(note))).into();
+    }
+  }
+
+  match compared {
+    true => (0.0, explain.then_some(Detail::Note("dates overlap"))).into(),
+    false => (0.0, explain.then_some(Detail::Note(NO_DATA))).into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn incorporation_date_match() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["ACME Ltd"]), ("incorporationDate", &["2001-03-14"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("incorporationDate", &["2001-03-14"])]).build();
+
+    assert_eq!(super::IncorporationDateMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+    assert_eq!(super::IncorporationDateMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "2001-03-14 == 2001-03-14");
+
+    let rhs = Entity::builder("Company").properties(&[("incorporationDate", &["2001"])]).build();
+
+    assert_eq!(super::IncorporationDateMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let rhs = Entity::builder("Company").properties(&[("incorporationDate", &["1987-03-14"])]).build();
+
+    assert_eq!(super::IncorporationDateMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::OrgDatesDisjoint.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
+
+  #[test]
+  fn org_dates_disjoint() {
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["ACME Ltd"]), ("incorporationDate", &["2001-03-14"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("incorporationDate", &["1987-03-14"])]).build();
+
+    assert_eq!(super::OrgDatesDisjoint.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+    assert_eq!(super::OrgDatesDisjoint.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "incorporation years differ");
+
+    let rhs = Entity::builder("Company").properties(&[("incorporationDate", &["2001-06-01"])]).build();
+
+    assert_eq!(super::OrgDatesDisjoint.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["ACME Ltd"]), ("dissolutionDate", &["2015"])]).build();
+    let rhs = Entity::builder("Company")
+      .properties(&[("incorporationDate", &["1987-03-14"]), ("dissolutionDate", &["2019-01-01"])])
+      .build();
+
+    assert_eq!(super::OrgDatesDisjoint.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+    assert_eq!(super::OrgDatesDisjoint.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "dissolution years differ");
+  }
+
+  #[test]
+  fn org_dates_only_for_organizations() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["John Doe"]), ("incorporationDate", &["2001"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("incorporationDate", &["1987"])]).build();
+
+    assert_eq!(super::IncorporationDateMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::OrgDatesDisjoint.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("name", &["ACME Ltd"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("incorporationDate", &["1987"])]).build();
+
+    assert_eq!(super::OrgDatesDisjoint.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no data to match against");
+  }
+}
@@ -17,15 +17,16 @@ use crate::{
   model::{Entity, HasProperties, SearchEntity, format_score},
 };
 
+/// Query properties holding organization identifiers.
+pub(crate) const ORG_IDENTIFIERS: &[&str] = &["registrationNumber", "taxNumber", "leiCode", "innCode", "bicCode", "ogrnCode"];
+
 #[scoring_feature(OrgIdMismatch, name = "orgid_disjoint")]
 fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
   if !lhs.schema.is_a("Organization") || !rhs.schema.is_a("Organization") {
     return (0.0, explain.then_some(Detail::Note("not an organization"))).into();
   }
 
-  let lhs = lhs.props(&["registrationNumber", "taxNumber", "leiCode", "innCode", "bicCode", "ogrnCode"]);
-
-  if lhs.is_empty() {
+  if lhs.props(ORG_IDENTIFIERS).is_empty() {
     return (0.0, explain.then_some(Detail::Note("no organization identifiers to compare"))).into();
   }
 
@@ -35,14 +36,14 @@ fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) ->
     return (0.0, explain.then_some(Detail::Note("no organization identifiers to compare"))).into();
   }
 
-  let lhs = extractors::normalize_identifiers(lhs.iter()).collect_in::<Vec<_>>(bump);
+  let lhs = &lhs.org_identifiers;
   let rhs = extractors::normalize_identifiers(rhs.iter()).collect_in::<Vec<_>>(bump);
 
   if lhs.is_empty() || rhs.is_empty() {
     return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
   }
 
-  if !is_disjoint(&lhs, &rhs) {
+  if !is_disjoint(lhs, &rhs) {
     return (0.0, explain.then_some(Detail::Note("organization identifiers overlap"))).into();
   }
 
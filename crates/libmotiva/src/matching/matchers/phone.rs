@@ -0,0 +1,365 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use itertools::Itertools;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, matchers::NO_DATA},
+  model::{Entity, HasProperties, PropertyFilter, SearchEntity},
+};
+
+/// Score of two numbers only compared on their trailing digits, when the
+/// country of a local number is unknown.
+const SUFFIX_SCORE: f64 = 0.8;
+/// Minimum number of digits for a number to be compared on its trailing digits.
+const MIN_SUFFIX_DIGITS: usize = 7;
+
+/// International calling codes, keyed by ISO 3166-1 alpha-2 country code.
+const CALLING_CODES: &[(&str, &str)] = &[
+  ("ad", "376"),
+  ("ae", "971"),
+  ("af", "93"),
+  ("ag", "1"),
+  ("ai", "1"),
+  ("al", "355"),
+  ("am", "374"),
+  ("ao", "244"),
+  ("ar", "54"),
+  ("as", "1"),
+  ("at", "43"),
+  ("au", "61"),
+  ("aw", "297"),
+  ("az", "994"),
+  ("ba", "387"),
+  ("bb", "1"),
+  ("bd", "880"),
+  ("be", "32"),
+  ("bf", "226"),
+  ("bg", "359"),
+  ("bh", "973"),
+  ("bi", "257"),
+  ("bj", "229"),
+  ("bm", "1"),
+  ("bn", "673"),
+  ("bo", "591"),
+  ("br", "55"),
+  ("bs", "1"),
+  ("bt", "975"),
+  ("bw", "267"),
+  ("by", "375"),
+  ("bz", "501"),
+  ("ca", "1"),
+  ("cd", "243"),
+  ("cf", "236"),
+  ("cg", "242"),
+  ("ch", "41"),
+  ("ci", "225"),
+  ("cl", "56"),
+  ("cm", "237"),
+  ("cn", "86"),
+  ("co", "57"),
+  ("cr", "506"),
+  ("cu", "53"),
+  ("cv", "238"),
+  ("cy", "357"),
+  ("cz", "420"),
+  ("de", "49"),
+  ("dj", "253"),
+  ("dk", "45"),
+  ("dm", "1"),
+  ("do", "1"),
+  ("dz", "213"),
+  ("ec", "593"),
+  ("ee", "372"),
+  ("eg", "20"),
+  ("er", "291"),
+  ("es", "34"),
+  ("et", "251"),
+  ("fi", "358"),
+  ("fj", "679"),
+  ("fr", "33"),
+  ("ga", "241"),
+  ("gb", "44"),
+  ("gd", "1"),
+  ("ge", "995"),
+  ("gh", "233"),
+  ("gi", "350"),
+  ("gm", "220"),
+  ("gn", "224"),
+  ("gq", "240"),
+  ("gr", "30"),
+  ("gt", "502"),
+  ("gu", "1"),
+  ("gw", "245"),
+  ("gy", "592"),
+  ("hk", "852"),
+  ("hn", "504"),
+  ("hr", "385"),
+  ("ht", "509"),
+  ("hu", "36"),
+  ("id", "62"),
+  ("ie", "353"),
+  ("il", "972"),
+  ("in", "91"),
+  ("iq", "964"),
+  ("ir", "98"),
+  ("is", "354"),
+  ("it", "39"),
+  ("jm", "1"),
+  ("jo", "962"),
+  ("jp", "81"),
+  ("ke", "254"),
+  ("kg", "996"),
+  ("kh", "855"),
+  ("km", "269"),
+  ("kn", "1"),
+  ("kp", "850"),
+  ("kr", "82"),
+  ("kw", "965"),
+  ("ky", "1"),
+  ("kz", "7"),
+  ("la", "856"),
+  ("lb", "961"),
+  ("lc", "1"),
+  ("li", "423"),
+  ("lk", "94"),
+  ("lr", "231"),
+  ("ls", "266"),
+  ("lt", "370"),
+  ("lu", "352"),
+  ("lv", "371"),
+  ("ly", "218"),
+  ("ma", "212"),
+  ("mc", "377"),
+  ("md", "373"),
+  ("me", "382"),
+  ("mg", "261"),
+  ("mk", "389"),
+  ("ml", "223"),
+  ("mm", "95"),
+  ("mn", "976"),
+  ("mo", "853"),
+  ("mr", "222"),
+  ("ms", "1"),
+  ("mt", "356"),
+  ("mu", "230"),
+  ("mv", "960"),
+  ("mw", "265"),
+  ("mx", "52"),
+  ("my", "60"),
+  ("mz", "258"),
+  ("na", "264"),
+  ("ne", "227"),
+  ("ng", "234"),
+  ("ni", "505"),
+  ("nl", "31"),
+  ("no", "47"),
+  ("np", "977"),
+  ("nz", "64"),
+  ("om", "968"),
+  ("pa", "507"),
+  ("pe", "51"),
+  ("pg", "675"),
+  ("ph", "63"),
+  ("pk", "92"),
+  ("pl", "48"),
+  ("pr", "1"),
+  ("ps", "970"),
+  ("pt", "351"),
+  ("py", "595"),
+  ("qa", "974"),
+  ("ro", "40"),
+  ("rs", "381"),
+  ("ru", "7"),
+  ("rw", "250"),
+  ("sa", "966"),
+  ("sc", "248"),
+  ("sd", "249"),
+  ("se", "46"),
+  ("sg", "65"),
+  ("si", "386"),
+  ("sk", "421"),
+  ("sl", "232"),
+  ("sm", "378"),
+  ("sn", "221"),
+  ("so", "252"),
+  ("sr", "597"),
+  ("ss", "211"),
+  ("sv", "503"),
+  ("sy", "963"),
+  ("sz", "268"),
+  ("tc", "1"),
+  ("td", "235"),
+  ("tg", "228"),
+  ("th", "66"),
+  ("tj", "992"),
+  ("tm", "993"),
+  ("tn", "216"),
+  ("tr", "90"),
+  ("tt", "1"),
+  ("tw", "886"),
+  ("tz", "255"),
+  ("ua", "380"),
+  ("ug", "256"),
+  ("us", "1"),
+  ("uy", "598"),
+  ("uz", "998"),
+  ("va", "39"),
+  ("vc", "1"),
+  ("ve", "58"),
+  ("vg", "1"),
+  ("vi", "1"),
+  ("vn", "84"),
+  ("ye", "967"),
+  ("za", "27"),
+  ("zm", "260"),
+  ("zw", "263"),
+];
+
+fn calling_code(country: &str) -> Option<&'static str> {
+  CALLING_CODES.iter().find(|(code, _)| code.eq_ignore_ascii_case(country)).map(|(_, calling_code)| *calling_code)
+}
+
+/// Normalize a phone number to its E.164 forms.
+///
+/// Numbers in international format (starting with `+` or `00`) have a single
+/// form. Local numbers have one form per country they may belong to, and none
+/// if those countries are unknown.
+pub(crate) fn e164(phone: &str, countries: &[String]) -> std::vec::Vec<String> {
+  let digits = phone.chars().filter(char::is_ascii_digit).collect::<String>();
+
+  if phone.trim_start().starts_with('+') {
+    return vec![format!("+{digits}")];
+  }
+  if let Some(digits) = digits.strip_prefix("00") {
+    return vec![format!("+{digits}")];
+  }
+
+  let local = digits.trim_start_matches('0');
+
+  if local.is_empty() {
+    return vec![];
+  }
+
+  countries
+    .iter()
+    .filter_map(|country| calling_code(country))
+    .unique()
+    .map(|code| {
+      // North American numbers are often written with their `1` trunk prefix.
+      let local = match code == "1" && local.len() == 11 {
+        true => local.strip_prefix('1').unwrap_or(local),
+        false => local,
+      };
+
+      format!("+{code}{local}")
+    })
+    .collect()
+}
+
+// Compares phone numbers once normalized to E.164, using the countries of the
+// entity to qualify local numbers. When a local number cannot be qualified,
+// numbers are compared on their trailing digits instead, for a lower score.
+#[scoring_feature(PhoneMatch, name = "phone_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let lhs_phones = lhs.props(&["phone"]);
+  let rhs_phones = rhs.props(&["phone"]);
+
+  if lhs_phones.is_empty() || rhs_phones.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  let lhs_countries = lhs.prop_group("country", PropertyFilter::Matchable);
+  let rhs_countries = rhs.prop_group("country", PropertyFilter::Matchable);
+
+  // Each phone number, along with its trailing digits (without trunk prefix) and E.164 forms.
+  let numbers = |phones: &[String], countries: &[String]| {
+    phones
+      .iter()
+      .map(|phone| (phone.chars().filter(char::is_ascii_digit).skip_while(|c| *c == '0').collect::<String>(), e164(phone, countries)))
+      .collect_in::<Vec<_>>(bump)
+  };
+
+  let lhs_numbers = numbers(lhs_phones.as_ref(), lhs_countries.as_ref());
+  let rhs_numbers = numbers(rhs_phones.as_ref(), rhs_countries.as_ref());
+
+  let matched = lhs_numbers
+    .iter()
+    .flat_map(|(_, forms)| forms)
+    .find(|number| rhs_numbers.iter().any(|(_, forms)| forms.contains(number)));
+
+  if let Some(number) = matched {
+    return (1.0, explain.then(|| Detail::Labeled("matched phone number", number.as_str().into()))).into();
+  }
+
+  // Numbers that could not be normalized on either side are compared on their trailing digits.
+  let suffix = lhs_numbers.iter().cartesian_product(rhs_numbers.iter()).find_map(|((lhs, lhs_forms), (rhs, rhs_forms))| {
+    let (shorter, longer) = if lhs.len() < rhs.len() { (lhs, rhs) } else { (rhs, lhs) };
+
+    ((lhs_forms.is_empty() || rhs_forms.is_empty()) && shorter.len() >= MIN_SUFFIX_DIGITS && longer.ends_with(shorter.as_str())).then_some((lhs, rhs))
+  });
+
+  match suffix {
+    Some((lhs, rhs)) => (SUFFIX_SCORE, explain.then(|| Detail::Equal(lhs.as_str().into(), rhs.as_str().into()))).into(),
+    None => (0.0, explain.then_some(Detail::Note("no matching phone number"))).into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn e164() {
+    let us = vec!["us".to_string()];
+    let gb = vec!["gb".to_string()];
+
+    assert_eq!(super::e164("+1 202-555-0100", &[]), vec!["+12025550100"]);
+    assert_eq!(super::e164("001 202 555 0100", &gb), vec!["+12025550100"]);
+    assert_eq!(super::e164("(202) 555-0100", &us), vec!["+12025550100"]);
+    assert_eq!(super::e164("1-202-555-0100", &us), vec!["+12025550100"]);
+    assert_eq!(super::e164("020 7946 0018", &gb), vec!["+442079460018"]);
+    assert!(super::e164("020 7946 0018", &[]).is_empty());
+  }
+
+  #[test]
+  fn phone_match_international_and_local() {
+    let lhs = SearchEntity::builder("Person").properties(&[("phone", &["+1 202-555-0100"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("phone", &["2025550100"]), ("country", &["us"])]).build();
+
+    assert_eq!(super::PhoneMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let lhs = SearchEntity::builder("Company").properties(&[("phone", &["020 7946 0018"]), ("country", &["gb"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("phone", &["+44 20 7946 0018"])]).build();
+
+    assert_eq!(super::PhoneMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+    assert_eq!(super::PhoneMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "matched phone number: +442079460018");
+
+    let rhs = Entity::builder("Company").properties(&[("phone", &["+33 1 23 45 67 89"])]).build();
+
+    assert_eq!(super::PhoneMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+
+  #[test]
+  fn phone_match_without_country() {
+    let lhs = SearchEntity::builder("Person").properties(&[("phone", &["+1 202-555-0100"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("phone", &["202 555 0100"])]).build();
+
+    assert_eq!(super::PhoneMatch.score_scalar(&Bump::new(), &lhs, &rhs), super::SUFFIX_SCORE);
+
+    let rhs = Entity::builder("Person").properties(&[("phone", &["202 555 0199"])]).build();
+
+    assert_eq!(super::PhoneMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let rhs = Entity::builder("Person").properties(&[]).build();
+
+    assert_eq!(super::PhoneMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no data to match against");
+  }
+}
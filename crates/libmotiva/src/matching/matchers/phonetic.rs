@@ -16,11 +16,10 @@ fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) ->
     return (0.0, explain.then_some(Detail::Note("not a person"))).into();
   }
 
-  let lhs_names = &lhs.clean_names;
   let rhs_names = extractors::clean_names(rhs.prop_group("name", PropertyFilter::All).iter()).collect_in::<Vec<_>>(bump);
 
-  let lhs_phone = extractors::phonetic_names_tuples(lhs_names.iter());
-  let rhs_phone = extractors::phonetic_names_tuples(rhs_names.iter());
+  let lhs_phone = &lhs.phonetic_names;
+  let rhs_phone = extractors::phonetic_names_tuples(rhs_names.iter(), lhs.phonetic_algorithm);
 
   let mut score = 0.0f64;
   let mut best_matches: std::vec::Vec<CodedPair> = std::vec::Vec::new();
@@ -104,4 +103,20 @@ mod tests {
     let rhs = Entity::builder("Person").properties(&[("name", &["Zeppelin"])]).build();
     assert_eq!(detail(&lhs, &rhs).as_deref(), Some("no phonetic match"));
   }
+
+  #[test]
+  fn person_name_phonetic_match_algorithm() {
+    use crate::matching::PhoneticAlgorithm;
+
+    let rhs = Entity::builder("Person").properties(&[("name", &["Ivan Bavel"])]).build();
+
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Ivan Babel"])]).build();
+    assert_eq!(super::PersonNamePhoneticMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.5);
+
+    let lhs = SearchEntity::builder("Person")
+      .properties(&[("name", &["Ivan Babel"])])
+      .phonetic_algorithm(PhoneticAlgorithm::Soundex)
+      .build();
+    assert_eq!(super::PersonNamePhoneticMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
 }
@@ -13,11 +13,11 @@ use crate::{
   model::{Entity, HasProperties, PropertyFilter, SearchEntity},
 };
 
-static SOUNDEX: LazyLock<Soundex> = LazyLock::new(Soundex::default);
+pub(crate) static SOUNDEX: LazyLock<Soundex> = LazyLock::new(Soundex::default);
 
 #[scoring_feature(SoundexNameParts, name = "soundex_name_parts")]
 fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
-  let mut similarities = Vec::with_capacity_in(lhs.name_parts_flat.len(), bump);
+  let mut similarities = Vec::with_capacity_in(lhs.name_parts_soundex.len(), bump);
 
   let rhs_soundexes = extractors::name_parts_flat(rhs.prop_group("name", PropertyFilter::All).iter())
     .unique()
@@ -29,9 +29,8 @@ fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) ->
 
   let mut best_match: Option<CodedPair> = None;
 
-  for part in &lhs.name_parts_flat {
-    let lhs_soundex = SOUNDEX.encode(part);
-    let matched = rhs_soundexes.iter().find(|(_, code)| code == &lhs_soundex);
+  for (part, lhs_soundex) in &lhs.name_parts_soundex {
+    let matched = rhs_soundexes.iter().find(|(_, code)| code == lhs_soundex);
 
     similarities.push(if matched.is_some() { 1.0 } else { 0.0 });
 
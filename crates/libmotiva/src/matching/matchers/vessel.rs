@@ -0,0 +1,99 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use itertools::Itertools;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, extractors, matchers::NO_DATA},
+  model::{Entity, HasProperties, SearchEntity},
+};
+
+const CALL_SIGN_SCORE: f64 = 0.7;
+const FLAG_SCORE: f64 = 0.3;
+
+#[scoring_feature(VesselAttributesMatch, name = "vessel_attributes_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  if !lhs.schema.is_a("Vessel") || !rhs.schema.is_a("Vessel") {
+    return (0.0, explain.then_some(Detail::Note("not a vessel"))).into();
+  }
+
+  let lhs_call_signs = lhs.props(&["callSign"]);
+  let rhs_call_signs = rhs.props(&["callSign"]);
+  let lhs_flags = lhs.props(&["flag"]);
+  let rhs_flags = rhs.props(&["flag"]);
+
+  if (lhs_call_signs.is_empty() || rhs_call_signs.is_empty()) && (lhs_flags.is_empty() || rhs_flags.is_empty()) {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  let mut score = 0.0;
+  let mut agreements = Vec::new_in(bump);
+
+  let lhs_call_signs = extractors::normalize_identifiers(lhs_call_signs.iter()).collect_in::<Vec<_>>(bump);
+
+  if extractors::normalize_identifiers(rhs_call_signs.iter()).any(|call_sign| lhs_call_signs.contains(&call_sign)) {
+    score += CALL_SIGN_SCORE;
+    agreements.push("call sign");
+  }
+
+  if lhs_flags.iter().any(|flag| rhs_flags.iter().any(|other| other.eq_ignore_ascii_case(flag))) {
+    score += FLAG_SCORE;
+    agreements.push("flag");
+  }
+
+  let detail = explain.then(|| match agreements.is_empty() {
+    true => Detail::Note("no vessel attribute agreement"),
+    false => Detail::Labeled("vessel attributes agree", agreements.iter().join(", ").into()),
+  });
+
+  (score, detail).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+  use float_cmp::approx_eq;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn vessel_call_sign_match() {
+    let lhs = SearchEntity::builder("Vessel").properties(&[("callSign", &["UBAF-7"])]).build();
+    let rhs = Entity::builder("Vessel").properties(&[("callSign", &["ubaf7"])]).build();
+
+    assert!(approx_eq!(f64, super::VesselAttributesMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.7));
+    assert_eq!(
+      super::VesselAttributesMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(),
+      "vessel attributes agree: call sign"
+    );
+  }
+
+  #[test]
+  fn vessel_flag_agreement() {
+    let lhs = SearchEntity::builder("Vessel").properties(&[("flag", &["Panama"]), ("callSign", &["3FAB4"])]).build();
+    let rhs = Entity::builder("Vessel").properties(&[("flag", &["pa"]), ("callSign", &["UBAF7"])]).build();
+
+    assert!(approx_eq!(f64, super::VesselAttributesMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.3));
+
+    let rhs = Entity::builder("Vessel").properties(&[("flag", &["pa"]), ("callSign", &["3FAB4"])]).build();
+
+    assert!(approx_eq!(f64, super::VesselAttributesMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0));
+    assert_eq!(
+      super::VesselAttributesMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(),
+      "vessel attributes agree: call sign, flag"
+    );
+  }
+
+  #[test]
+  fn vessel_attributes_other_schema() {
+    let lhs = SearchEntity::builder("Company").properties(&[("flag", &["pa"])]).build();
+    let rhs = Entity::builder("Vessel").properties(&[("flag", &["pa"])]).build();
+
+    assert_eq!(super::VesselAttributesMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+  }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use ahash::RandomState;
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use itertools::Itertools;
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, extractors},
+  model::{Entity, HasProperties, PropertyFilter, SearchEntity},
+};
+
+/// Contribution of each individual signal to the feature score.
+const SIGNAL_WEIGHT: f64 = 0.2;
+/// Number of leading digits of a phone number considered to be its area.
+const PHONE_AREA_DIGITS: usize = 5;
+
+// Corroborates candidates through several weak signals (country, partial
+// address, phone area) that mean little by themselves, but together make a
+// candidate worth surfacing. Each signal contributes at most 0.2, for a score
+// capped at 0.6.
+//
+// This improves recall for entities that do not share a strong name or
+// identifier match, at the cost of precision: many unrelated entities share a
+// country or a city. It is therefore disabled by default, and should only be
+// enabled with a low weight.
+#[scoring_feature(WeakCorroboration, name = "weak_corroboration")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let mut score = 0.0;
+  let mut signals = Vec::new_in(bump);
+
+  let lhs_countries = lhs.prop_group("country", PropertyFilter::Matchable);
+  let rhs_countries = rhs.prop_group("country", PropertyFilter::Matchable);
+
+  if lhs_countries.iter().any(|country| rhs_countries.iter().any(|other| other.eq_ignore_ascii_case(country))) {
+    score += SIGNAL_WEIGHT;
+    signals.push("country");
+  }
+
+  let address = address_overlap(bump, lhs, rhs);
+
+  if address > 0.0 {
+    score += SIGNAL_WEIGHT * address;
+    signals.push("address");
+  }
+
+  let lhs_areas = phone_areas(bump, lhs);
+  let rhs_areas = phone_areas(bump, rhs);
+
+  if lhs_areas.iter().any(|area| rhs_areas.contains(area)) {
+    score += SIGNAL_WEIGHT;
+    signals.push("phone area");
+  }
+
+  let detail = explain.then(|| match signals.is_empty() {
+    true => Detail::Note("no corroborating signals"),
+    false => Detail::Labeled("corroborating signals", signals.iter().join(", ").into()),
+  });
+
+  (score, detail).into()
+}
+
+/// Best token overlap between any two addresses, relative to the shortest one.
+fn address_overlap(bump: &Bump, lhs: &SearchEntity, rhs: &Entity) -> f64 {
+  let tokens = |props: &[String]| {
+    extractors::clean_address_parts(props.iter())
+      .map(|address| address.split_whitespace().map(str::to_string).collect::<HashSet<_, RandomState>>())
+      .filter(|tokens| !tokens.is_empty())
+      .collect_in::<Vec<_>>(bump)
+  };
+
+  let lhs_props = lhs.props(&["address"]);
+  let rhs_props = rhs.props(&["address"]);
+  let lhs_addresses = tokens(lhs_props.as_ref());
+  let rhs_addresses = tokens(rhs_props.as_ref());
+
+  lhs_addresses
+    .iter()
+    .cartesian_product(rhs_addresses.iter())
+    .map(|(lhs, rhs)| lhs.intersection(rhs).count() as f64 / lhs.len().min(rhs.len()) as f64)
+    .fold(0.0, f64::max)
+}
+
+fn phone_areas<'b>(bump: &'b Bump, entity: &impl HasProperties) -> Vec<'b, String> {
+  entity
+    .props(&["phone"])
+    .iter()
+    .map(|phone| phone.chars().filter(char::is_ascii_digit).collect::<String>())
+    .filter(|digits| digits.len() > PHONE_AREA_DIGITS)
+    .map(|digits| digits[..PHONE_AREA_DIGITS].to_string())
+    .collect_in::<Vec<_>>(bump)
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+  use float_cmp::approx_eq;
+
+  use crate::{
+    matching::{Feature, ScoreResult},
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn weak_corroboration() {
+    let lhs = SearchEntity::builder("Company")
+      .properties(&[("name", &["ACME"]), ("country", &["fr"]), ("address", &["Rue de Rivoli, Paris"])])
+      .build();
+    let rhs = Entity::builder("Company")
+      .properties(&[("name", &["Totally Different"]), ("country", &["fr"]), ("address", &["4 avenue de l'Opera, Paris"])])
+      .build();
+
+    let ScoreResult(score, detail) = super::WeakCorroboration.score(&Bump::new(), &lhs, &rhs, true);
+
+    // 0.2 for the country, and 0.2 * 2/4 for the address ("de" and "paris")
+    assert!(approx_eq!(f64, score, 0.3));
+    assert_eq!(detail.unwrap().to_string(), "corroborating signals: country, address");
+  }
+
+  #[test]
+  fn weak_corroboration_capped() {
+    let lhs = SearchEntity::builder("Company")
+      .properties(&[("country", &["fr"]), ("address", &["Paris"]), ("phone", &["+33 1 42 68 53 00"])])
+      .build();
+    let rhs = Entity::builder("Company")
+      .properties(&[("country", &["FR"]), ("address", &["Paris, France"]), ("phone", &["+33 1 42 00 00 00"])])
+      .build();
+
+    assert!(approx_eq!(f64, super::WeakCorroboration.score_scalar(&Bump::new(), &lhs, &rhs), 0.6));
+  }
+
+  #[test]
+  fn weak_corroboration_no_signal() {
+    let lhs = SearchEntity::builder("Company").properties(&[("country", &["fr"])]).build();
+    let rhs = Entity::builder("Company").properties(&[("country", &["de"])]).build();
+
+    assert_eq!(super::WeakCorroboration.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+    assert_eq!(super::WeakCorroboration.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no corroborating signals");
+  }
+}
@@ -0,0 +1,95 @@
+use bumpalo::{
+  Bump,
+  collections::{CollectIn, Vec},
+};
+use libmotiva_macros::scoring_feature;
+
+use crate::{
+  matching::{Detail, Feature, ScoreResult, matchers::NO_DATA},
+  model::{Entity, HasProperties, SearchEntity},
+};
+
+/// Normalize a Wikidata item ID (e.g. `q7747`), if the value is one.
+pub(crate) fn wikidata_qid(value: &str) -> Option<String> {
+  let value = value.trim();
+  let digits = value.strip_prefix(['Q', 'q'])?;
+
+  (!digits.is_empty() && !digits.starts_with('0') && digits.chars().all(|c| c.is_ascii_digit())).then(|| format!("Q{digits}"))
+}
+
+#[scoring_feature(WikidataIdMatch, name = "wikidata_id_match")]
+fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, explain: bool) -> ScoreResult {
+  let lhs_props = lhs.props(&["wikidataId"]);
+  let rhs_props = rhs.props(&["wikidataId"]);
+
+  let lhs_qids = lhs_props.iter().map(String::as_str).chain(lhs.id.as_deref()).filter_map(wikidata_qid).collect_in::<Vec<_>>(bump);
+  let rhs_qids = rhs_props
+    .iter()
+    .map(String::as_str)
+    .chain(std::iter::once(rhs.id.as_str()))
+    .chain(rhs.referents.iter().map(String::as_str))
+    .filter_map(wikidata_qid)
+    .collect_in::<Vec<_>>(bump);
+
+  if lhs_qids.is_empty() || rhs_qids.is_empty() {
+    return (0.0, explain.then_some(Detail::Note(NO_DATA))).into();
+  }
+
+  match lhs_qids.iter().find(|qid| rhs_qids.contains(qid)) {
+    Some(qid) => (1.0, explain.then(|| Detail::Labeled("matched wikidata ID", qid.as_str().into()))).into(),
+    None => (0.0, explain.then_some(Detail::Note("no matching wikidata ID"))).into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bumpalo::Bump;
+
+  use crate::{
+    matching::Feature,
+    model::{Entity, SearchEntity},
+  };
+
+  #[test]
+  fn wikidata_qid() {
+    assert_eq!(super::wikidata_qid("Q7747").as_deref(), Some("Q7747"));
+    assert_eq!(super::wikidata_qid(" q7747 ").as_deref(), Some("Q7747"));
+    assert_eq!(super::wikidata_qid("Q"), None);
+    assert_eq!(super::wikidata_qid("Q0123"), None);
+    assert_eq!(super::wikidata_qid("ofac-35096"), None);
+  }
+
+  #[test]
+  fn wikidata_id_match() {
+    let lhs = SearchEntity::builder("Person").properties(&[("wikidataId", &["Q7747"])]).build();
+    let rhs = Entity::builder("Person").id("Q7747").build();
+
+    assert_eq!(super::WikidataIdMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let rhs = Entity::builder("Person").id("NK-12345").properties(&[("wikidataId", &["Q1234"])]).build();
+
+    assert_eq!(super::WikidataIdMatch.score_scalar(&Bump::new(), &lhs, &rhs), 0.0);
+
+    let mut rhs = Entity::builder("Person").id("NK-12345").build();
+    rhs.referents = vec!["ofac-35096".to_string(), "Q7747".to_string()];
+
+    assert_eq!(super::WikidataIdMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+
+    let lhs = SearchEntity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build();
+    let rhs = Entity::builder("Person").id("NK-12345").properties(&[("wikidataId", &["Q7747"])]).build();
+
+    assert_eq!(super::WikidataIdMatch.score_scalar(&Bump::new(), &lhs, &rhs), 1.0);
+  }
+
+  #[test]
+  fn wikidata_id_match_details() {
+    let lhs = SearchEntity::builder("Person").properties(&[("wikidataId", &["Q7747"])]).build();
+    let rhs = Entity::builder("Person").id("Q7747").build();
+
+    assert_eq!(super::WikidataIdMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "matched wikidata ID: Q7747");
+
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    assert_eq!(super::WikidataIdMatch.score(&Bump::new(), &lhs, &rhs, true).1.unwrap().to_string(), "no data to match against");
+  }
+}
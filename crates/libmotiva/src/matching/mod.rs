@@ -1,36 +1,50 @@
 mod explanation;
-mod matchers;
+pub(crate) mod matchers;
 
 #[cfg(test)]
 mod tests;
 
 pub use explanation::{CodedPair, Detail, Explanation};
 
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, str::FromStr, time::Instant};
 
 use bumpalo::Bump;
 use jiff::Timestamp;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use serde_inline_default::serde_inline_default;
 use tracing::info_span;
+use whatlang::Script;
 
 use crate::{
+  error::MotivaError,
+  matching::replacers::{
+    addresses::ADDRESS_FORMS,
+    company_types::ORG_TYPES,
+    ordinals::ORDINALS,
+    stopwords::{self, STOPWORDS},
+    symbols::ORG_SYMBOLS,
+  },
   model::{Entity, SearchEntity},
   scoring::ScoringOptions,
 };
 
 pub(crate) mod comparers;
+pub(crate) mod composite;
 pub(crate) mod extractors;
+pub(crate) mod intermediates;
 pub(crate) mod latinize;
 pub(crate) mod logic_v1;
 pub(crate) mod marble_v0;
 pub(crate) mod name_based;
 pub(crate) mod name_qualified;
+pub(crate) mod reasons;
+pub(crate) mod registry;
 pub(crate) mod replacers;
 pub(crate) mod validators;
 
 /// Matching algorithms supported by motiva
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum Algorithm {
   #[serde(rename = "name-based")]
   NameBased,
@@ -110,14 +124,29 @@ pub trait Feature: Send + Sync {
   fn score_scalar(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity) -> f64 {
     self.score(bump, lhs, rhs, false).into()
   }
+
+  /// Whether the query has the data this feature compares, but the candidate lacks it.
+  ///
+  /// Features implementing this can have missing data penalized, see
+  /// [`ScoringOptions::penalize_missing`]. By default, features never report
+  /// missing data.
+  fn missing_data(&self, _lhs: &SearchEntity, _rhs: &Entity) -> bool {
+    false
+  }
 }
 
+/// Raw feature score given to a feature lacking data on either side, when
+/// missing data is penalized for that feature.
+pub(crate) const MISSING_DATA_PENALTY: f64 = 0.5;
+
+/// Weighted features to run with [`run_features`], and how to combine their scores.
 pub struct FeaturesConfig<'f, F>
 where
   F: IntoIterator<Item = &'f (&'f dyn Feature, f64)>,
 {
   features: F,
   weights: &'f HashMap<String, f64>,
+  penalize_missing: &'f [String],
   behavior: FeaturesBehavior,
   skip: FeaturesSkip,
   explain: bool,
@@ -131,6 +160,7 @@ where
     Self {
       features,
       weights: &options.weights,
+      penalize_missing: &options.penalize_missing,
       behavior: FeaturesBehavior::Sum,
       skip: FeaturesSkip::Never,
       explain: options.explain,
@@ -141,6 +171,7 @@ where
     Self {
       features,
       weights: &options.weights,
+      penalize_missing: &options.penalize_missing,
       behavior: FeaturesBehavior::Highest,
       skip: FeaturesSkip::Never,
       explain: options.explain,
@@ -151,6 +182,7 @@ where
     Self {
       features,
       weights: &options.weights,
+      penalize_missing: &options.penalize_missing,
       behavior: FeaturesBehavior::Sum,
       skip: FeaturesSkip::ScoreBelow(options.cutoff),
       explain: options.explain,
@@ -171,7 +203,13 @@ pub enum FeaturesSkip {
   ScoreBelow(f64),
 }
 
-fn run_features<'f, F>(bump: &Bump, lhs: &SearchEntity, rhs: &Entity, init: f64, config: FeaturesConfig<'f, F>, results: &mut Vec<Explanation>) -> f64
+/// Run a list of weighted features, combining their scores.
+///
+/// Scores are combined according to the [`FeaturesConfig`], starting from
+/// `init`, and an [`Explanation`] is pushed to `results` for every feature that
+/// was run. Features whose weight (possibly overridden in the scoring options)
+/// is zero are not run.
+pub fn run_features<'f, F>(bump: &Bump, lhs: &SearchEntity, rhs: &Entity, init: f64, config: FeaturesConfig<'f, F>, results: &mut Vec<Explanation>) -> f64
 where
   F: IntoIterator<Item = &'f (&'f dyn Feature, f64)>,
 {
@@ -197,7 +235,10 @@ where
     let then = Instant::now();
     // The detail is only built when explanations are requested; otherwise the
     // feature returns `None` and does no explanation work at all.
-    let ScoreResult(feature_score, detail) = func.score(bump, lhs, rhs, config.explain);
+    let ScoreResult(feature_score, detail) = match config.penalize_missing.iter().any(|name| name == func.name()) && func.missing_data(lhs, rhs) {
+      true => ScoreResult(MISSING_DATA_PENALTY, config.explain.then_some(Detail::Note("missing data penalized"))),
+      false => func.score(bump, lhs, rhs, config.explain),
+    };
 
     let weighted = feature_score * weight;
 
@@ -218,9 +259,25 @@ where
   })
 }
 
+/// Score at or above which a candidate is considered a match when neither the
+/// request nor the schema defaults provide one.
+pub const DEFAULT_THRESHOLD: f64 = 0.7;
+/// Minimum score for a candidate to be returned when neither the request nor
+/// the schema defaults provide one.
+pub const DEFAULT_CUTOFF: f64 = 0.5;
+
+/// Whether a score makes a candidate a match for the provided threshold.
+///
+/// The threshold is inclusive: a score exactly equal to the threshold is a match.
+#[inline]
+pub fn is_match(score: f64, threshold: f64) -> bool {
+  score >= threshold
+}
+
 /// Settings for a search
 #[serde_inline_default]
 #[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
 pub struct MatchParams {
   /// Root dataset for all search operations
   #[serde(skip_deserializing)]
@@ -233,17 +290,30 @@ pub struct MatchParams {
   /// `limit`*`candidate_factor` entities will be fetched, and `limit` will be returned at most.
   #[serde(skip)]
   pub candidate_factor: usize,
-  /// Minimum score to be considered a match.
+  /// Minimum score to be considered a match, inclusively (see [`is_match`]).
   ///
   /// An entity can still be returned if it is not a match, if it meet the `cutoff`.
-  #[serde_inline_default(0.7)]
-  pub threshold: f64,
+  /// When unset, the per-schema default or [`DEFAULT_THRESHOLD`] is used.
+  #[serde(default)]
+  pub threshold: Option<f64>,
   /// Minimum score to be returned.
-  #[serde_inline_default(0.5)]
-  pub cutoff: f64,
+  ///
+  /// When unset, the per-schema default or [`DEFAULT_CUTOFF`] is used.
+  #[serde(default)]
+  pub cutoff: Option<f64>,
   /// Algorithm to use for scoring.
   #[serde_inline_default(Algorithm::LogicV1)]
   pub algorithm: Algorithm,
+  /// Additional algorithms to score results with, for comparison purposes.
+  ///
+  /// Results are still selected, ordered and flagged as matches using `algorithm`.
+  #[serde(default)]
+  pub algorithms: Vec<Algorithm>,
+  /// Name of a custom algorithm to use for scoring instead of `algorithm`.
+  ///
+  /// See [`Motiva::register_algorithm`](crate::Motiva::register_algorithm).
+  #[serde(default)]
+  pub custom_algorithm: Option<String>,
   /// Filter topics an entity must be part of to be considered.
   pub topics: Option<Vec<String>>,
   /// Datasets to search from.
@@ -253,9 +323,21 @@ pub struct MatchParams {
   #[serde(default)]
   pub exclude_dataset: Vec<String>,
   /// List of entity IDs that should not be returned with the matches
-  #[serde(default)]
+  ///
+  /// This is typically used to suppress known false positives. Entities are
+  /// excluded both from the index query and, as a safety net, from the
+  /// returned candidates, whether the ID is their own or one they refer to.
+  #[serde(default, alias = "exclude_ids")]
   pub exclude_entity_ids: Vec<String>,
+  /// Drop candidates whose schema cannot be matched against the query's before
+  /// scoring, instead of letting them score 0.0.
+  ///
+  /// Disabled by default, since schemas retrieved through the index can be
+  /// related in ways the schema hierarchy alone does not capture.
+  #[serde(default)]
+  pub filter_schemas: bool,
   /// Only consider entities that were modified after the provided timestamp.
+  #[cfg_attr(feature = "openapi", param(value_type = Option<String>))]
   pub changed_since: Option<Timestamp>,
   /// List of schema to exclude from the search.
   #[serde(default)]
@@ -271,14 +353,313 @@ pub struct MatchParams {
   /// How many names to sample from the list of names and aliases
   #[serde_inline_default(10)]
   pub name_sample_size: usize,
+  /// Fraction of the query's name parts that must match for an entity to be
+  /// retrieved through them, rounded up (e.g. `0.5` requires two parts of a
+  /// four-part name). Other clauses of the query are not counted.
+  ///
+  /// When unset, matching a single clause is enough.
+  #[serde(default)]
+  pub name_parts_should_match: Option<f64>,
+  /// Minimum relevance score, as computed by the index, for an entity to be
+  /// retrieved as a candidate.
+  ///
+  /// This reduces the number of candidates handed to the scorer, but lowers
+  /// recall: relevant entities with a low index score are never scored.
+  #[serde(default)]
+  pub min_index_score: Option<f64>,
   /// Return a per-feature `explanations` object detailing how each feature
   /// scored. Disabled by default; enabling it costs extra computation.
   #[serde(default)]
   pub explain: bool,
+  /// Return the intermediate values computed by the name features (cleaned
+  /// names, tokens, phonemes and part alignments) for each result. This is
+  /// very verbose and only meant for debugging.
+  #[serde(default)]
+  pub explain_score: bool,
+  /// Return human-readable `reasons` summarizing why each result matched.
+  #[serde(default)]
+  pub include_reasons: bool,
+  /// Add the datasets each result was sourced from to its `reasons`.
+  #[serde(default)]
+  pub include_provenance: bool,
+  /// Mismatch features (e.g. `gender_mismatch`) for which a property set on
+  /// the query but missing on the candidate is mildly penalized, instead of
+  /// being ignored.
+  #[serde(default)]
+  pub penalize_missing: Vec<String>,
+  /// Escalate the penalty of mismatch qualifiers once at least that many of
+  /// them fire on a result. Disabled by default.
+  #[serde(default)]
+  pub escalate_mismatches: Option<usize>,
+  /// Render scores as integer percentages (0 to 100) instead of floats.
+  #[serde(default)]
+  pub score_as_percent: bool,
+  /// Number of decimals to which returned scores are rounded, instead of the
+  /// default of 3. Whether a result is a match is still decided on the
+  /// unrounded score.
+  #[serde(default)]
+  pub score_precision: Option<u8>,
+  /// Seed used to make sampling decisions reproducible across runs.
+  ///
+  /// When unset, the value configured in [`MotivaConfig`](crate::MotivaConfig) is used.
+  #[serde(default)]
+  pub seed: Option<u64>,
+  /// Search type to use when querying the index for candidates.
+  ///
+  /// When unset, the value configured in [`MotivaConfig`](crate::MotivaConfig) is used.
+  #[serde(default)]
+  pub search_type: Option<SearchType>,
+  /// Strategy used to pick the caption of returned entities.
+  ///
+  /// When unset, the value configured in [`MotivaConfig`](crate::MotivaConfig) is used.
+  #[serde(skip)]
+  pub caption_preference: Option<CaptionPreference>,
+  /// Language (e.g. `en` or `rus`) in which captions and names should preferably be surfaced.
+  ///
+  /// Names written in the language's script are preferred when available.
+  /// Unknown languages are ignored.
+  #[serde(default)]
+  pub prefer_lang: Option<String>,
+  /// Index fields to query for the values of some property types, overriding
+  /// the default mapping (e.g. `address` to `address_full` instead of `addresses`).
+  ///
+  /// Keys must be one of [`MAPPED_PROPERTY_TYPES`]. Overrides are given as
+  /// `type:field` pairs when deserialized (e.g. `address:address_full`).
+  #[serde(default, deserialize_with = "deserialize_field_mapping")]
+  #[cfg_attr(feature = "openapi", param(value_type = Option<Vec<String>>))]
+  pub field_mapping: HashMap<String, String>,
+}
+
+/// Property types whose values are queried against a dedicated index field,
+/// and can be remapped through [`MatchParams::field_mapping`].
+pub const MAPPED_PROPERTY_TYPES: &[&str] = &["address", "country", "date", "identifier", "url"];
+
+/// Read field mapping overrides given as `type:field` pairs.
+fn deserialize_field_mapping<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, String>, D::Error> {
+  Vec::<String>::deserialize(deserializer)?
+    .into_iter()
+    .map(|pair| match pair.split_once(':') {
+      Some((prop_type, field)) => Ok((prop_type.trim().to_string(), field.trim().to_string())),
+      None => Err(serde::de::Error::custom(format!("invalid field mapping, expected type:field: {pair}"))),
+    })
+    .collect()
+}
+
+/// How the index computes relevance when retrieving candidates.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum SearchType {
+  /// Gather term frequencies from all shards before scoring, for more accurate ranking.
+  #[default]
+  #[serde(rename = "dfs_query_then_fetch")]
+  DfsQueryThenFetch,
+  /// Score using shard-local term frequencies, trading some accuracy for latency.
+  #[serde(rename = "query_then_fetch")]
+  QueryThenFetch,
+}
+
+impl FromStr for SearchType {
+  type Err = anyhow::Error;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "dfs_query_then_fetch" => Ok(SearchType::DfsQueryThenFetch),
+      "query_then_fetch" => Ok(SearchType::QueryThenFetch),
+      other => Err(anyhow::anyhow!("unsupported search type: {other}")),
+    }
+  }
+}
+
+/// Phonetic encoder used to compare names.
+///
+/// The same encoder is used to query the `name_phonetic` index field and to
+/// score phonetic name similarity, so it must match the encoder the index was
+/// built with (Yente uses Metaphone).
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum PhoneticAlgorithm {
+  #[default]
+  Metaphone,
+  DoubleMetaphone,
+  Soundex,
+}
+
+impl FromStr for PhoneticAlgorithm {
+  type Err = anyhow::Error;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "metaphone" => Ok(PhoneticAlgorithm::Metaphone),
+      "double_metaphone" => Ok(PhoneticAlgorithm::DoubleMetaphone),
+      "soundex" => Ok(PhoneticAlgorithm::Soundex),
+      other => Err(anyhow::anyhow!("unsupported phonetic algorithm: {other}")),
+    }
+  }
+}
+
+/// A dictionary of replacements applied to names before they are fingerprinted.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum NameReplacer {
+  /// Remove person name prefixes (e.g. `mr` or `dr`).
+  Stopwords,
+  /// Remove person name prefixes written in the same script as the name.
+  ScriptStopwords,
+  /// Normalize company types to their short form (e.g. `limited` to `ltd`).
+  CompanyTypes,
+  /// Remove ordinals, including roman numerals.
+  Ordinals,
+  /// Remove address forms (e.g. `street` or `avenue`).
+  Addresses,
+  /// Normalize common organization words (e.g. `company` to `co`).
+  Symbols,
+}
+
+impl NameReplacer {
+  fn apply(self, name: &str) -> String {
+    match self {
+      NameReplacer::Stopwords => replacers::replace(&STOPWORDS.0, &STOPWORDS.1, name),
+      NameReplacer::ScriptStopwords => stopwords::remove_stopwords(name),
+      NameReplacer::CompanyTypes => replacers::replace(&ORG_TYPES.0, &ORG_TYPES.1, name),
+      NameReplacer::Ordinals => replacers::replace(&ORDINALS.0, &ORDINALS.1, name),
+      NameReplacer::Addresses => replacers::replace(&ADDRESS_FORMS.0, &ADDRESS_FORMS.1, name),
+      NameReplacer::Symbols => replacers::replace(&ORG_SYMBOLS.0, &ORG_SYMBOLS.1, name),
+    }
+  }
+}
+
+impl FromStr for NameReplacer {
+  type Err = anyhow::Error;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "stopwords" => Ok(NameReplacer::Stopwords),
+      "script_stopwords" => Ok(NameReplacer::ScriptStopwords),
+      "company_types" => Ok(NameReplacer::CompanyTypes),
+      "ordinals" => Ok(NameReplacer::Ordinals),
+      "addresses" => Ok(NameReplacer::Addresses),
+      "symbols" => Ok(NameReplacer::Symbols),
+      other => Err(anyhow::anyhow!("unsupported name replacer: {other}")),
+    }
+  }
+}
+
+/// Ordered pipeline of [`NameReplacer`]s applied when fingerprinting names.
+///
+/// Each replacer runs on the output of the previous one, so the order matters
+/// when dictionaries overlap. Defaults to removing stopwords, then normalizing
+/// company types.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct NameReplacers(pub Vec<NameReplacer>);
+
+impl Default for NameReplacers {
+  fn default() -> Self {
+    NameReplacers(vec![NameReplacer::Stopwords, NameReplacer::CompanyTypes])
+  }
+}
+
+impl NameReplacers {
+  /// Run all replacers on a name, in order.
+  pub(crate) fn apply(&self, name: &str) -> String {
+    self.0.iter().fold(name.to_string(), |name, replacer| replacer.apply(&name))
+  }
+}
+
+/// Parse a comma-separated list of replacers (e.g. `ordinals,company_types`).
+impl FromStr for NameReplacers {
+  type Err = anyhow::Error;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    let replacers = value.split(',').map(str::trim).filter(|name| !name.is_empty()).map(str::parse).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(NameReplacers(replacers))
+  }
+}
+
+/// Settings applied to the names of a query before it is scored.
+#[derive(Clone, Debug, Default)]
+pub struct QueryNormalization {
+  /// Phonetic encoder used to score names.
+  pub phonetic_algorithm: PhoneticAlgorithm,
+  /// Replacers applied, in order, to names before they are fingerprinted.
+  pub name_replacers: NameReplacers,
+  /// Ignore generational suffixes when aligning person name parts.
+  pub ignore_generational_suffixes: bool,
+}
+
+/// How to pick an entity's caption among its names.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum CaptionPreference {
+  /// Use the first value of the first caption property, as indexed.
+  First,
+  /// Prefer the longest name that does not look like an abbreviation.
+  #[default]
+  Longest,
+  /// Prefer names written in the given script, falling back to [`CaptionPreference::Longest`].
+  Script(Script),
+  /// Pick the most complete name among names and aliases, even over the
+  /// indexed caption, for datasets listing their canonical name as an alias.
+  Complete,
+}
+
+impl FromStr for CaptionPreference {
+  type Err = anyhow::Error;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "first" => Ok(CaptionPreference::First),
+      "longest" => Ok(CaptionPreference::Longest),
+      "complete" => Ok(CaptionPreference::Complete),
+      other => match parse_script(other) {
+        Some(script) => Ok(CaptionPreference::Script(script)),
+        None => Err(anyhow::anyhow!("unsupported caption preference: {other}")),
+      },
+    }
+  }
+}
+
+pub(crate) fn parse_script(name: &str) -> Option<Script> {
+  let script = match name.to_lowercase().as_str() {
+    "arabic" => Script::Arabic,
+    "armenian" => Script::Armenian,
+    "cyrillic" => Script::Cyrillic,
+    "devanagari" => Script::Devanagari,
+    "georgian" => Script::Georgian,
+    "greek" => Script::Greek,
+    "hangul" => Script::Hangul,
+    "hebrew" => Script::Hebrew,
+    "latin" => Script::Latin,
+    "mandarin" => Script::Mandarin,
+    "thai" => Script::Thai,
+    _ => return None,
+  };
+
+  Some(script)
+}
+
+/// Get the script a language is usually written in, from its ISO 639-1 or
+/// ISO 639-3 code. Script names (e.g. `cyrillic`) are also accepted.
+pub(crate) fn script_for_lang(lang: &str) -> Option<Script> {
+  let script = match lang.to_lowercase().as_str() {
+    "ar" | "ara" | "fa" | "fas" | "per" | "ur" | "urd" | "ps" | "pus" => Script::Arabic,
+    "hy" | "hye" | "arm" => Script::Armenian,
+    "ru" | "rus" | "uk" | "ukr" | "be" | "bel" | "bg" | "bul" | "sr" | "srp" | "mk" | "mkd" | "kk" | "kaz" | "ky" | "kir" | "tg" | "tgk" => Script::Cyrillic,
+    "hi" | "hin" | "mr" | "mar" | "ne" | "nep" => Script::Devanagari,
+    "ka" | "kat" | "geo" => Script::Georgian,
+    "el" | "ell" | "gre" => Script::Greek,
+    "ko" | "kor" => Script::Hangul,
+    "he" | "heb" | "yi" | "yid" => Script::Hebrew,
+    "zh" | "zho" | "chi" | "cmn" => Script::Mandarin,
+    "th" | "tha" => Script::Thai,
+    "en" | "eng" | "fr" | "fra" | "fre" | "de" | "deu" | "ger" | "es" | "spa" | "it" | "ita" | "pt" | "por" | "nl" | "nld" | "dut" | "pl" | "pol" | "tr" | "tur" | "ro" | "ron" | "rum" | "cs"
+    | "ces" | "cze" | "sv" | "swe" | "da" | "dan" | "no" | "nor" | "fi" | "fin" | "hu" | "hun" | "hr" | "hrv" | "id" | "ind" | "vi" | "vie" | "az" | "aze" | "uz" | "uzb" => Script::Latin,
+    other => return parse_script(other),
+  };
+
+  Some(script)
 }
 
 /// Variant of the index to use.
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum IndexType {
   #[default]
   #[serde(rename = "main")]
@@ -296,6 +677,21 @@ impl MatchParams {
   pub fn candidate_limit(&self, query: usize) -> usize {
     (self.limit * self.candidate_factor).max(query).clamp(20, 9999)
   }
+
+  /// Check that the field mapping overrides only refer to known property types.
+  pub fn validate_field_mapping(&self) -> Result<(), MotivaError> {
+    for (prop_type, field) in &self.field_mapping {
+      if !MAPPED_PROPERTY_TYPES.contains(&prop_type.as_str()) {
+        return Err(MotivaError::InvalidParameter(format!("cannot remap unknown property type: {prop_type}")));
+      }
+
+      if field.trim().is_empty() {
+        return Err(MotivaError::InvalidParameter(format!("empty index field for property type: {prop_type}")));
+      }
+    }
+
+    Ok(())
+  }
 }
 
 #[cfg(test)]
@@ -323,6 +719,12 @@ mod testing {
     }
   }
 
+  #[test]
+  fn algorithm_deserialize() {
+    assert_eq!(serde_json::from_str::<Algorithm>(r#""name-based""#).unwrap(), Algorithm::NameBased);
+    assert!(serde_json::from_str::<Algorithm>(r#""my-algorithm""#).is_err());
+  }
+
   #[test]
   fn index_type_deserialize() {
     assert_eq!(serde_json::from_str::<IndexType>(r#""main""#).unwrap(), IndexType::Main);
@@ -342,6 +744,84 @@ mod testing {
     assert_eq!(params.index_type, IndexType::Scoped);
   }
 
+  #[test]
+  fn search_type_from_str() {
+    use super::SearchType;
+
+    assert_eq!("dfs_query_then_fetch".parse::<SearchType>().unwrap(), SearchType::DfsQueryThenFetch);
+    assert_eq!("query_then_fetch".parse::<SearchType>().unwrap(), SearchType::QueryThenFetch);
+    assert!("other".parse::<SearchType>().is_err());
+    assert_eq!(SearchType::default(), SearchType::DfsQueryThenFetch);
+  }
+
+  #[test]
+  fn caption_preference_from_str() {
+    use whatlang::Script;
+
+    use super::CaptionPreference;
+
+    assert_eq!("first".parse::<CaptionPreference>().unwrap(), CaptionPreference::First);
+    assert_eq!("longest".parse::<CaptionPreference>().unwrap(), CaptionPreference::Longest);
+    assert_eq!("complete".parse::<CaptionPreference>().unwrap(), CaptionPreference::Complete);
+    assert_eq!("Cyrillic".parse::<CaptionPreference>().unwrap(), CaptionPreference::Script(Script::Cyrillic));
+    assert!("other".parse::<CaptionPreference>().is_err());
+    assert_eq!(CaptionPreference::default(), CaptionPreference::Longest);
+  }
+
+  #[test]
+  fn name_replacers_from_str() {
+    use super::{NameReplacer, NameReplacers};
+
+    assert_eq!(
+      "ordinals, company_types".parse::<NameReplacers>().unwrap(),
+      NameReplacers(vec![NameReplacer::Ordinals, NameReplacer::CompanyTypes])
+    );
+    assert_eq!("".parse::<NameReplacers>().unwrap(), NameReplacers(vec![]));
+    assert!("ordinals,other".parse::<NameReplacers>().is_err());
+    assert_eq!(NameReplacers::default(), NameReplacers(vec![NameReplacer::Stopwords, NameReplacer::CompanyTypes]));
+  }
+
+  #[test]
+  fn name_replacers_order() {
+    use super::{NameReplacer, NameReplacers};
+
+    let ordinals_first = NameReplacers(vec![NameReplacer::Ordinals, NameReplacer::CompanyTypes]);
+    let types_first = NameReplacers(vec![NameReplacer::CompanyTypes, NameReplacer::Ordinals]);
+
+    // Stripping the roman numeral first breaks the company type apart.
+    assert_eq!(types_first.apply("acme b.v.").trim(), "acme bv");
+    assert_ne!(ordinals_first.apply("acme b.v.").trim(), "acme bv");
+    assert_eq!(NameReplacers(vec![]).apply("acme b.v."), "acme b.v.");
+  }
+
+  #[test]
+  fn name_replacers_script_stopwords() {
+    use super::{NameReplacer, NameReplacers};
+
+    let plain = NameReplacers(vec![NameReplacer::Stopwords]);
+    let by_script = "script_stopwords".parse::<NameReplacers>().unwrap();
+
+    assert_eq!(by_script, NameReplacers(vec![NameReplacer::ScriptStopwords]));
+
+    assert_eq!(plain.apply("mr john smith").trim(), "john smith");
+    assert_eq!(by_script.apply("mr john smith").trim(), "john smith");
+
+    assert_eq!(plain.apply("mr иван петров").trim(), "иван петров");
+    assert_eq!(by_script.apply("mr иван петров"), "mr иван петров");
+  }
+
+  #[test]
+  fn script_for_lang() {
+    use whatlang::Script;
+
+    assert_eq!(super::script_for_lang("en"), Some(Script::Latin));
+    assert_eq!(super::script_for_lang("fra"), Some(Script::Latin));
+    assert_eq!(super::script_for_lang("RU"), Some(Script::Cyrillic));
+    assert_eq!(super::script_for_lang("ukr"), Some(Script::Cyrillic));
+    assert_eq!(super::script_for_lang("cyrillic"), Some(Script::Cyrillic));
+    assert_eq!(super::script_for_lang("xx"), None);
+  }
+
   #[test]
   fn candidate_limit() {
     fn p(limit: usize, factor: usize) -> MatchParams {
@@ -357,4 +837,19 @@ mod testing {
     assert_eq!(p(1, 1).candidate_limit(1), 20);
     assert_eq!(p(10, 1000).candidate_limit(1), 9999);
   }
+
+  #[test]
+  fn validate_field_mapping() {
+    fn p(mapping: &[(&str, &str)]) -> MatchParams {
+      super::MatchParams {
+        field_mapping: mapping.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        ..Default::default()
+      }
+    }
+
+    assert!(p(&[]).validate_field_mapping().is_ok());
+    assert!(p(&[("address", "address_full"), ("date", "all_dates")]).validate_field_mapping().is_ok());
+    assert!(matches!(p(&[("birthDate", "dates")]).validate_field_mapping(), Err(crate::MotivaError::InvalidParameter(_))));
+    assert!(matches!(p(&[("address", " ")]).validate_field_mapping(), Err(crate::MotivaError::InvalidParameter(_))));
+  }
 }
@@ -5,7 +5,7 @@ use tracing::instrument;
 
 use crate::{
   matching::{
-    Explanation, Feature, FeaturesConfig, MatchingAlgorithm,
+    Explanation, Feature, FeaturesConfig, MatchingAlgorithm, extractors,
     matchers::{
       jaro_winkler::JaroNameParts,
       mismatch::{SimpleMismatch, dob_day_disjoint, dob_year_disjoint},
@@ -28,7 +28,7 @@ static FEATURES: LazyLock<Vec<(&'static dyn Feature, f64)>> = LazyLock::new(|| {
     (SimpleMismatch::new("country_disjoint", &|e| e.props(&["country"]), None), -0.1),
     (SimpleMismatch::new("dob_year_disjoint", &|e| e.props(&["birthDate"]), Some(dob_year_disjoint)), -0.1),
     (SimpleMismatch::new("dob_day_disjoint", &|e| e.props(&["birthDate"]), Some(dob_day_disjoint)), -0.15),
-    (SimpleMismatch::new("gender_disjoint", &|e| e.props(&["gender"]), None), -0.1),
+    (SimpleMismatch::new("gender_disjoint", &|e| extractors::genders(e), None), -0.1),
     (&OrgIdMismatch, -0.1),
   ]
 });
@@ -0,0 +1,110 @@
+use std::cmp::Ordering;
+
+use itertools::Itertools;
+
+use crate::model::format_score;
+
+/// Build human-readable reasons explaining a match from its feature scores.
+///
+/// Reasons are ordered from the strongest to the weakest feature score, and
+/// features without a known template are reported with their raw score.
+pub fn match_reasons(features: &[(&'static str, f64)]) -> Vec<String> {
+  features
+    .iter()
+    .filter(|(_, score)| *score != 0.0)
+    .sorted_by(|(_, lhs), (_, rhs)| rhs.partial_cmp(lhs).unwrap_or(Ordering::Equal))
+    .map(|(name, score)| reason(name, *score))
+    .collect()
+}
+
+/// Describe which datasets a result was sourced from, if any.
+pub fn provenance_reason(datasets: &[String]) -> Option<String> {
+  if datasets.is_empty() {
+    return None;
+  }
+
+  Some(format!("listed in {}", datasets.iter().sorted().dedup().join(", ")))
+}
+
+fn reason(feature: &str, score: f64) -> String {
+  let score = format_score(score);
+
+  match feature {
+    "name_literal_match" => "name matches exactly".to_string(),
+    "name_literal_containment" => "name contains the other".to_string(),
+    "caption_exact_match" => "name is the listed caption".to_string(),
+    "person_name_jaro_winkler" | "name_fingerprint_levenshtein" | "jaro_name_parts" | "longest_common_subsequence" => format!("name similar ({score})"),
+    "person_name_phonetic_match" | "soundex_name_parts" => format!("name sounds similar ({score})"),
+    "weak_alias_match" => "matched on a weak alias".to_string(),
+    "acronym_match" => "matched on an acronym".to_string(),
+    "org_initials_match" => format!("initials match the name ({score})"),
+    "isin_security_match" => "matched on ISIN code".to_string(),
+    "lei_code_match" => "matched on LEI code".to_string(),
+    "ogrn_code_match" | "ogrn_code_fuzzy_match" => "matched on OGRN code".to_string(),
+    "inn_code_match" | "inn_code_fuzzy_match" => "matched on INN code".to_string(),
+    "bic_code_match" => "matched on BIC code".to_string(),
+    "vessel_imo_mmsi_match" | "vessel_imo_mmsi_fuzzy_match" => "matched on IMO or MMSI number".to_string(),
+    "identifier_match" => "matched on identifier".to_string(),
+    "wikidata_id_match" => "matched on Wikidata ID".to_string(),
+    "phone_match" => format!("phone number matches ({score})"),
+    "crypto_wallet_match" => "matched on crypto wallet".to_string(),
+    "address_entity_match" => format!("address similar ({score})"),
+    "country_match" => "country agrees".to_string(),
+    "nationality_match" => format!("nationality agrees ({score})"),
+    "jurisdiction_match" => "jurisdiction agrees".to_string(),
+    "incorporation_date_match" => format!("incorporation date agrees ({score})"),
+    "birth_place_match" => format!("birth place agrees ({score})"),
+    "weak_corroboration" => format!("weak signals agree ({score})"),
+    "vessel_attributes_match" => format!("vessel call sign or flag agrees ({score})"),
+    "country_mismatch" => "country disagrees".to_string(),
+    "last_name_mismatch" => "last name disagrees".to_string(),
+    "dob_year_disjoint" => "birth year disagrees".to_string(),
+    "dob_day_disjoint" => "birth date disagrees".to_string(),
+    "org_dates_disjoint" => "incorporation or dissolution year disagrees".to_string(),
+    "gender_mismatch" => "gender disagrees".to_string(),
+    "identifier_mismatch" | "orgid_disjoint" => "identifiers disagree".to_string(),
+    "numbers_mismatch" => "numbers in names disagree".to_string(),
+    "status_mismatch" => "status disagrees".to_string(),
+    "mismatch_escalation" => "several attributes disagree".to_string(),
+    other => format!("{other} ({score})"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    matching::logic_v1::LogicV1,
+    model::{Entity, SearchEntity},
+    scoring,
+  };
+
+  #[test]
+  fn lei_match_reasons() {
+    let lhs = SearchEntity::builder("Company")
+      .properties(&[("name", &["Google LLC"]), ("leiCode", &["529900T8BM49AURSDO55"])])
+      .build();
+    let rhs = Entity::builder("Company")
+      .properties(&[("name", &["Gogole LIMITED LIABILITY COMPANY"]), ("leiCode", &["529900T8BM49AURSDO55"])])
+      .build();
+
+    let results = scoring::score::<LogicV1>(&lhs, vec![rhs], &Default::default()).unwrap();
+    let reasons = super::match_reasons(&results[0].0.features);
+
+    assert_eq!(reasons[0], "matched on LEI code");
+    assert!(reasons.contains(&"name similar (0.778)".to_string()));
+  }
+
+  #[test]
+  fn provenance_reason() {
+    assert_eq!(super::provenance_reason(&[]), None);
+    assert_eq!(
+      super::provenance_reason(&["us_ofac_sdn".to_string(), "eu_fsf".to_string(), "us_ofac_sdn".to_string()]),
+      Some("listed in eu_fsf, us_ofac_sdn".to_string())
+    );
+  }
+
+  #[test]
+  fn unknown_feature_reason() {
+    assert_eq!(super::match_reasons(&[("custom_feature", 0.12345), ("disabled", 0.0)]), vec!["custom_feature (0.123)"]);
+  }
+}
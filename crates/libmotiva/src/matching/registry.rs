@@ -0,0 +1,81 @@
+use std::{
+  collections::HashMap,
+  fmt::Debug,
+  sync::{Arc, PoisonError, RwLock},
+};
+
+use bumpalo::Bump;
+
+use crate::{
+  Algorithm,
+  matching::Explanation,
+  model::{Entity, SearchEntity},
+  scoring::ScoringOptions,
+};
+
+/// Object-safe counterpart to [`MatchingAlgorithm`](crate::MatchingAlgorithm).
+///
+/// Built-in algorithms are statically dispatched, but library users can
+/// implement this trait to provide their own matching logic, register it with
+/// [`Motiva::register_algorithm`](crate::Motiva::register_algorithm) and select
+/// it with [`MatchParams::custom_algorithm`](crate::MatchParams::custom_algorithm).
+pub trait DynMatchingAlgorithm: Send + Sync {
+  /// Name under which the algorithm is registered and selected.
+  fn name(&self) -> &str;
+  /// Score an entity against search parameters.
+  ///
+  /// See [`MatchingAlgorithm::score`](crate::MatchingAlgorithm::score).
+  fn score(&self, bump: &Bump, lhs: &SearchEntity, rhs: &Entity, options: &ScoringOptions) -> (f64, Vec<Explanation>);
+}
+
+/// Algorithm resolved for scoring, either built-in or registered at runtime.
+///
+/// Built-in algorithms convert from [`Algorithm`], custom ones are looked up
+/// with [`Motiva::custom_algorithm`](crate::Motiva::custom_algorithm).
+#[derive(Clone)]
+pub enum ScoringAlgorithm {
+  Builtin(Algorithm),
+  Custom(Arc<dyn DynMatchingAlgorithm>),
+}
+
+impl ScoringAlgorithm {
+  pub fn name(&self) -> &str {
+    match self {
+      ScoringAlgorithm::Builtin(algorithm) => algorithm.name(),
+      ScoringAlgorithm::Custom(algorithm) => algorithm.name(),
+    }
+  }
+}
+
+impl From<Algorithm> for ScoringAlgorithm {
+  fn from(algorithm: Algorithm) -> Self {
+    ScoringAlgorithm::Builtin(algorithm)
+  }
+}
+
+/// Custom algorithms registered at runtime, shared between clones of [`Motiva`](crate::Motiva).
+#[derive(Clone, Default)]
+pub(crate) struct AlgorithmRegistry(Arc<RwLock<HashMap<String, Arc<dyn DynMatchingAlgorithm>>>>);
+
+impl AlgorithmRegistry {
+  pub(crate) fn register(&self, algorithm: Arc<dyn DynMatchingAlgorithm>) {
+    self.0.write().unwrap_or_else(PoisonError::into_inner).insert(algorithm.name().to_string(), algorithm);
+  }
+
+  pub(crate) fn get(&self, name: &str) -> Option<Arc<dyn DynMatchingAlgorithm>> {
+    self.0.read().unwrap_or_else(PoisonError::into_inner).get(name).cloned()
+  }
+
+  pub(crate) fn names(&self) -> Vec<String> {
+    let mut names = self.0.read().unwrap_or_else(PoisonError::into_inner).keys().cloned().collect::<Vec<_>>();
+
+    names.sort();
+    names
+  }
+}
+
+impl Debug for AlgorithmRegistry {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_list().entries(self.names()).finish()
+  }
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+  collections::{HashMap, HashSet},
+  sync::LazyLock,
+};
 
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
 use serde::Deserialize;
@@ -28,7 +31,50 @@ pub(crate) static ORDINALS: LazyLock<(AhoCorasick, Vec<&'static str>)> = LazyLoc
   )
 });
 
+/// Generational suffixes found at the end of person names, on top of the roman
+/// numerals from the ordinals dictionary.
+const GENERATIONAL_WORDS: &[&str] = &["jr", "junior", "sr", "senior"];
+
+/// Generational suffixes (e.g. `jr` or `iii`), in lowercase.
+///
+/// Single-letter numerals are left out, since they cannot be told apart from
+/// initials.
+pub(crate) static GENERATIONAL_SUFFIXES: LazyLock<HashSet<String>> = LazyLock::new(|| {
+  let file = Dictionaries::get("text/ordinals.yml").expect("could not read ordinals dictionary");
+  let dictionary = serde_yaml::from_slice::<AddressFormDictionary>(&file.data).expect("could not unmarshal ordinals dictionary");
+
+  dictionary
+    .ordinals
+    .into_iter()
+    .filter(|(key, _)| *key >= 2)
+    .flat_map(|(_, items)| items)
+    .map(|item| item.to_lowercase())
+    .filter(|item| item.len() > 1 && item.chars().all(|c| matches!(c, 'i' | 'v' | 'x')))
+    .chain(GENERATIONAL_WORDS.iter().map(|word| word.to_string()))
+    .collect()
+});
+
+#[inline]
+pub(crate) fn is_generational_suffix(part: &str) -> bool {
+  GENERATIONAL_SUFFIXES.contains(part)
+}
+
 #[derive(Deserialize)]
 struct AddressFormDictionary {
   ordinals: HashMap<usize, Vec<String>>,
 }
+
+#[cfg(test)]
+mod tests {
+  #[test]
+  fn is_generational_suffix() {
+    assert!(super::is_generational_suffix("jr"));
+    assert!(super::is_generational_suffix("sr"));
+    assert!(super::is_generational_suffix("ii"));
+    assert!(super::is_generational_suffix("iii"));
+
+    assert!(!super::is_generational_suffix("i"));
+    assert!(!super::is_generational_suffix("v"));
+    assert!(!super::is_generational_suffix("john"));
+  }
+}
@@ -1,30 +1,87 @@
-use std::sync::LazyLock;
+use std::{collections::HashMap, sync::LazyLock};
 
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use itertools::Itertools;
 use serde::Deserialize;
+use whatlang::Script;
 
-use crate::matching::replacers::Dictionaries;
+use crate::matching::replacers::{self, Dictionaries};
 
-pub(crate) static STOPWORDS: LazyLock<(AhoCorasick, Vec<String>)> = LazyLock::new(|| {
+pub(crate) static STOPWORDS: LazyLock<(AhoCorasick, Vec<String>)> = LazyLock::new(|| build(&PATTERNS));
+
+/// Person name prefixes, grouped by the script they are written in.
+static STOPWORDS_BY_SCRIPT: LazyLock<HashMap<Script, (AhoCorasick, Vec<String>)>> = LazyLock::new(|| {
+  PATTERNS
+    .iter()
+    .filter_map(|pattern| whatlang::detect_script(pattern).map(|script| (script, pattern.clone())))
+    .into_group_map()
+    .into_iter()
+    .map(|(script, patterns)| (script, build(&patterns)))
+    .collect()
+});
+
+static PATTERNS: LazyLock<Vec<String>> = LazyLock::new(|| {
   let file = Dictionaries::get("names/stopwords.yml").expect("could not read stopwords dictionary");
   let dictionary = serde_yaml::from_slice::<OrgSymbolDictionary>(&file.data).expect("could not unmarshal stopwords dictionary");
 
-  let mut patterns = Vec::new();
-  let mut replacements = Vec::new();
-
-  for item in dictionary.person_name_prefixes {
-    patterns.push(item.to_lowercase());
-    replacements.push(String::new());
-  }
+  dictionary.person_name_prefixes.iter().map(|item| item.to_lowercase()).collect()
+});
 
+fn build(patterns: &[String]) -> (AhoCorasick, Vec<String>) {
   (
     AhoCorasickBuilder::new().match_kind(MatchKind::LeftmostLongest).ascii_case_insensitive(true).build(patterns).unwrap(),
-    replacements,
+    vec![String::new(); patterns.len()],
   )
-});
+}
+
+/// Remove the person name prefixes written in the same script as the name.
+///
+/// The dictionary is not split by language, so the script detected by
+/// `whatlang` is used to tell which prefixes can apply: a name written in
+/// Cyrillic keeps a leading `Mr`, which is then more likely part of the name
+/// than an English honorific. Names whose script cannot be detected are
+/// stripped of all prefixes.
+pub(crate) fn remove_stopwords(name: &str) -> String {
+  let Some(script) = whatlang::detect_script(name) else {
+    return replacers::replace(&STOPWORDS.0, &STOPWORDS.1, name);
+  };
+
+  match STOPWORDS_BY_SCRIPT.get(&script) {
+    Some((aho, replacements)) => replacers::replace(aho, replacements, name),
+    None => name.to_string(),
+  }
+}
 
 #[derive(Deserialize)]
 struct OrgSymbolDictionary {
   #[serde(rename = "PERSON_NAME_PREFIXES")]
   person_name_prefixes: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+  #[test]
+  fn stopwords_latin_name() {
+    assert_eq!(super::remove_stopwords("mr john smith").trim(), "john smith");
+  }
+
+  #[test]
+  fn stopwords_other_script_name() {
+    assert_eq!(super::remove_stopwords("mr иван петров"), "mr иван петров");
+    assert_eq!(super::remove_stopwords("иван петров"), "иван петров");
+  }
+
+  #[test]
+  fn stopwords_english_and_non_english_names() {
+    let names = [
+      ("mr john smith", "john smith", "john smith"),
+      ("mr иван петров", "mr иван петров", "иван петров"),
+      ("mr ολγα παππα", "mr ολγα παππα", "ολγα παππα"),
+    ];
+
+    for (name, by_script, all) in names {
+      assert_eq!(super::remove_stopwords(name).trim(), by_script);
+      assert_eq!(super::replacers::replace(&super::STOPWORDS.0, &super::STOPWORDS.1, name).trim(), all);
+    }
+  }
+}
@@ -1 +1,2 @@
 mod comprehensive;
+mod throughput;
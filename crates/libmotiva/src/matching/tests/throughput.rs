@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, Instant};
+
+  use crate::{Entity, LogicV1, MatchingAlgorithm, NameBased, SearchEntity, scoring, tests::fixtures};
+
+  /// Minimum time spent measuring each algorithm on each case.
+  const MEASURE_FOR: Duration = Duration::from_millis(500);
+  /// How many times slower than `name-based` `logic-v1` may score the same
+  /// candidates before failing.
+  ///
+  /// `logic-v1` runs many more features, so it is expected to be slower, but
+  /// only by a rather stable factor: a clear regression (e.g. an allocation
+  /// added in a feature's hot loop) makes it grow well past this bound.
+  const MAX_SLOWDOWN: f64 = 25.0;
+
+  /// Score the candidates in a loop and return the throughput, in candidates
+  /// per second.
+  fn throughput<A: MatchingAlgorithm>(lhs: &SearchEntity, rhs: &[Entity]) -> f64 {
+    // Warm up the lazily-loaded dictionaries and taggers.
+    scoring::score::<A>(lhs, rhs.to_vec(), &Default::default()).unwrap();
+
+    let start = Instant::now();
+    let mut scored = 0;
+
+    while start.elapsed() < MEASURE_FOR {
+      scored += scoring::score::<A>(lhs, rhs.to_vec(), &Default::default()).unwrap().len();
+    }
+
+    scored as f64 / start.elapsed().as_secs_f64()
+  }
+
+  // The baseline is measured in the same run, on the same candidates, so the
+  // comparison holds regardless of the build profile and the machine.
+  #[test]
+  fn logic_v1_throughput() {
+    for (case, lhs, rhs) in fixtures::throughput_cases() {
+      let baseline = throughput::<NameBased>(&lhs, &rhs);
+      let throughput = throughput::<LogicV1>(&lhs, &rhs);
+      let min = baseline / MAX_SLOWDOWN;
+
+      assert!(
+        throughput >= min,
+        "{case}: logic-v1 scored {throughput:.0} candidates/s, expected at least {min:.0} candidates/s (name-based scored {baseline:.0})"
+      );
+    }
+  }
+}
@@ -1,6 +1,8 @@
 use std::{
   borrow::Cow,
+  cmp::Reverse,
   collections::{HashMap, HashSet},
+  fmt,
   str::FromStr,
   sync::{Arc, Mutex},
 };
@@ -10,14 +12,20 @@ use bon::bon;
 use celes::Country;
 use itertools::Itertools;
 use jiff::civil::DateTime;
-use serde::{Deserialize, Serialize, Serializer, ser::SerializeMap};
+use rphonetic::Encoder;
+use serde::{
+  Deserialize, Deserializer, Serialize, Serializer,
+  de::{MapAccess, Visitor},
+  ser::SerializeMap,
+};
 use strsim::levenshtein;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 use crate::{
   matching::{
-    Explanation,
+    Explanation, NameReplacers, PhoneticAlgorithm,
     extractors::{self, clean_names},
+    matchers::{marble::fingerprint_name, orgid_mismatch::ORG_IDENTIFIERS, soundex::SOUNDEX},
   },
   schemas::{FtmProperty, SCHEMAS, resolve_schemas},
 };
@@ -41,6 +49,7 @@ pub(crate) enum ResolveSchemaLevel {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Schema(String);
 
 impl Schema {
@@ -117,22 +126,60 @@ impl Schema {
   }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema), schema(as = SearchEntityParams))]
 pub struct PayloadParams {
   pub include_datasets: Option<Vec<String>>,
   pub exclude_datasets: Option<Vec<String>>,
 }
 
 /// Search terms
+///
+/// Scoring relies on values derived from the properties (cleaned names, name
+/// parts, etc.), which are computed by [`SearchEntity::precompute`]. Entities
+/// built with [`SearchEntity::builder`] or [`SearchEntity::from_properties`]
+/// are precomputed, but entities constructed or deserialized manually must
+/// call `precompute()` before being used. [`Motiva`](crate::Motiva) search and
+/// scoring methods precompute entities that were not.
 #[derive(Clone, Debug, Deserialize, Serialize, Validate)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SearchEntity {
+  /// Known ID of the entity, possibly from an older export.
+  ///
+  /// When set, the entity carrying this ID, or having it as a referent, is
+  /// retrieved as a candidate, on top of those found from its properties.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  #[validate(custom(function = "validate_schema"))]
   pub schema: Schema,
+  /// Additional schemas the query may match, on top of `schema`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  #[validate(custom(function = "validate_schemas"))]
+  pub schemas: Vec<Schema>,
+  /// Property values, keyed by property name.
+  ///
+  /// Values derived from the properties are cached, so mutating them requires
+  /// calling [`SearchEntity::recompute`] for the changes to be used in scoring.
+  #[serde(deserialize_with = "merge_properties")]
+  #[validate(custom(function = "validate_properties"))]
+  #[cfg_attr(feature = "openapi", schema(value_type = HashMap<String, Vec<String>>))]
   pub properties: HashMap<String, Vec<String>, RandomState>,
 
+  /// Advanced boolean filters, keyed by property name.
   #[serde(default)]
   pub filters: Option<HashMap<String, Vec<Vec<String>>>>,
-  #[serde(skip_serializing)]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
   pub params: Option<PayloadParams>,
+  /// Phonetic encoder used both to query and to score this entity's names.
+  #[serde(skip)]
+  pub phonetic_algorithm: PhoneticAlgorithm,
+  /// Replacers applied, in order, to names before they are fingerprinted.
+  #[serde(skip)]
+  pub name_replacers: NameReplacers,
+  /// Ignore generational suffixes (e.g. `jr` or `iii`) when aligning person
+  /// name parts, so `John Smith Jr.` aligns with `John Smith`.
+  #[serde(skip)]
+  pub ignore_generational_suffixes: bool,
 
   // Those attributes will be precomputed when receiving the request to skip the computation for every matching entity.
   #[serde(skip)]
@@ -141,15 +188,162 @@ pub struct SearchEntity {
   pub(crate) name_parts_flat: HashSet<String>,
   #[serde(skip)]
   pub(crate) name_parts: Vec<Vec<String>>,
+  #[serde(skip)]
+  pub(crate) light_names: Vec<String>,
+  #[serde(skip)]
+  pub(crate) phonetic_names: Vec<Vec<(String, Option<String>)>>,
+  #[serde(skip)]
+  pub(crate) name_parts_soundex: Vec<(String, String)>,
+  #[serde(skip)]
+  pub(crate) name_fingerprints: Vec<Vec<char>>,
+  #[serde(skip)]
+  pub(crate) org_identifiers: Vec<String>,
+  /// Whether the values above were computed, set by [`SearchEntity::precompute`].
+  #[serde(skip)]
+  pub(crate) precomputed: bool,
+}
+
+/// Hint given along errors about unknown schemas.
+pub const SCHEMA_HINT: &str = "expected a FollowTheMoney schema, such as Person, Organization, Company or Vessel";
+
+/// Queries must use schemas known to FollowTheMoney.
+fn validate_schema(schema: &Schema) -> Result<(), ValidationError> {
+  if SCHEMAS.contains_key(schema.as_str()) {
+    return Ok(());
+  }
+
+  Err(ValidationError {
+    message: Some(Cow::Owned(format!("unknown schema '{}', {SCHEMA_HINT}", schema.as_str()))),
+    code: Cow::Borrowed("unknown_schema"),
+    params: Default::default(),
+  })
+}
+
+fn validate_schemas(schemas: &[Schema]) -> Result<(), ValidationError> {
+  schemas.iter().try_for_each(validate_schema)
+}
+
+/// Queries must carry at least one usable value to search for.
+fn validate_properties(properties: &HashMap<String, Vec<String>, RandomState>) -> Result<(), ValidationError> {
+  if properties.values().flatten().all(|value| value.trim().is_empty()) {
+    return Err(ValidationError {
+      message: Some(Cow::Borrowed("at least one property must have a non-empty value")),
+      code: Cow::Borrowed("empty"),
+      params: Default::default(),
+    });
+  }
+
+  Ok(())
+}
+
+/// Deserialize properties, appending the values of keys present several times
+/// instead of only keeping the last ones, like the entity builders do.
+fn merge_properties<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, Vec<String>, RandomState>, D::Error> {
+  struct PropertiesVisitor;
+
+  impl<'de> Visitor<'de> for PropertiesVisitor {
+    type Value = HashMap<String, Vec<String>, RandomState>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      f.write_str("a map of property names to lists of values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+      let mut properties = HashMap::with_capacity_and_hasher(map.size_hint().unwrap_or_default(), RandomState::default());
+
+      while let Some((key, values)) = map.next_entry::<String, Vec<String>>()? {
+        properties.entry(key).or_insert_with(Vec::new).extend(values);
+      }
+
+      Ok(properties)
+    }
+  }
+
+  deserializer.deserialize_map(PropertiesVisitor)
 }
 
 impl SearchEntity {
+  /// All schemas the query may match, starting with its main one.
+  pub fn all_schemas(&self) -> impl Iterator<Item = &Schema> {
+    std::iter::once(&self.schema).chain(self.schemas.iter())
+  }
+
+  /// Whether an entity of the provided schema can be matched against this query.
+  pub(crate) fn can_match(&self, schema: &Schema) -> bool {
+    self.all_schemas().any(|s| schema.can_match(s.as_str()))
+  }
+
+  /// Build a query from a map of properties, ready to be used for scoring.
+  pub fn from_properties(schema: &str, properties: HashMap<String, Vec<String>>) -> SearchEntity {
+    let mut entity = SearchEntity {
+      id: None,
+      schema: Schema::from(schema),
+      schemas: Vec::new(),
+      properties: properties.into_iter().collect(),
+      filters: None,
+      params: None,
+      phonetic_algorithm: PhoneticAlgorithm::default(),
+      name_replacers: NameReplacers::default(),
+      ignore_generational_suffixes: false,
+      clean_names: Default::default(),
+      name_parts: Default::default(),
+      name_parts_flat: Default::default(),
+      light_names: Default::default(),
+      phonetic_names: Default::default(),
+      name_parts_soundex: Default::default(),
+      name_fingerprints: Default::default(),
+      org_identifiers: Default::default(),
+      precomputed: false,
+    };
+
+    entity.precompute();
+    entity
+  }
+
+  /// Recompute the values derived from the properties, after they were mutated.
+  ///
+  /// Derived values are never refreshed automatically, so any change to
+  /// `properties` after construction must be followed by a call to this
+  /// method, which is safe to call several times.
+  pub fn recompute(&mut self) {
+    self.precompute();
+  }
+
+  /// Whether the values derived from the properties were computed.
+  pub fn is_precomputed(&self) -> bool {
+    self.precomputed
+  }
+
+  /// This entity with its derived values computed, only cloning it when they
+  /// were not.
+  pub fn precomputed(&self) -> Cow<'_, SearchEntity> {
+    if self.is_precomputed() {
+      return Cow::Borrowed(self);
+    }
+
+    let mut entity = self.clone();
+
+    entity.precompute();
+
+    Cow::Owned(entity)
+  }
+
+  /// Clean up the properties and compute the values derived from them.
   pub fn precompute(&mut self) {
+    self.clean_properties();
+    self.normalize_names();
     self.combine_names();
 
     self.clean_names = extractors::clean_names(self.prop_group("name", PropertyFilter::All).iter()).collect();
     self.name_parts = extractors::name_parts(self.prop_group("name", PropertyFilter::All).iter()).collect();
     self.name_parts_flat = extractors::name_parts_flat(self.prop_group("name", PropertyFilter::All).iter()).collect();
+    self.light_names = extractors::clean_names_light(self.prop_group("name", PropertyFilter::All).iter()).collect();
+    self.phonetic_names = extractors::phonetic_names_tuples(self.clean_names.iter(), self.phonetic_algorithm);
+    self.name_parts_soundex = self.name_parts_flat.iter().map(|part| (part.clone(), SOUNDEX.encode(part))).collect();
+    self.name_fingerprints = extractors::index_name_keys(self.prop_group("name", PropertyFilter::All).iter())
+      .map(|name| fingerprint_name(&name, &self.name_replacers).chars().collect())
+      .collect();
+    self.org_identifiers = extractors::normalize_identifiers(self.props(ORG_IDENTIFIERS).iter()).collect();
 
     for (prop, values) in &mut self.properties {
       let Some((_, p)) = self.schema.property(prop) else { continue };
@@ -165,6 +359,45 @@ impl SearchEntity {
           .collect();
       }
     }
+
+    self.precomputed = true;
+  }
+
+  /// Trim property values and drop those left empty, so extractors do not
+  /// process blank values.
+  fn clean_properties(&mut self) {
+    for values in self.properties.values_mut() {
+      values.retain_mut(|value| {
+        let trimmed = value.trim();
+
+        if trimmed.len() != value.len() {
+          *value = trimmed.to_string();
+        }
+
+        !value.is_empty()
+      });
+    }
+
+    self.properties.retain(|_, values| !values.is_empty());
+  }
+
+  /// Normalize name values the way index queries do (see
+  /// [`extractors::normalize_name`]), so query terms and scoring features are
+  /// derived from the same names.
+  fn normalize_names(&mut self) {
+    for (prop, values) in &mut self.properties {
+      if self.schema.property(prop).is_none_or(|(_, p)| p._type != "name") {
+        continue;
+      }
+
+      for value in values.iter_mut() {
+        *value = extractors::normalize_name(value);
+      }
+
+      values.retain(|value| !value.is_empty());
+    }
+
+    self.properties.retain(|_, values| !values.is_empty());
   }
 
   pub fn combine_names(&mut self) {
@@ -205,22 +438,42 @@ impl SearchEntity {
     };
 
     if !aliases.is_empty() {
-      self.properties.entry("alias".into()).or_default().extend(aliases);
+      let existing = self.properties.entry("alias".into()).or_default();
+
+      // Names combined by an earlier computation are not added twice.
+      for alias in aliases {
+        if !existing.contains(&alias) {
+          existing.push(alias);
+        }
+      }
     }
   }
 
-  pub fn pick_names(&self, count: usize) -> Cow<'_, [String]> {
+  /// Sample up to `count` names that are as different from each other as possible.
+  ///
+  /// When a `seed` is provided, ties between equally-distant names are broken
+  /// deterministically from the seed instead of relying on the order in which
+  /// properties were read, so the same query always yields the same sample.
+  pub fn pick_names(&self, count: usize, seed: Option<u64>) -> Cow<'_, [String]> {
     let names = self.prop_group("name", PropertyFilter::Matchable);
 
     if names.len() < count {
       return names;
     }
 
+    let rank = |name: &String| seed.map(|seed| stable_hash(seed, name));
+
     let mut picked = Vec::with_capacity(count);
     let processed = clean_names(names.iter()).collect::<Vec<_>>();
 
     // TODO: Centroid is **not** the longest name in the original Yente implementation
-    if let Some(centroid) = names.iter().max_by_key(|name| name.len()) {
+    let centroid = if seed.is_some() {
+      names.iter().max_by_key(|name| (name.len(), Reverse(rank(name))))
+    } else {
+      names.iter().max_by_key(|name| name.len())
+    };
+
+    if let Some(centroid) = centroid {
       picked.push(centroid.to_owned());
     }
 
@@ -234,8 +487,9 @@ impl SearchEntity {
         }
 
         let total: usize = picked.iter().map(|name| levenshtein(candidate, name)).sum();
+        let tied = total as isize == max_distance && best.as_ref().is_some_and(|best| rank(names.get(index).unwrap()) < rank(best));
 
-        if total as isize > max_distance {
+        if total as isize > max_distance || tied {
           max_distance = total as isize;
           best = Some(names.get(index).unwrap().clone());
         }
@@ -251,6 +505,21 @@ impl SearchEntity {
   }
 }
 
+/// 64-bit FNV-1a hash of a seed and a name.
+///
+/// The output of FNV-1a is fully specified, unlike the standard library's
+/// hashers, so seeded ranks do not change across toolchains or releases.
+fn stable_hash(seed: u64, name: &str) -> u64 {
+  const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+  const PRIME: u64 = 0x100000001b3;
+
+  seed
+    .to_le_bytes()
+    .iter()
+    .chain(name.as_bytes())
+    .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(PRIME))
+}
+
 impl HasProperties for SearchEntity {
   fn props(&self, keys: &[&str]) -> Cow<'_, [String]> {
     match keys.len() {
@@ -305,7 +574,15 @@ impl HasProperties for SearchEntity {
 #[bon]
 impl SearchEntity {
   #[builder]
-  pub fn builder(#[builder(start_fn)] schema: &str, properties: &[(&str, &[&str])]) -> SearchEntity {
+  pub fn builder(
+    #[builder(start_fn)] schema: &str,
+    id: Option<&str>,
+    #[builder(default)] schemas: &[&str],
+    properties: &[(&str, &[&str])],
+    #[builder(default)] phonetic_algorithm: PhoneticAlgorithm,
+    #[builder(default)] name_replacers: NameReplacers,
+    #[builder(default)] ignore_generational_suffixes: bool,
+  ) -> SearchEntity {
     let mut props: HashMap<_, _, RandomState> = HashMap::default();
 
     for (prop, values) in properties {
@@ -313,13 +590,24 @@ impl SearchEntity {
     }
 
     let mut entity = SearchEntity {
+      id: id.map(ToString::to_string),
       schema: Schema::from(schema),
+      schemas: schemas.iter().map(|schema| Schema::from(schema)).collect(),
       properties: props,
       filters: None,
       params: None,
+      phonetic_algorithm,
+      name_replacers,
+      ignore_generational_suffixes,
       clean_names: Default::default(),
       name_parts: Default::default(),
       name_parts_flat: Default::default(),
+      light_names: Default::default(),
+      phonetic_names: Default::default(),
+      name_parts_soundex: Default::default(),
+      name_fingerprints: Default::default(),
+      org_identifiers: Default::default(),
+      precomputed: false,
     };
 
     entity.precompute();
@@ -328,7 +616,8 @@ impl SearchEntity {
 }
 
 /// An Entity returned from the index
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(bound(deserialize = "'de: 'static"))]
 pub struct Entity {
   pub id: String,
@@ -339,19 +628,89 @@ pub struct Entity {
   pub target: bool,
 
   #[serde(skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub first_seen: Option<DateTime>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub last_seen: Option<DateTime>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
   pub last_change: Option<DateTime>,
 
+  /// Property values, keyed by property name. Related entities are inlined as
+  /// objects when they were fetched along the entity.
+  #[cfg_attr(feature = "openapi", schema(value_type = HashMap<String, Vec<String>>))]
   pub properties: Properties,
 
-  #[serde(serialize_with = "features_to_map", skip_serializing_if = "Vec::is_empty")]
+  /// Score of each scoring feature, keyed by feature name.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[cfg_attr(feature = "openapi", schema(value_type = Option<HashMap<String, f64>>))]
   pub features: Vec<(&'static str, f64)>,
 
-  #[serde(serialize_with = "explanations_to_map", skip_serializing_if = "Vec::is_empty", skip_deserializing)]
+  #[serde(skip_serializing_if = "Vec::is_empty", skip_deserializing)]
   pub explanations: Vec<Explanation>,
+
+  /// Relevance score the index gave this entity when it was retrieved as a
+  /// candidate, only set on search results.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub index_score: Option<f64>,
+
+  /// Names of the index query clauses through which this entity was retrieved,
+  /// only populated when explanations are requested.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub matched_queries: Vec<String>,
+
+  /// Number of decimals feature and explanation scores are rounded to when
+  /// serialized, defaults to three.
+  #[serde(skip)]
+  pub score_precision: Option<u8>,
+
+  /// Caption picked with the default preference when read from the index, so
+  /// that scoring does not depend on the caption preference of the request.
+  #[serde(skip)]
+  pub(crate) canonical_caption: String,
+}
+
+// Custom serializer for output entities, so feature and explanation scores
+// can be rounded to the requested precision.
+impl Serialize for Entity {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let decimals = self.score_precision.unwrap_or(SCORE_DECIMALS);
+    let mut map = serializer.serialize_map(None)?;
+
+    map.serialize_entry("id", &self.id)?;
+    map.serialize_entry("caption", &self.caption)?;
+    map.serialize_entry("schema", &self.schema)?;
+    map.serialize_entry("datasets", &self.datasets)?;
+    map.serialize_entry("referents", &self.referents)?;
+    map.serialize_entry("target", &self.target)?;
+
+    for (key, value) in [("first_seen", &self.first_seen), ("last_seen", &self.last_seen), ("last_change", &self.last_change)] {
+      if let Some(value) = value {
+        map.serialize_entry(key, value)?;
+      }
+    }
+
+    map.serialize_entry("properties", &self.properties)?;
+
+    if !self.features.is_empty() {
+      map.serialize_entry("features", &Features(&self.features, decimals))?;
+    }
+    if !self.explanations.is_empty() {
+      map.serialize_entry("explanations", &Explanations(&self.explanations, decimals))?;
+    }
+    if let Some(index_score) = self.index_score {
+      map.serialize_entry("index_score", &index_score)?;
+    }
+    if !self.matched_queries.is_empty() {
+      map.serialize_entry("matched_queries", &self.matched_queries)?;
+    }
+
+    map.end()
+  }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -388,44 +747,46 @@ impl Serialize for Properties {
   }
 }
 
-fn features_to_map<S: Serializer>(input: &[(&'static str, f64)], ser: S) -> Result<S::Ok, S::Error> {
-  if input.is_empty() {
-    return ser.serialize_unit();
-  }
+struct Features<'a>(&'a [(&'static str, f64)], u8);
 
-  let mut map = ser.serialize_map(Some(input.len()))?;
-  for (k, v) in input {
-    map.serialize_entry(k, &format_score(*v))?;
+impl Serialize for Features<'_> {
+  fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+    let mut map = ser.serialize_map(Some(self.0.len()))?;
+    for (k, v) in self.0 {
+      map.serialize_entry(k, &round_score(*v, self.1))?;
+    }
+    map.end()
   }
-  map.end()
 }
 
-fn explanations_to_map<S: Serializer>(input: &[crate::matching::Explanation], ser: S) -> Result<S::Ok, S::Error> {
-  use crate::matching::Explanation;
+struct Explanations<'a>(&'a [Explanation], u8);
 
-  struct Rendered<'a>(&'a Explanation);
+impl Serialize for Explanations<'_> {
+  fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+    struct Rendered<'a>(&'a Explanation, u8);
 
-  impl Serialize for Rendered<'_> {
-    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
-      let scored = self.0.score != 0.0;
+    impl Serialize for Rendered<'_> {
+      fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let scored = self.0.score != 0.0;
 
-      let mut map = ser.serialize_map(Some(if scored { 3 } else { 1 }))?;
+        let mut map = ser.serialize_map(Some(if scored { 3 } else { 1 }))?;
 
-      if scored {
-        map.serialize_entry("score", &format_score(self.0.score))?;
-        map.serialize_entry("weighted", &format_score(self.0.weighted))?;
-      }
+        if scored {
+          map.serialize_entry("score", &round_score(self.0.score, self.1))?;
+          map.serialize_entry("weighted", &round_score(self.0.weighted, self.1))?;
+        }
 
-      map.serialize_entry("detail", &self.0.detail.to_string())?;
-      map.end()
+        map.serialize_entry("detail", &self.0.detail.to_string())?;
+        map.end()
+      }
     }
-  }
 
-  let mut map = ser.serialize_map(Some(input.len()))?;
-  for explanation in input {
-    map.serialize_entry(explanation.name, &Rendered(explanation))?;
+    let mut map = ser.serialize_map(Some(self.0.len()))?;
+    for explanation in self.0 {
+      map.serialize_entry(explanation.name, &Rendered(explanation, self.1))?;
+    }
+    map.end()
   }
-  map.end()
 }
 
 impl HasProperties for Entity {
@@ -482,7 +843,7 @@ impl HasProperties for Entity {
 #[bon]
 impl Entity {
   #[builder]
-  pub fn builder(#[builder(start_fn)] schema: &str, id: Option<&str>, #[builder(default)] properties: &[(&str, &[&str])]) -> Entity {
+  pub fn builder(#[builder(start_fn)] schema: &str, id: Option<&str>, #[builder(default)] properties: &[(&str, &[&str])], #[builder(default)] datasets: &[&str]) -> Entity {
     let mut props: HashMap<_, _, RandomState> = HashMap::default();
 
     for (prop, values) in properties {
@@ -493,18 +854,44 @@ impl Entity {
       schema: Schema::from(schema),
       id: id.map(ToOwned::to_owned).unwrap_or_default(),
       caption: String::new(),
+      datasets: datasets.iter().map(|s| s.to_string()).collect(),
       properties: Properties { strings: props, ..Default::default() },
       ..Default::default()
     }
   }
 }
 
+impl Entity {
+  /// Caption to score against, which does not depend on the caption
+  /// preference of the request.
+  pub(crate) fn scoring_caption(&self) -> &str {
+    match self.canonical_caption.is_empty() {
+      true => &self.caption,
+      false => &self.canonical_caption,
+    }
+  }
+
+  /// Scores of the features that contributed to this entity's score, keyed by
+  /// feature name.
+  pub fn features_map(&self) -> HashMap<&'static str, f64, RandomState> {
+    self.features.iter().copied().collect()
+  }
+}
+
+/// Default number of decimals scores are rounded to.
+const SCORE_DECIMALS: u8 = 3;
+
 #[inline]
 pub fn format_score(score: f64) -> f64 {
-  const SCORE_DECIMALS: u32 = 3;
-  const MULTIPLIER: f64 = 10u32.pow(SCORE_DECIMALS) as f64;
+  round_score(score, SCORE_DECIMALS)
+}
 
-  (score * MULTIPLIER).round() / MULTIPLIER
+/// Round a score to the provided number of decimals.
+#[inline]
+pub fn round_score(score: f64, decimals: u8) -> f64 {
+  let multiplier = 10f64.powi(decimals as i32);
+
+  (score * multiplier).round() / multiplier
 }
 
 #[cfg(test)]
@@ -517,6 +904,64 @@ mod tests {
     model::{Entity, PropertyFilter, ResolveSchemaLevel, Schema},
   };
 
+  #[test]
+  fn validate_properties() {
+    use validator::Validate;
+
+    assert!(SearchEntity::builder("Person").properties(&[]).build().validate().is_err());
+    assert!(SearchEntity::builder("Person").properties(&[("name", &[])]).build().validate().is_err());
+    assert!(SearchEntity::builder("Person").properties(&[("name", &["  "])]).build().validate().is_err());
+    assert!(SearchEntity::builder("Person").properties(&[("name", &[]), ("country", &["fr"])]).build().validate().is_ok());
+    assert!(SearchEntity::builder("Person").properties(&[("name", &["x"])]).build().validate().is_ok());
+  }
+
+  #[test]
+  fn validate_schema() {
+    use validator::Validate;
+
+    assert!(SearchEntity::builder("Person").properties(&[("name", &["x"])]).build().validate().is_ok());
+
+    let errors = SearchEntity::builder("Wizard").properties(&[("name", &["x"])]).build().validate().unwrap_err();
+
+    assert!(errors.to_string().contains("unknown schema 'Wizard'"));
+
+    let mut entity = SearchEntity::builder("Person").properties(&[("name", &["x"])]).build();
+
+    entity.schemas = vec![Schema::from("Company"), Schema::from("Wizard")];
+
+    assert!(entity.validate().is_err());
+  }
+
+  #[test]
+  fn search_entity_from_properties() {
+    let properties = [("name".to_string(), vec!["Vladimir Putin".to_string()])].into_iter().collect();
+    let entity = SearchEntity::from_properties("Person", properties);
+
+    assert_eq!(entity.schema.as_str(), "Person");
+    assert!(!entity.name_parts.is_empty());
+    assert!(entity.name_parts_flat.contains("vladimir"));
+    assert!(entity.name_parts_flat.contains("putin"));
+  }
+
+  #[test]
+  fn search_entity_recompute() {
+    let mut entity = SearchEntity::builder("Person")
+      .properties(&[("name", &["Vladimir Putin"]), ("firstName", &["Vladimir"]), ("lastName", &["Putin"])])
+      .build();
+
+    entity.properties.insert("name".to_string(), vec!["Xi Jinping".to_string()]);
+
+    assert!(entity.name_parts_flat.contains("putin"));
+    assert!(!entity.name_parts_flat.contains("jinping"));
+
+    entity.recompute();
+    entity.recompute();
+
+    assert!(entity.name_parts_flat.contains("jinping"));
+    assert!(entity.clean_names.iter().any(|name| name.contains("jinping")));
+    assert_eq!(entity.props(&["alias"]).len(), 1);
+  }
+
   #[test]
   fn explanations_serialize_to_map() {
     let mut entity = Entity::builder("Person").properties(&[]).build();
@@ -631,6 +1076,51 @@ mod tests {
     assert_eq!(se.clean_names, ["vladimir putin", "barack obama", "baraku obama"]);
   }
 
+  #[test]
+  fn precompute_query_artifacts() {
+    use crate::matching::{
+      PhoneticAlgorithm, extractors,
+      matchers::{marble::fingerprint_name, soundex::SOUNDEX},
+    };
+    use rphonetic::Encoder;
+
+    let se = SearchEntity::builder("Company")
+      .properties(&[
+        ("name", &["The ACME Holding Ltd", "Acme-Corp"]),
+        ("registrationNumber", &["12-34 56", "x"]),
+        ("leiCode", &["529900T8BM49AURSDO55"]),
+      ])
+      .phonetic_algorithm(PhoneticAlgorithm::Soundex)
+      .build();
+
+    let names = se.prop_group("name", PropertyFilter::All);
+
+    assert_eq!(se.light_names, extractors::clean_names_light(names.iter()).collect::<Vec<_>>());
+    assert_eq!(se.phonetic_names, extractors::phonetic_names_tuples(se.clean_names.iter(), PhoneticAlgorithm::Soundex));
+    assert_eq!(
+      se.name_fingerprints,
+      extractors::index_name_keys(names.iter())
+        .map(|name| fingerprint_name(&name, &se.name_replacers).chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(se.org_identifiers, ["123456", "529900T8BM49AURSDO55"]);
+
+    assert_eq!(se.name_parts_soundex.len(), se.name_parts_flat.len());
+    assert!(se.name_parts_soundex.iter().all(|(part, code)| se.name_parts_flat.contains(part) && *code == SOUNDEX.encode(part)));
+  }
+
+  #[test]
+  fn precompute_clean_properties() {
+    let se = SearchEntity::builder("Person")
+      .properties(&[("name", &["Bob ", "", "  Builder"]), ("alias", &[" ", ""]), ("country", &[" fr"])])
+      .build();
+
+    assert_eq!(se.props(&["name"]).as_ref(), ["Bob", "Builder"]);
+    assert!(!se.properties.contains_key("alias"));
+    assert_eq!(se.props(&["country"]).as_ref(), ["fr"]);
+    assert_eq!(se.clean_names, ["bob", "builder"]);
+  }
+
   #[test]
   fn precompute_name_parts_combinations() {
     let se = SearchEntity::builder("Person")
@@ -661,11 +1151,47 @@ mod tests {
       .properties(&[("name", &["Vladimir Putin"]), ("alias", &["John Doe", "John  Doe", "J. Doe", "Jonathan Doe", "JD", "Mr. John Doe"])])
       .build();
 
-    let names = aliases.pick_names(4);
+    let names = aliases.pick_names(4, None);
 
     assert_eq!(names.as_ref(), &["Vladimir Putin", "John Doe", "JD", "Jonathan Doe"]);
   }
 
+  #[test]
+  fn pick_names_seeded() {
+    let aliases = SearchEntity::builder("Person")
+      .properties(&[("name", &["Vladimir Putin"]), ("alias", &["Jon Doe", "Jan Doe", "Jim Doe", "Jen Doe", "Joe Doe", "Jay Doe"])])
+      .build();
+
+    let shuffled = SearchEntity::builder("Person")
+      .properties(&[("name", &["Vladimir Putin"]), ("alias", &["Jay Doe", "Joe Doe", "Jen Doe", "Jim Doe", "Jan Doe", "Jon Doe"])])
+      .build();
+
+    for seed in [0, 42, 1337] {
+      let first = aliases.pick_names(3, Some(seed));
+      let second = aliases.pick_names(3, Some(seed));
+
+      assert_eq!(first, second);
+      assert_eq!(first, shuffled.pick_names(3, Some(seed)));
+    }
+  }
+
+  #[test]
+  fn pick_names_seeded_stable() {
+    let aliases = SearchEntity::builder("Person")
+      .properties(&[("name", &["Vladimir Putin"]), ("alias", &["Jon Doe", "Jan Doe", "Jim Doe", "Jen Doe", "Joe Doe", "Jay Doe"])])
+      .build();
+
+    // Samples must not change across releases for a given seed.
+    assert_eq!(aliases.pick_names(3, Some(42)).as_ref(), &["Vladimir Putin", "Jon Doe", "Jay Doe"]);
+  }
+
+  #[test]
+  fn stable_hash() {
+    // Reference FNV-1a values, the hash must never change.
+    assert_eq!(super::stable_hash(0, ""), 0xa8c7f832281a39c5);
+    assert_ne!(super::stable_hash(0, "Jon Doe"), super::stable_hash(1, "Jon Doe"));
+  }
+
   #[test]
   fn resolve_schema_chain() {
     assert_eq!(Schema::from("Person").matchable_schemas(ResolveSchemaLevel::Root), &["Person", "LegalEntity"]);
@@ -1,16 +1,26 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
 use bon::bon;
+use itertools::Itertools;
 use jiff::Span;
+use serde::Deserialize;
 use tokio::sync::RwLock;
 
 use crate::{
   HttpCatalogFetcher, TestFetcher,
-  catalog::{Catalog, get_merged_catalog},
+  catalog::{Catalog, CatalogDiff, get_merged_catalog, get_merged_dataset, merge_manifest_catalogs},
   error::MotivaError,
   fetcher::CatalogFetcher,
   index::{EntityHandle, IndexProvider, elastic::config::IndexVersion},
-  matching::MatchParams,
+  matching::{
+    Algorithm, CaptionPreference, DEFAULT_CUTOFF, DEFAULT_THRESHOLD, MatchParams, QueryNormalization, SearchType, is_match,
+    logic_v1::LogicV1,
+    marble_v0::MarbleV0,
+    name_based::NameBased,
+    name_qualified::NameQualified,
+    registry::{AlgorithmRegistry, DynMatchingAlgorithm, ScoringAlgorithm},
+    script_for_lang,
+  },
   model::{Entity, SearchEntity},
   nested::fetch_nested_entities,
   prelude::MatchingAlgorithm,
@@ -22,7 +32,28 @@ pub enum GetEntityBehavior {
   /// Only fetch the requested entity
   RootOnly,
   /// Recursive into related entities and join them to the requested one.
-  FetchNestedEntity,
+  FetchNestedEntity(EdgeExpansion),
+}
+
+/// Which related entities to walk through when resolving nested entities.
+///
+/// The entities directly related to the requested one are always fetched.
+/// This policy decides whether their own references are followed further.
+///
+/// Edge schemas are detected from the FollowTheMoney schema definitions:
+/// schemas declaring an `edge` (source and target properties), such as
+/// `Family`, `Ownership` or `Documentation`, represent a link between two
+/// entities rather than an entity in their own right.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EdgeExpansion {
+  /// Follow references from the directly related entities, and from edge
+  /// entities at deeper levels, so that both ends of a relation are resolved.
+  #[default]
+  EdgesOnly,
+  /// Follow references from every fetched entity, up to the recursion limit.
+  Always,
+  /// Never follow references further than the directly related entities.
+  Never,
 }
 
 /// Entity graph resolution effort settings
@@ -49,9 +80,41 @@ impl GetEntityLimits {
   }
 }
 
+/// Scoring thresholds to use for a specific schema.
+///
+/// Any value left unset falls back to the global default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub struct SchemaDefaults {
+  pub threshold: Option<f64>,
+  pub cutoff: Option<f64>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct MotivaConfig {
   pub outdated_grace: Span,
+  /// Default threshold and cutoff, keyed by schema name.
+  pub schema_defaults: HashMap<String, SchemaDefaults>,
+  /// Search type used to retrieve candidates, unless overridden in [`MatchParams`].
+  pub search_type: SearchType,
+  /// Seed for reproducible sampling, unless overridden in [`MatchParams`].
+  pub seed: Option<u64>,
+  /// How to pick entity captions, unless overridden in [`MatchParams`].
+  pub caption_preference: CaptionPreference,
+}
+
+impl MotivaConfig {
+  /// Resolve the `(threshold, cutoff)` pair to use for a query.
+  ///
+  /// Values explicitly set on the request take precedence, then the defaults
+  /// configured for the query's schema, then the global defaults.
+  pub fn thresholds(&self, schema: &str, params: &MatchParams) -> (f64, f64) {
+    let defaults = self.schema_defaults.get(schema).copied().unwrap_or_default();
+
+    let threshold = params.threshold.or(defaults.threshold).unwrap_or(DEFAULT_THRESHOLD);
+    let cutoff = params.cutoff.or(defaults.cutoff).unwrap_or(DEFAULT_CUTOFF);
+
+    (threshold, cutoff)
+  }
 }
 
 /// The main entrypoint for using the Motiva library.
@@ -87,6 +150,9 @@ pub struct Motiva<P: IndexProvider, F: CatalogFetcher = HttpCatalogFetcher> {
   fetcher: F,
   config: MotivaConfig,
   catalog: Arc<RwLock<Catalog>>,
+  /// Changes brought by the latest full refresh of the catalog.
+  last_diff: Arc<RwLock<CatalogDiff>>,
+  algorithms: AlgorithmRegistry,
 }
 
 /// Perform the initial catalog fetch, tolerating failures.
@@ -94,15 +160,26 @@ pub struct Motiva<P: IndexProvider, F: CatalogFetcher = HttpCatalogFetcher> {
 /// If the catalog cannot be built (e.g. the index is missing or upstream is
 /// unreachable), we start with an empty catalog and log a warning rather than
 /// aborting startup. The background refresh loop recovers it once the index and
-/// upstream become available.
-async fn init_catalog<P: IndexProvider, F: CatalogFetcher>(fetcher: &F, provider: &P, outdated_grace: Span) -> Catalog {
-  match get_merged_catalog(fetcher, provider, outdated_grace).await {
-    Ok(catalog) => catalog,
+/// upstream become available. An invalid manifest is rejected, however, since
+/// refreshing would not fix it.
+async fn init_catalog<P: IndexProvider, F: CatalogFetcher>(fetcher: &F, provider: &P, outdated_grace: Span) -> Result<Catalog, MotivaError> {
+  let catalog = match fetcher.fetch_manifest().await {
+    Ok(manifest) => {
+      manifest.validate()?;
+
+      merge_manifest_catalogs(fetcher, provider, outdated_grace, manifest).await
+    }
+
+    Err(err) => Err(err),
+  };
+
+  match catalog {
+    Ok(catalog) => Ok(catalog),
 
     Err(err) => {
       tracing::warn!(error = err.to_string(), "could not initialize catalog, starting with an empty catalog");
 
-      Catalog::default()
+      Ok(Catalog::default())
     }
   }
 }
@@ -132,13 +209,16 @@ impl<P: IndexProvider> Motiva<P> {
     provider.after_init();
 
     let fetcher = HttpCatalogFetcher::default();
-    let catalog = init_catalog(&fetcher, &provider, config.outdated_grace).await;
+
+    let catalog = init_catalog(&fetcher, &provider, config.outdated_grace).await?;
 
     Ok(Motiva {
       config,
       index: provider,
       fetcher,
       catalog: Arc::new(RwLock::new(catalog)),
+      last_diff: Default::default(),
+      algorithms: AlgorithmRegistry::default(),
     })
   }
 
@@ -148,13 +228,15 @@ impl<P: IndexProvider> Motiva<P> {
 
     provider.after_init();
 
-    let catalog = init_catalog(&fetcher, &provider, config.outdated_grace).await;
+    let catalog = init_catalog(&fetcher, &provider, config.outdated_grace).await?;
 
     Ok(Motiva {
       config,
       index: provider,
       fetcher,
       catalog: Arc::new(RwLock::new(catalog)),
+      last_diff: Default::default(),
+      algorithms: AlgorithmRegistry::default(),
     })
   }
 }
@@ -169,13 +251,15 @@ impl<P: IndexProvider> Motiva<P, TestFetcher> {
   ) -> Result<Motiva<P, TestFetcher>, MotivaError> {
     crate::init();
 
-    let catalog = init_catalog(&fetcher, &provider, config.outdated_grace).await;
+    let catalog = init_catalog(&fetcher, &provider, config.outdated_grace).await?;
 
     Ok(Motiva::<P, _> {
       config,
       index: provider,
       fetcher,
       catalog: Arc::new(RwLock::new(catalog)),
+      last_diff: Default::default(),
+      algorithms: AlgorithmRegistry::default(),
     })
   }
 }
@@ -215,8 +299,49 @@ impl<P: IndexProvider, F: CatalogFetcher> Motiva<P, F> {
   }
 
   /// Perform an entity search and return the candidates.
+  ///
+  /// Entities listed in `exclude_entity_ids` (through their own ID or one of
+  /// their referents) are never returned, even if the index did not honor the
+  /// exclusion. With `filter_schemas`, entities of a schema the query cannot
+  /// match are dropped as well, in case the index did not restrict them.
   pub async fn search(&self, entity: &SearchEntity, params: &MatchParams) -> Result<Vec<Entity>, MotivaError> {
-    self.index.search(&self.catalog, entity, params).await
+    params.validate_field_mapping()?;
+
+    let entity = entity.precomputed();
+    let mut hits = self.index.search(&self.catalog, &entity, &self.search_params(params)).await?;
+
+    if !params.exclude_entity_ids.is_empty() {
+      hits.retain(|hit| !params.exclude_entity_ids.iter().any(|id| hit.id == *id || hit.referents.contains(id)));
+    }
+
+    if params.filter_schemas {
+      hits.retain(|hit| entity.can_match(&hit.schema));
+    }
+
+    Ok(hits)
+  }
+
+  /// Count the entities a search in `scope` could return, given the dataset,
+  /// topic and exclusion filters of `params`.
+  pub async fn count(&self, scope: &str, params: &MatchParams) -> Result<u64, MotivaError> {
+    self.index.count(&self.catalog, scope, params).await
+  }
+
+  /// Fill in search parameters left unset by the caller from the configuration.
+  fn search_params<'p>(&self, params: &'p MatchParams) -> Cow<'p, MatchParams> {
+    if params.search_type.is_some() && params.caption_preference.is_some() && (params.seed.is_some() || self.config.seed.is_none()) {
+      return Cow::Borrowed(params);
+    }
+
+    Cow::Owned(MatchParams {
+      search_type: params.search_type.or(Some(self.config.search_type)),
+      seed: params.seed.or(self.config.seed),
+      caption_preference: params
+        .caption_preference
+        .or_else(|| params.prefer_lang.as_deref().and_then(script_for_lang).map(CaptionPreference::Script))
+        .or(Some(self.config.caption_preference)),
+      ..params.clone()
+    })
   }
 
   /// Get an entity from its ID.
@@ -239,20 +364,145 @@ impl<P: IndexProvider, F: CatalogFetcher> Motiva<P, F> {
       EntityHandle::Referent(id) => Ok(EntityHandle::Referent(id)),
 
       EntityHandle::Nominal(mut entity) => {
-        if let GetEntityBehavior::RootOnly = behavior {
+        let GetEntityBehavior::FetchNestedEntity(expansion) = behavior else {
           return Ok(EntityHandle::Nominal(entity));
-        }
+        };
 
-        fetch_nested_entities(&self.index, limits, &mut entity, id).await?;
+        fetch_nested_entities(&self.index, limits, expansion, &mut entity, id).await?;
 
         Ok(EntityHandle::Nominal(entity))
       }
     }
   }
 
+  /// Resolve the threshold and cutoff to apply to results for this query.
+  ///
+  /// See [`MotivaConfig::thresholds`] for the resolution order.
+  pub fn thresholds(&self, entity: &SearchEntity, params: &MatchParams) -> (f64, f64) {
+    self.config.thresholds(entity.schema.as_str(), params)
+  }
+
   /// Perform the scoring of all candidates against the search parameters.
+  ///
+  /// The query is precomputed first if it was not.
   pub fn score<A: MatchingAlgorithm>(&self, entity: &SearchEntity, hits: Vec<Entity>, options: &ScoringOptions) -> anyhow::Result<Vec<(Entity, f64)>> {
-    scoring::score::<A>(entity, hits, options)
+    scoring::score::<A>(&entity.precomputed(), hits, options)
+  }
+
+  /// Register a custom matching algorithm.
+  ///
+  /// The algorithm can then be selected with
+  /// [`MatchParams::custom_algorithm`], using the name it reports. Registering
+  /// another algorithm under the same name replaces the previous one.
+  /// Registered algorithms are shared between all clones of this instance.
+  pub fn register_algorithm(&self, algorithm: impl DynMatchingAlgorithm + 'static) {
+    self.algorithms.register(Arc::new(algorithm));
+  }
+
+  /// Names of the custom matching algorithms registered on this instance.
+  pub fn custom_algorithms(&self) -> Vec<String> {
+    self.algorithms.names()
+  }
+
+  /// Look up the custom matching algorithm registered under `name`.
+  pub fn custom_algorithm(&self, name: &str) -> Option<ScoringAlgorithm> {
+    self.algorithms.get(name).map(ScoringAlgorithm::Custom)
+  }
+
+  /// Resolve the algorithm selected by search parameters.
+  ///
+  /// This is the custom algorithm named by
+  /// [`MatchParams::custom_algorithm`] if one is set, or `None` if it was not
+  /// registered, and the built-in [`MatchParams::algorithm`] otherwise.
+  pub fn requested_algorithm(&self, params: &MatchParams) -> Option<ScoringAlgorithm> {
+    match &params.custom_algorithm {
+      Some(name) => self.custom_algorithm(name),
+      None => Some(params.algorithm.into()),
+    }
+  }
+
+  /// Perform the scoring of all candidates with an algorithm selected at runtime.
+  ///
+  /// This dispatches to [`Motiva::score`] for built-in algorithms, and to the
+  /// registered algorithm for custom ones.
+  pub fn score_algorithm(&self, algorithm: &ScoringAlgorithm, entity: &SearchEntity, hits: Vec<Entity>, options: &ScoringOptions) -> anyhow::Result<Vec<(Entity, f64)>> {
+    match algorithm {
+      ScoringAlgorithm::Builtin(Algorithm::NameBased) => self.score::<NameBased>(entity, hits, options),
+      ScoringAlgorithm::Builtin(Algorithm::NameQualified) => self.score::<NameQualified>(entity, hits, options),
+      ScoringAlgorithm::Builtin(Algorithm::MarbleV0) => self.score::<MarbleV0>(entity, hits, options),
+      ScoringAlgorithm::Builtin(Algorithm::LogicV1 | Algorithm::Best) => self.score::<LogicV1>(entity, hits, options),
+      ScoringAlgorithm::Custom(algorithm) => scoring::score_dyn(algorithm.as_ref(), &entity.precomputed(), hits, options),
+    }
+  }
+
+  /// Score two indexed entities against each other.
+  ///
+  /// The `left` entity is used as the query, and the `right` one is returned
+  /// along with its score and feature breakdown. Entities merged into another
+  /// one are resolved to their canonical entity.
+  pub async fn compare(&self, left: &str, right: &str, algorithm: &ScoringAlgorithm, normalization: &QueryNormalization, options: &ScoringOptions) -> Result<(Entity, f64), MotivaError> {
+    let left = self.get_canonical_entity(left).await?;
+    let right = self.get_canonical_entity(right).await?;
+
+    let values = left
+      .properties
+      .strings
+      .iter()
+      .map(|(prop, values)| (prop.as_str(), values.iter().map(String::as_str).collect::<Vec<_>>()))
+      .collect::<Vec<_>>();
+    let properties = values.iter().map(|(prop, values)| (*prop, values.as_slice())).collect::<Vec<_>>();
+
+    let query = SearchEntity::builder(left.schema.as_str())
+      .id(&left.id)
+      .properties(&properties)
+      .phonetic_algorithm(normalization.phonetic_algorithm)
+      .name_replacers(normalization.name_replacers.clone())
+      .ignore_generational_suffixes(normalization.ignore_generational_suffixes)
+      .build();
+
+    self.score_algorithm(algorithm, &query, vec![right], options)?.into_iter().next().ok_or(MotivaError::ResourceNotFound)
+  }
+
+  /// Get an entity without its related entities, following referents.
+  async fn get_canonical_entity(&self, id: &str) -> Result<Entity, MotivaError> {
+    match self.index.get_entity(id).await? {
+      EntityHandle::Nominal(entity) => Ok(*entity),
+      EntityHandle::Referent(canonical) => match self.index.get_entity(&canonical).await? {
+        EntityHandle::Nominal(entity) => Ok(*entity),
+        EntityHandle::Referent(_) => Err(MotivaError::ResourceNotFound),
+      },
+    }
+  }
+
+  /// Score all candidates and apply the query's threshold, cutoff and limit.
+  ///
+  /// Candidates scoring under the cutoff are dropped, the remaining ones are
+  /// ordered by descending score (ties are broken by entity ID, for stable
+  /// ordering) and truncated to `limit`. Each result is returned along with
+  /// whether it scored above the threshold.
+  ///
+  /// Features are weighted with the algorithm's default weights.
+  pub fn score_with_params<A: MatchingAlgorithm>(&self, entity: &SearchEntity, hits: Vec<Entity>, params: &MatchParams) -> anyhow::Result<Vec<(Entity, f64, bool)>> {
+    let (threshold, cutoff) = self.thresholds(entity, params);
+
+    let options = ScoringOptions {
+      cutoff,
+      explain: params.explain,
+      penalize_missing: params.penalize_missing.clone(),
+      escalate_mismatches: params.escalate_mismatches,
+      ..Default::default()
+    };
+
+    let results = self
+      .score::<A>(entity, hits, &options)?
+      .into_iter()
+      .filter(|(_, score)| *score >= cutoff)
+      .sorted_by(|(lhs, lscore), (rhs, rscore)| lscore.total_cmp(rscore).reverse().then_with(|| lhs.id.cmp(&rhs.id)))
+      .take(params.limit)
+      .map(|(entity, score)| (entity, score, is_match(score, threshold)))
+      .collect();
+
+    Ok(results)
   }
 
   /// Refresh the local catalog from upstream.
@@ -262,13 +512,56 @@ impl<P: IndexProvider, F: CatalogFetcher> Motiva<P, F> {
   pub async fn refresh_catalog(&self) {
     match get_merged_catalog(&self.fetcher, &self.index, self.config.outdated_grace).await {
       Ok(catalog) => {
-        *self.catalog.write().await = catalog;
+        let mut current = self.catalog.write().await;
+
+        // The diff is computed under the catalog's guard, so it matches the catalog it was replaced with.
+        *self.last_diff.write().await = CatalogDiff::between(&current, &catalog);
+        *current = catalog;
       }
 
       Err(err) => tracing::warn!(error = err.to_string(), "could not refresh catalog"),
     }
   }
 
+  /// Refresh a single dataset of the local catalog from upstream.
+  ///
+  /// Only the upstream catalogs up to the one declaring the dataset are
+  /// fetched, and the dataset is updated in place in the cached catalog.
+  /// Returns [`MotivaError::ResourceNotFound`] if the dataset is not declared
+  /// upstream.
+  pub async fn refresh_dataset(&self, name: &str) -> Result<(), MotivaError> {
+    let Some((dataset, status)) = get_merged_dataset(&self.fetcher, &self.index, self.config.outdated_grace, name).await? else {
+      return Err(MotivaError::ResourceNotFound);
+    };
+
+    self.catalog.write().await.replace_dataset(dataset, status);
+
+    Ok(())
+  }
+
+  /// Changes brought to the catalog by the latest refresh.
+  ///
+  /// The diff is empty if the catalog was never refreshed. Datasets refreshed
+  /// individually through [`Motiva::refresh_dataset`] are not reflected.
+  pub async fn catalog_diff(&self) -> CatalogDiff {
+    self.last_diff.read().await.clone()
+  }
+
+  /// Datasets that need to be reindexed.
+  ///
+  /// Returns the name, currently indexed version and expected upstream
+  /// version of every outdated dataset in the cached catalog.
+  pub async fn outdated_datasets(&self) -> Vec<(String, Option<String>, String)> {
+    let catalog = self.catalog.read().await;
+
+    catalog
+      .outdated
+      .iter()
+      .filter_map(|name| catalog.datasets.iter().find(|ds| &ds.name == name))
+      .map(|ds| (ds.name.clone(), ds.index_version.clone(), ds.version.clone()))
+      .collect()
+  }
+
   /// Return the merged catalog.
   ///
   /// By default, returns the cached merged dataset from the latest pull.
@@ -299,9 +592,13 @@ impl<P: IndexProvider, F: CatalogFetcher> Motiva<P, F> {
 mod tests {
   use std::collections::HashMap;
 
+  use jiff::civil::DateTime;
+
   use crate::{
-    Catalog, CatalogDataset, MockedElasticsearch, Motiva, TestFetcher,
+    Algorithm, Catalog, CatalogDataset, DatasetVersionChange, Entity, MatchParams, MockedElasticsearch, Motiva, MotivaConfig, ScoringOptions, SearchEntity, SearchType, TestFetcher,
     catalog::{Manifest, ManifestCatalog},
+    error::MotivaError,
+    motiva::SchemaDefaults,
   };
 
   #[tokio::test]
@@ -339,6 +636,155 @@ mod tests {
     motiva.refresh_catalog().await;
   }
 
+  #[tokio::test]
+  async fn reject_invalid_manifest() {
+    let fetcher = TestFetcher {
+      manifest: Manifest {
+        catalogs: vec![ManifestCatalog {
+          url: " ".to_string(),
+          ..Default::default()
+        }],
+        ..Default::default()
+      },
+      catalogs: HashMap::default(),
+    };
+
+    let index = MockedElasticsearch::builder().healthy(true).build();
+
+    assert!(matches!(Motiva::custom(index).fetcher(fetcher).build().await, Err(MotivaError::ConfigError(_))));
+  }
+
+  #[tokio::test]
+  async fn catalog_refresh_diff() {
+    let catalog = |datasets: &[(&str, &str)]| Catalog {
+      datasets: datasets
+        .iter()
+        .map(|(name, version)| CatalogDataset {
+          name: name.to_string(),
+          version: version.to_string(),
+          ..Default::default()
+        })
+        .collect(),
+      ..Default::default()
+    };
+
+    let fetcher = TestFetcher {
+      manifest: Manifest {
+        catalogs: vec![ManifestCatalog {
+          url: "dummyurl".to_string(),
+          ..Default::default()
+        }],
+        ..Default::default()
+      },
+      catalogs: HashMap::from([("dummyurl".to_string(), catalog(&[("dataset1", "v1"), ("dataset2", "v1")]))]),
+    };
+
+    let index = MockedElasticsearch::builder().healthy(true).build();
+    let mut motiva = Motiva::custom(index).fetcher(fetcher).build().await.unwrap();
+
+    assert!(motiva.catalog_diff().await.is_empty());
+
+    motiva.fetcher.catalogs.insert("dummyurl".to_string(), catalog(&[("dataset1", "v2"), ("dataset3", "v1")]));
+    motiva.refresh_catalog().await;
+
+    let diff = motiva.catalog_diff().await;
+
+    assert_eq!(diff.added, vec!["dataset3"]);
+    assert_eq!(diff.removed, vec!["dataset2"]);
+    assert_eq!(
+      diff.updated,
+      vec![DatasetVersionChange {
+        name: "dataset1".to_string(),
+        previous: "v1".to_string(),
+        current: "v2".to_string(),
+      }]
+    );
+
+    motiva.fetcher.catalogs.insert("dummyurl".to_string(), catalog(&[("dataset1", "v3"), ("dataset3", "v1")]));
+    motiva.refresh_dataset("dataset1").await.unwrap();
+
+    assert_eq!(motiva.catalog_diff().await, diff);
+  }
+
+  #[tokio::test]
+  async fn refresh_single_dataset() {
+    let catalog = |version: &str| Catalog {
+      datasets: ["dataset1", "dataset2"]
+        .iter()
+        .map(|name| CatalogDataset {
+          name: name.to_string(),
+          version: version.to_string(),
+          ..Default::default()
+        })
+        .collect(),
+      ..Default::default()
+    };
+
+    let fetcher = TestFetcher {
+      manifest: Manifest {
+        catalogs: vec![ManifestCatalog {
+          url: "dummyurl".to_string(),
+          ..Default::default()
+        }],
+        ..Default::default()
+      },
+      catalogs: HashMap::from([("dummyurl".to_string(), catalog("v1"))]),
+    };
+
+    let index = MockedElasticsearch::builder().healthy(true).build();
+    let mut motiva = Motiva::custom(index).fetcher(fetcher).build().await.unwrap();
+
+    motiva.fetcher.catalogs.insert("dummyurl".to_string(), catalog("v2"));
+    motiva.refresh_dataset("dataset1").await.unwrap();
+
+    let catalog = motiva.catalog.read().await.clone();
+    let version = |name: &str| catalog.datasets.iter().find(|ds| ds.name == name).map(|ds| ds.version.clone());
+
+    assert_eq!(catalog.datasets.len(), 2);
+    assert_eq!(version("dataset1").as_deref(), Some("v2"));
+    assert_eq!(version("dataset2").as_deref(), Some("v1"));
+    assert_eq!(catalog.loaded_datasets["dataset1"].version, "v2");
+
+    assert!(matches!(motiva.refresh_dataset("unknown").await, Err(crate::MotivaError::ResourceNotFound)));
+  }
+
+  #[tokio::test]
+  async fn outdated_datasets() {
+    let dataset = |name: &str| CatalogDataset {
+      name: name.to_string(),
+      version: "20251125100000-pop".to_string(),
+      last_export: Some(DateTime::constant(2025, 11, 25, 10, 0, 0, 0)),
+      ..Default::default()
+    };
+
+    let fetcher = TestFetcher {
+      manifest: Manifest {
+        catalogs: vec![ManifestCatalog {
+          url: "dummyurl".to_string(),
+          ..Default::default()
+        }],
+        ..Default::default()
+      },
+      catalogs: HashMap::from([(
+        "dummyurl".to_string(),
+        Catalog {
+          datasets: vec![dataset("dataset1"), dataset("dataset2"), dataset("dataset3")],
+          ..Default::default()
+        },
+      )]),
+    };
+
+    let indices = vec![("dataset1".to_string(), "20251125100000-pop".to_string()), ("dataset2".to_string(), "20251101100000-pop".to_string())];
+
+    let index = MockedElasticsearch::builder().healthy(true).indices(indices).build();
+    let motiva = Motiva::custom(index).fetcher(fetcher).build().await.unwrap();
+
+    assert_eq!(
+      motiva.outdated_datasets().await,
+      vec![("dataset2".to_string(), Some("20251101100000-pop".to_string()), "20251125100000-pop".to_string())]
+    );
+  }
+
   #[tokio::test]
   async fn ready_and_refresh_passthrough() {
     let index = MockedElasticsearch::builder().ready(false).build();
@@ -361,4 +807,341 @@ mod tests {
 
     assert!(motiva.get_catalog(false).await.unwrap().datasets.is_empty());
   }
+
+  #[tokio::test]
+  async fn compare_entities() {
+    let index = MockedElasticsearch::builder()
+      .healthy(true)
+      .entities(vec![
+        Entity::builder("Person").id("left").properties(&[("name", &["Vladimir Putin"])]).build(),
+        Entity::builder("Person").id("right").properties(&[("name", &["Vladimir Putin"])]).build(),
+      ])
+      .build();
+
+    let motiva = Motiva::test(index).build().await.unwrap();
+    let options = ScoringOptions { explain: true, ..Default::default() };
+
+    let (entity, score) = motiva.compare("left", "right", &Algorithm::LogicV1.into(), &Default::default(), &options).await.unwrap();
+
+    assert_eq!(entity.id, "right");
+    assert_eq!(score, 1.0);
+    assert!(!entity.features.is_empty());
+    assert!(!entity.explanations.is_empty());
+
+    assert!(matches!(
+      motiva.compare("left", "missing", &Algorithm::LogicV1.into(), &Default::default(), &options).await,
+      Err(MotivaError::ResourceNotFound)
+    ));
+  }
+
+  #[tokio::test]
+  async fn thresholds_resolution_order() {
+    let mut schema_defaults = HashMap::default();
+    schema_defaults.insert(
+      "Vessel".to_string(),
+      SchemaDefaults {
+        threshold: Some(0.9),
+        cutoff: Some(0.8),
+      },
+    );
+
+    let config = MotivaConfig {
+      schema_defaults,
+      ..Default::default()
+    };
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).config(config).build().await.unwrap();
+    let person = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let vessel = SearchEntity::builder("Vessel").properties(&[("name", &["Black Pearl"])]).build();
+
+    assert_eq!(motiva.thresholds(&person, &MatchParams::default()), (0.7, 0.5));
+    assert_eq!(motiva.thresholds(&vessel, &MatchParams::default()), (0.9, 0.8));
+
+    let params = MatchParams {
+      threshold: Some(0.6),
+      ..Default::default()
+    };
+
+    assert_eq!(motiva.thresholds(&person, &params), (0.6, 0.5));
+    assert_eq!(motiva.thresholds(&vessel, &params), (0.6, 0.8));
+
+    let params = MatchParams {
+      threshold: Some(0.95),
+      cutoff: Some(0.1),
+      ..Default::default()
+    };
+
+    assert_eq!(motiva.thresholds(&person, &params), (0.95, 0.1));
+    assert_eq!(motiva.thresholds(&vessel, &params), (0.95, 0.1));
+  }
+
+  #[tokio::test]
+  async fn search_type_from_config() {
+    let config = MotivaConfig {
+      search_type: SearchType::QueryThenFetch,
+      ..Default::default()
+    };
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).config(config).build().await.unwrap();
+
+    assert_eq!(motiva.search_params(&MatchParams::default()).search_type, Some(SearchType::QueryThenFetch));
+
+    let params = MatchParams {
+      search_type: Some(SearchType::DfsQueryThenFetch),
+      ..Default::default()
+    };
+
+    assert_eq!(motiva.search_params(&params).search_type, Some(SearchType::DfsQueryThenFetch));
+  }
+
+  #[tokio::test]
+  async fn seed_from_config() {
+    let config = MotivaConfig { seed: Some(42), ..Default::default() };
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).config(config).build().await.unwrap();
+
+    assert_eq!(motiva.search_params(&MatchParams::default()).seed, Some(42));
+
+    let params = MatchParams { seed: Some(7), ..Default::default() };
+
+    assert_eq!(motiva.search_params(&params).seed, Some(7));
+  }
+
+  #[tokio::test]
+  async fn prefer_lang_caption() {
+    use whatlang::Script;
+
+    use crate::CaptionPreference;
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).build().await.unwrap();
+
+    assert_eq!(motiva.search_params(&MatchParams::default()).caption_preference, Some(CaptionPreference::Longest));
+
+    let params = |lang: &str| MatchParams {
+      prefer_lang: Some(lang.to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(motiva.search_params(&params("en")).caption_preference, Some(CaptionPreference::Script(Script::Latin)));
+    assert_eq!(motiva.search_params(&params("ru")).caption_preference, Some(CaptionPreference::Script(Script::Cyrillic)));
+    assert_eq!(motiva.search_params(&params("unknown")).caption_preference, Some(CaptionPreference::Longest));
+  }
+
+  #[tokio::test]
+  async fn search_excluded_ids() {
+    use crate::Entity;
+
+    let entities = vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("NK-1").properties(&[("name", &["Vladimir Putin"])]).build(),
+    ];
+
+    let motiva = Motiva::test(MockedElasticsearch::builder().entities(entities).build()).build().await.unwrap();
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let hits = motiva.search(&entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits.len(), 2);
+
+    let params = MatchParams {
+      exclude_entity_ids: vec!["Q7747".to_string()],
+      ..Default::default()
+    };
+
+    let hits = motiva.search(&entity, &params).await.unwrap();
+
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, "NK-1");
+  }
+
+  #[tokio::test]
+  async fn search_precomputes_entity() {
+    let entities = vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"]), ("country", &["ru"])]).build()];
+
+    let index = MockedElasticsearch::builder().entities(entities).build();
+    let motiva = Motiva::test(index.clone()).build().await.unwrap();
+
+    let manual: SearchEntity = serde_json::from_value(serde_json::json!({ "schema": "Person", "properties": { "name": ["Vladimir Putin"], "country": ["Russia"] } })).unwrap();
+    let built = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"]), ("country", &["Russia"])]).build();
+
+    assert!(!manual.is_precomputed());
+    assert!(built.is_precomputed());
+
+    let hits = motiva.search(&manual, &MatchParams::default()).await.unwrap();
+
+    assert!(index.calls().search[0].0.is_precomputed());
+
+    let manual_scores = motiva.score_with_params::<crate::LogicV1>(&manual, hits.clone(), &MatchParams::default()).unwrap();
+    let built_scores = motiva.score_with_params::<crate::LogicV1>(&built, hits, &MatchParams::default()).unwrap();
+
+    assert_eq!(manual_scores[0].1, built_scores[0].1);
+    assert!(manual_scores[0].2);
+  }
+
+  #[tokio::test]
+  async fn search_precomputes_identifier_only_entity() {
+    let index = MockedElasticsearch::builder().build();
+    let motiva = Motiva::test(index.clone()).build().await.unwrap();
+
+    let manual: SearchEntity = serde_json::from_value(serde_json::json!({ "schema": "Company", "properties": { "registrationNumber": [" 12-34 56 "], "leiCode": ["529900T8BM49AURSDO55"] } })).unwrap();
+
+    assert!(!manual.is_precomputed());
+
+    motiva.search(&manual, &MatchParams::default()).await.unwrap();
+
+    let searched = &index.calls().search[0].0;
+
+    assert!(searched.is_precomputed());
+    assert!(!searched.org_identifiers.is_empty());
+    assert_eq!(searched.properties["registrationNumber"], ["12-34 56"]);
+  }
+
+  #[tokio::test]
+  async fn search_filter_schemas() {
+    use crate::Entity;
+
+    let entities = vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Vessel").id("IMO-1").properties(&[("name", &["Vladimir Putin"])]).build(),
+    ];
+
+    let motiva = Motiva::test(MockedElasticsearch::builder().entities(entities).build()).build().await.unwrap();
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let hits = motiva.search(&entity, &MatchParams::default()).await.unwrap();
+
+    assert_eq!(hits.len(), 2);
+
+    let params = MatchParams {
+      filter_schemas: true,
+      ..Default::default()
+    };
+
+    let hits = motiva.search(&entity, &params).await.unwrap();
+
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, "Q7747");
+  }
+
+  #[tokio::test]
+  async fn score_with_params() {
+    use crate::{Entity, NameBased};
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).build().await.unwrap();
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let hits = vec![
+      Entity::builder("Person").id("C").properties(&[("name", &["Xi Jinping"])]).build(),
+      Entity::builder("Person").id("B").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("D").properties(&[("name", &["Vladimir Petrov"])]).build(),
+    ];
+
+    let params = MatchParams {
+      threshold: Some(0.99),
+      cutoff: Some(0.3),
+      limit: 2,
+      ..Default::default()
+    };
+
+    let results = motiva.score_with_params::<NameBased>(&entity, hits.clone(), &params).unwrap();
+
+    assert_eq!(
+      results.iter().map(|(entity, _, matched)| (entity.id.as_str(), *matched)).collect::<Vec<_>>(),
+      vec![("A", true), ("B", true)]
+    );
+
+    let params = MatchParams {
+      threshold: Some(0.99),
+      cutoff: Some(0.3),
+      limit: 5,
+      ..Default::default()
+    };
+
+    let results = motiva.score_with_params::<NameBased>(&entity, hits, &params).unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2].0.id, "D");
+    assert!(!results[2].2);
+  }
+
+  #[tokio::test]
+  async fn threshold_is_inclusive() {
+    use crate::{Entity, NameBased};
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).build().await.unwrap();
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let hits = vec![Entity::builder("Person").id("A").properties(&[("name", &["Vladimir Petrov"])]).build()];
+
+    let params = MatchParams {
+      cutoff: Some(0.0),
+      ..Default::default()
+    };
+    let score = motiva.score_with_params::<NameBased>(&entity, hits.clone(), &params).unwrap()[0].1;
+
+    assert!(score > 0.0 && score < 1.0);
+
+    let params = MatchParams {
+      threshold: Some(score),
+      cutoff: Some(0.0),
+      ..Default::default()
+    };
+    let results = motiva.score_with_params::<NameBased>(&entity, hits.clone(), &params).unwrap();
+
+    assert_eq!(results[0].1, score);
+    assert!(results[0].2);
+
+    let params = MatchParams {
+      threshold: Some(score + f64::EPSILON),
+      cutoff: Some(0.0),
+      ..Default::default()
+    };
+    let results = motiva.score_with_params::<NameBased>(&entity, hits, &params).unwrap();
+
+    assert!(!results[0].2);
+  }
+
+  #[tokio::test]
+  async fn custom_algorithm() {
+    use bumpalo::Bump;
+
+    use crate::{DynMatchingAlgorithm, Entity, Explanation, MatchParams, ScoringOptions};
+
+    struct ShortIds;
+
+    impl DynMatchingAlgorithm for ShortIds {
+      fn name(&self) -> &str {
+        "short-ids"
+      }
+
+      fn score(&self, _: &Bump, _: &SearchEntity, rhs: &Entity, _: &ScoringOptions) -> (f64, Vec<Explanation>) {
+        (1.0 / rhs.id.len() as f64, vec![])
+      }
+    }
+
+    let motiva = Motiva::test(MockedElasticsearch::default()).build().await.unwrap();
+    let params = MatchParams {
+      custom_algorithm: Some("short-ids".to_string()),
+      ..Default::default()
+    };
+
+    assert!(motiva.custom_algorithm("short-ids").is_none());
+    assert!(motiva.requested_algorithm(&params).is_none());
+    assert_eq!(motiva.requested_algorithm(&MatchParams::default()).unwrap().name(), "logic-v1");
+
+    motiva.clone().register_algorithm(ShortIds);
+
+    assert_eq!(motiva.custom_algorithms(), vec!["short-ids"]);
+
+    let algorithm = motiva.requested_algorithm(&params).unwrap();
+
+    assert_eq!(algorithm.name(), "short-ids");
+
+    let entity = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let hits = vec![Entity::builder("Person").id("A").build(), Entity::builder("Person").id("BB").build()];
+
+    let results = motiva.score_algorithm(&algorithm, &entity, hits, &Default::default()).unwrap();
+
+    assert_eq!(results.iter().map(|(entity, score)| (entity.id.as_str(), *score)).collect::<Vec<_>>(), vec![("A", 1.0), ("BB", 0.5)]);
+  }
 }
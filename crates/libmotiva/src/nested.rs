@@ -6,9 +6,14 @@ use std::{
 use ahash::{HashMap, RandomState};
 use itertools::Itertools;
 
-use crate::{Entity, IndexProvider, MotivaError, model::HasProperties, motiva::GetEntityLimits, schemas::SCHEMAS};
+use crate::{
+  Entity, IndexProvider, MotivaError,
+  model::HasProperties,
+  motiva::{EdgeExpansion, GetEntityLimits},
+  schemas::SCHEMAS,
+};
 
-pub(crate) async fn fetch_nested_entities<P: IndexProvider>(index: &P, limits: GetEntityLimits, root_entity: &mut Entity, root_id: &str) -> Result<(), MotivaError> {
+pub(crate) async fn fetch_nested_entities<P: IndexProvider>(index: &P, limits: GetEntityLimits, expansion: EdgeExpansion, root_entity: &mut Entity, root_id: &str) -> Result<(), MotivaError> {
   let mut all_entities: HashMap<String, Arc<Mutex<Entity>>> = HashMap::default();
   let mut seen = HashSet::<_, RandomState>::from_iter([root_id.to_string()]);
   let mut queue: HashSet<(String, String, String), RandomState> = HashSet::default();
@@ -50,7 +55,13 @@ pub(crate) async fn fetch_nested_entities<P: IndexProvider>(index: &P, limits: G
       link_entity_to_parents(root_entity, &all_entities, &queue, root_id, &association, &node);
       link_reverse_properties(root_entity, &all_entities, &association, schema, &node);
 
-      if iteration == 0 || association.schema.is_edge() {
+      let expand = match expansion {
+        EdgeExpansion::EdgesOnly => iteration == 0 || association.schema.is_edge(),
+        EdgeExpansion::Always => true,
+        EdgeExpansion::Never => false,
+      };
+
+      if expand {
         queue_entity_references(&association, schema, &seen, &mut next);
       }
     }
@@ -147,14 +158,19 @@ fn queue_entity_references(association: &Entity, schema: &crate::schemas::FtmSch
 mod tests {
   use std_macro_extensions::{hash_set, string};
 
-  use crate::{Entity, MockedElasticsearch, motiva::GetEntityLimits};
+  use crate::{
+    Entity, MockedElasticsearch,
+    motiva::{EdgeExpansion, GetEntityLimits},
+  };
 
   #[tokio::test]
   async fn no_references() {
     let mut root = Entity::builder("Person").id("person-1").build();
     let index = MockedElasticsearch::builder().build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.is_empty());
   }
@@ -168,7 +184,9 @@ mod tests {
       .related_entitites(vec![((Some(string!("person-1")), vec![string!("wizard-1")], hash_set!(string!("person-1"))), vec![wizard.clone()])])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(!root.properties.entities.contains_key("addressEntity"));
   }
@@ -182,7 +200,9 @@ mod tests {
       .related_entitites(vec![((Some(string!("person-1")), vec![string!("addr-1")], hash_set!(string!("person-1"))), vec![address.clone()])])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("addressEntity"));
 
@@ -204,7 +224,9 @@ mod tests {
       )])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("addressEntity"));
     let addresses = &root.properties.entities["addressEntity"];
@@ -228,7 +250,9 @@ mod tests {
       ])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("familyRelative"));
     let relatives = &root.properties.entities["familyRelative"];
@@ -258,7 +282,9 @@ mod tests {
       ])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("familyRelative"));
     let relatives = &root.properties.entities["familyRelative"];
@@ -283,7 +309,9 @@ mod tests {
       )])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "company-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "company-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("parent"));
     let parents = &root.properties.entities["parent"];
@@ -307,7 +335,9 @@ mod tests {
       ])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("proof"));
     let proof = &root.properties.entities["proof"];
@@ -327,7 +357,9 @@ mod tests {
       .related_entitites(vec![((Some(string!("person-1")), vec![string!("company-missing")], hash_set!(string!("person-1"))), vec![])])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.is_empty());
   }
@@ -340,7 +372,9 @@ mod tests {
       .related_entitites(vec![((Some(string!("company-1")), vec![string!("company-1")], hash_set!(string!("company-1"))), vec![])])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "company-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "company-1")
+      .await
+      .unwrap();
 
     assert!(!root.properties.entities.contains_key("parent"));
   }
@@ -362,7 +396,9 @@ mod tests {
       ])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     let relatives = root.properties.entities.get("familyRelative").expect("root should have familyRelative entities");
 
@@ -390,11 +426,81 @@ mod tests {
       .related_entitites(vec![((Some(string!("person-1")), vec![string!("addr-1")], hash_set!(string!("person-1"))), vec![address.clone()])])
       .build();
 
-    super::fetch_nested_entities(&index, GetEntityLimits::default(), &mut root, "person-1").await.unwrap();
+    super::fetch_nested_entities(&index, GetEntityLimits::default(), EdgeExpansion::default(), &mut root, "person-1")
+      .await
+      .unwrap();
 
     assert!(root.properties.entities.contains_key("addressEntity"));
     let addresses = &root.properties.entities["addressEntity"];
     assert_eq!(addresses.len(), 1);
     assert_eq!(addresses[0].lock().unwrap().id, "addr-1");
   }
+
+  fn expansion_fixture() -> (Entity, MockedElasticsearch) {
+    let root = Entity::builder("Person").id("person-1").build();
+    let relative = Entity::builder("Family").id("relative-1").properties(&[("relative", &["person-1"]), ("person", &["person-2"])]).build();
+    let person = Entity::builder("Person").id("person-2").properties(&[("addressEntity", &["addr-1"])]).build();
+    let address = Entity::builder("Address").id("addr-1").build();
+
+    let index = MockedElasticsearch::builder()
+      .related_entitites(vec![
+        ((Some(string!("person-1")), vec![], hash_set!(string!("person-1"))), vec![relative]),
+        ((None, vec![string!("person-2")], hash_set!(string!("person-1"), string!("relative-1"))), vec![person]),
+        (
+          (None, vec![string!("addr-1")], hash_set!(string!("person-1"), string!("relative-1"), string!("person-2"))),
+          vec![address],
+        ),
+      ])
+      .build();
+
+    (root, index)
+  }
+
+  fn relative_person(root: &Entity) -> Option<Entity> {
+    let relatives = &root.properties.entities["familyRelative"];
+    let relative = relatives[0].lock().unwrap();
+
+    relative.properties.entities.get("person").map(|people| people[0].lock().unwrap().clone())
+  }
+
+  #[tokio::test]
+  async fn expansion_edges_only() {
+    let (mut root, index) = expansion_fixture();
+
+    super::fetch_nested_entities(&index, GetEntityLimits::new(3, 200), EdgeExpansion::EdgesOnly, &mut root, "person-1")
+      .await
+      .unwrap();
+
+    // The Family edge is expanded to its other end, but not the person beyond it.
+    let person = relative_person(&root).expect("edge should be expanded");
+
+    assert_eq!(person.id, "person-2");
+    assert!(!person.properties.entities.contains_key("addressEntity"));
+  }
+
+  #[tokio::test]
+  async fn expansion_always() {
+    let (mut root, index) = expansion_fixture();
+
+    super::fetch_nested_entities(&index, GetEntityLimits::new(3, 200), EdgeExpansion::Always, &mut root, "person-1")
+      .await
+      .unwrap();
+
+    let person = relative_person(&root).expect("edge should be expanded");
+
+    assert_eq!(person.id, "person-2");
+    assert_eq!(person.properties.entities["addressEntity"][0].lock().unwrap().id, "addr-1");
+  }
+
+  #[tokio::test]
+  async fn expansion_never() {
+    let (mut root, index) = expansion_fixture();
+
+    super::fetch_nested_entities(&index, GetEntityLimits::new(3, 200), EdgeExpansion::Never, &mut root, "person-1")
+      .await
+      .unwrap();
+
+    assert_eq!(root.properties.entities["familyRelative"].len(), 1);
+    assert!(relative_person(&root).is_none());
+  }
 }
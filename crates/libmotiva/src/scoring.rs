@@ -9,7 +9,7 @@ use tokio::time::Instant;
 use tracing::{Span, instrument};
 
 use crate::{
-  matching::MatchingAlgorithm,
+  matching::{Explanation, MatchingAlgorithm, registry::DynMatchingAlgorithm},
   model::{Entity, SearchEntity},
 };
 
@@ -18,6 +18,17 @@ pub struct ScoringOptions {
   pub cutoff: f64,
   pub weights: HashMap<String, f64>,
   pub explain: bool,
+  /// Names of the features for which data set on the query but missing on the
+  /// candidate is penalized, instead of being neutral.
+  ///
+  /// Such features score half of their weight when the candidate lacks the property.
+  pub penalize_missing: Vec<String>,
+  /// Number of mismatch qualifiers from which their penalties escalate.
+  ///
+  /// When exactly that many mismatches fire on a result, their combined
+  /// penalty is doubled, tripled with one more mismatch, and so on. Disabled
+  /// when `None`.
+  pub escalate_mismatches: Option<usize>,
 }
 
 impl ScoringOptions {
@@ -26,8 +37,20 @@ impl ScoringOptions {
   }
 }
 
-#[instrument(name = "compute_scores", skip_all, fields(algorithm = A::name()))]
 pub fn score<A: MatchingAlgorithm>(entity: &SearchEntity, hits: Vec<Entity>, options: &ScoringOptions) -> anyhow::Result<Vec<(Entity, f64)>> {
+  score_with(A::name(), entity, hits, options, A::score)
+}
+
+/// Score candidates with an algorithm registered at runtime.
+pub(crate) fn score_dyn(algorithm: &dyn DynMatchingAlgorithm, entity: &SearchEntity, hits: Vec<Entity>, options: &ScoringOptions) -> anyhow::Result<Vec<(Entity, f64)>> {
+  score_with(algorithm.name(), entity, hits, options, |bump, lhs, rhs, options| algorithm.score(bump, lhs, rhs, options))
+}
+
+#[instrument(name = "compute_scores", skip_all, fields(algorithm = name))]
+fn score_with<S>(name: &str, entity: &SearchEntity, hits: Vec<Entity>, options: &ScoringOptions, scorer: S) -> anyhow::Result<Vec<(Entity, f64)>>
+where
+  S: Fn(&Bump, &SearchEntity, &Entity, &ScoringOptions) -> (f64, Vec<Explanation>),
+{
   let span = Span::current();
 
   let mut bump = Bump::with_capacity(1024);
@@ -38,13 +61,13 @@ pub fn score<A: MatchingAlgorithm>(entity: &SearchEntity, hits: Vec<Entity>, opt
     let then = Instant::now();
     let _enter = span.enter();
 
-    if !hit.schema.can_match(entity.schema.as_str()) {
+    if !entity.can_match(&hit.schema) {
       tracing::debug!(score = 0.0, "incomparable schemas, skipping");
 
       return (hit, 0.0);
     }
 
-    let (score, explanations) = A::score(&bump, entity, &hit, options);
+    let (score, explanations) = scorer(&bump, entity, &hit, options);
 
     hit.features = explanations.iter().filter(|e| e.score != 0.0).map(|e| (e.name, e.score)).collect();
 
@@ -86,6 +109,34 @@ mod tests {
     assert!(approx_eq!(f64, result[0].1, 0.0));
   }
 
+  #[test]
+  fn schema_union() {
+    let lhs = SearchEntity::builder("Person").schemas(&["Company"]).properties(&[("name", &["Ford"])]).build();
+    let person = Entity::builder("Person").id("person").properties(&[("name", &["Ford"])]).build();
+    let company = Entity::builder("Company").id("company").properties(&[("name", &["Ford"])]).build();
+    let vessel = Entity::builder("Vessel").id("vessel").properties(&[("name", &["Ford"])]).build();
+
+    let result = super::score::<LogicV1>(&lhs, vec![person, company, vessel], &Default::default()).unwrap();
+
+    assert_eq!(result.len(), 3);
+    assert!(result[0].1 > 0.0);
+    assert!(result[1].1 > 0.0);
+    assert!(approx_eq!(f64, result[2].1, 0.0));
+  }
+
+  #[test]
+  fn features_as_map() {
+    let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+    let rhs = Entity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
+
+    let result = super::score::<LogicV1>(&lhs, vec![rhs], &Default::default()).unwrap();
+    let features = result[0].0.features_map();
+
+    assert_eq!(features.len(), result[0].0.features.len());
+    assert_eq!(features.get("name_literal_match"), Some(&1.0));
+    assert_eq!(features.get("gender_mismatch"), None);
+  }
+
   #[test]
   fn explanations_are_opt_in() {
     let lhs = SearchEntity::builder("Person").properties(&[("name", &["Vladimir Putin"])]).build();
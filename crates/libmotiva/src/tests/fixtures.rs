@@ -0,0 +1,56 @@
+use crate::model::{Entity, SearchEntity};
+
+/// Number of candidates scored against the query of each throughput case.
+const THROUGHPUT_CANDIDATES: usize = 100;
+
+/// Person, company and vessel queries, each along with a batch of candidates
+/// of the same schema and varying names, to measure scoring throughput.
+pub fn throughput_cases() -> Vec<(&'static str, SearchEntity, Vec<Entity>)> {
+  vec![
+    (
+      "person",
+      SearchEntity::builder("Person")
+        .properties(&[("name", &["Vladimir Vladimirovich Putin"]), ("birthDate", &["1952-10-07"]), ("nationality", &["ru"])])
+        .build(),
+      candidates(
+        "Person",
+        &["Vladimir Putin", "Vladimir Poutine", "Wladimir Putin", "Vladimir Pudin", "Volodymyr Zelensky"],
+        &[("birthDate", &["1952-10-07"]), ("nationality", &["ru"])],
+      ),
+    ),
+    (
+      "company",
+      SearchEntity::builder("Company")
+        .properties(&[("name", &["Rosneft Oil Company PJSC"]), ("jurisdiction", &["ru"]), ("registrationNumber", &["1027700043502"])])
+        .build(),
+      candidates(
+        "Company",
+        &["Rosneft Oil Company", "Rosneft PJSC", "Rosneft Trading SA", "Gazprom Neft PJSC", "Rosneftegaz JSC"],
+        &[("jurisdiction", &["ru"]), ("registrationNumber", &["1027700043502"])],
+      ),
+    ),
+    (
+      "vessel",
+      SearchEntity::builder("Vessel")
+        .properties(&[("name", &["Ocean Voyager"]), ("imoNumber", &["IMO 9187629"]), ("flag", &["pa"])])
+        .build(),
+      candidates(
+        "Vessel",
+        &["Ocean Voyager", "Ocean Voyager II", "Oceanic Voyager", "Sea Voyager", "Arctic Explorer"],
+        &[("imoNumber", &["9187629"]), ("flag", &["pa"])],
+      ),
+    ),
+  ]
+}
+
+fn candidates(schema: &str, names: &[&str], properties: &[(&str, &[&str])]) -> Vec<Entity> {
+  (0..THROUGHPUT_CANDIDATES)
+    .map(|idx| {
+      let name = [names[idx % names.len()]];
+      let mut props = vec![("name", name.as_slice())];
+      props.extend_from_slice(properties);
+
+      Entity::builder(schema).properties(&props).build()
+    })
+    .collect()
+}
@@ -1,2 +1,3 @@
+pub mod fixtures;
 #[cfg(test)]
 pub(crate) mod python;
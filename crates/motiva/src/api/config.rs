@@ -8,41 +8,63 @@ use std::{
 
 use anyhow::Context;
 use jiff::Span;
-use libmotiva::{EsTlsVerification, GetEntityLimits, prelude::EsAuthMethod};
+use libmotiva::{CaptionPreference, EsFlavor, EsTlsVerification, GetEntityLimits, NameReplacers, PhoneticAlgorithm, SchemaDefaults, SearchType, prelude::EsAuthMethod};
 use tokio::net::TcpListener;
 
 use crate::api::errors::AppError;
 
 #[derive(Default, Debug)]
 pub struct Config {
-  pub env: Env,
   pub listen_addr: String,
   pub listener: Option<TcpListener>,
   pub api_key: Option<String>,
+  pub api_keys: HashMap<String, Vec<String>>,
 
   // Elasticsearch
   pub index_url: String,
   pub index_auth_method: EsAuthMethod,
   pub index_tls_verification: EsTlsVerification,
   pub index_name: Option<String>,
+  pub index_min_version: Option<String>,
+  pub index_flavor: EsFlavor,
 
   // Timeouts
   pub request_timeout: Span,
 
+  // Limits
+  /// Maximum size of request bodies, in bytes, unlimited if `None`.
+  pub max_body_size: Option<usize>,
+  /// Maximum number of results returned per query, unlimited if `None`.
+  pub max_limit: Option<usize>,
+  /// Match requests taking longer than this, in milliseconds, are logged, disabled if `None`.
+  pub slow_query_ms: Option<u64>,
+  /// Maximum number of queries of a single match request scored concurrently, unlimited if `None`.
+  pub match_concurrency: Option<usize>,
+
   // Match settings
   pub manifest_url: Option<String>,
+  pub catalog_user_agent: Option<String>,
+  pub catalog_headers: HashMap<String, String>,
   pub catalog_refresh_interval: Span,
   pub outdated_grace: Span,
   pub match_candidates: usize,
   pub weights: HashMap<String, f64>,
+  pub schema_defaults: HashMap<String, SchemaDefaults>,
+  pub search_type: SearchType,
+  pub caption_preference: CaptionPreference,
+  pub phonetic_algorithm: PhoneticAlgorithm,
+  pub name_replacers: NameReplacers,
+  pub ignore_generational_suffixes: bool,
 
   // Enrichment settings
   pub enrichment_max_recursion: usize,
   pub enrichment_query_limit: usize,
 
   // Observability
+  pub log_format: LogFormat,
   pub enable_prometheus: bool,
   pub enable_tracing: bool,
+  pub enable_explain_score: bool,
   pub tracing_exporter: TracingExporter,
   #[cfg(feature = "gcp")]
   pub gcp_project_id: String,
@@ -50,25 +72,43 @@ pub struct Config {
 
 impl Config {
   pub async fn from_env() -> Result<Config, AppError> {
+    let env = Env::from(env::var("ENV").unwrap_or("dev".into()));
+
     let config = Config {
-      env: Env::from(env::var("ENV").unwrap_or("dev".into())),
+      log_format: parse_env("LOG_FORMAT", LogFormat::default_for(&env))?,
       listen_addr: env::var("LISTEN_ADDR").unwrap_or("0.0.0.0:8000".into()),
       listener: None,
       api_key: env::var("API_KEY").ok(),
+      api_keys: parse_api_keys_from_env()?,
       match_candidates: parse_env("MATCH_CANDIDATES", 10)?,
       weights: parse_weights_from_env()?,
+      schema_defaults: parse_schema_defaults_from_env()?,
+      search_type: parse_env("SEARCH_TYPE", SearchType::default())?,
+      caption_preference: parse_env("CAPTION_PREFERENCE", CaptionPreference::default())?,
+      phonetic_algorithm: parse_env("PHONETIC_ALGORITHM", PhoneticAlgorithm::default())?,
+      name_replacers: parse_env("NAME_REPLACERS", NameReplacers::default())?,
+      ignore_generational_suffixes: env::var("IGNORE_GENERATIONAL_SUFFIXES").unwrap_or_default() == "1",
       manifest_url: env::var("MANIFEST_URL").ok(),
+      catalog_user_agent: env::var("CATALOG_USER_AGENT").ok().filter(|value| !value.is_empty()),
+      catalog_headers: parse_catalog_headers_from_env()?,
       request_timeout: parse_env("REQUEST_TIMEOUT", Span::from_str("10s").unwrap())?,
+      max_body_size: Some(parse_env("MAX_BODY_SIZE", 2 * 1024 * 1024)?).filter(|size| *size > 0),
+      max_limit: parse_optional_limit("MAX_LIMIT", 100)?,
+      slow_query_ms: Some(parse_env("SLOW_QUERY_MS", 0)?).filter(|ms| *ms > 0),
+      match_concurrency: Some(parse_env("MATCH_CONCURRENCY", 0)?).filter(|count| *count > 0),
       catalog_refresh_interval: parse_env("CATALOG_REFRESH_INTERVAL", Span::from_str("1h").unwrap())?,
-      outdated_grace: parse_env("OUTDATED_GRACE", Span::default())?,
+      outdated_grace: parse_non_negative_span("OUTDATED_GRACE", Span::default())?,
       index_url: env::var("INDEX_URL").unwrap_or("http://localhost:9200".into()),
       index_auth_method: env::var("INDEX_AUTH_METHOD").unwrap_or("none".into()).parse::<WrappedEsAuthMethod>()?.0,
       index_tls_verification: parse_index_tls_verification()?,
       index_name: env::var("INDEX_NAME").ok(),
+      index_min_version: env::var("INDEX_MIN_VERSION").ok(),
+      index_flavor: parse_env("INDEX_FLAVOR", EsFlavor::default())?,
       enrichment_max_recursion: parse_env("ENRICHMENT_MAX_RECURSION", GetEntityLimits::default().max_recursion)?,
       enrichment_query_limit: parse_env("ENRICHMENT_QUERY_LIMIT", GetEntityLimits::default().query_limit)?,
       enable_prometheus: env::var("ENABLE_PROMETHEUS").unwrap_or_default() == "1",
       enable_tracing: env::var("ENABLE_TRACING").unwrap_or_default() == "1",
+      enable_explain_score: env::var("ENABLE_EXPLAIN_SCORE").unwrap_or_default() == "1",
       tracing_exporter: env::var("TRACING_EXPORTER").unwrap_or("otlp".into()).parse()?,
       #[cfg(feature = "gcp")]
       gcp_project_id: detect_gcp_project_id().await,
@@ -118,6 +158,37 @@ impl FromStr for WrappedEsAuthMethod {
   }
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LogFormat {
+  #[default]
+  Compact,
+  Pretty,
+  Json,
+}
+
+impl LogFormat {
+  /// Structured logs in production, human-readable ones otherwise.
+  fn default_for(env: &Env) -> LogFormat {
+    match env {
+      Env::Production => LogFormat::Json,
+      Env::Dev => LogFormat::Compact,
+    }
+  }
+}
+
+impl FromStr for LogFormat {
+  type Err = AppError;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "compact" => Ok(LogFormat::Compact),
+      "pretty" => Ok(LogFormat::Pretty),
+      "json" => Ok(LogFormat::Json),
+      other => Err(AppError::ConfigError(format!("unsupported log format: {other}"))),
+    }
+  }
+}
+
 #[derive(Clone, Debug, Default)]
 pub enum TracingExporter {
   #[default]
@@ -154,6 +225,26 @@ where
   }
 }
 
+/// Parse a duration, either in a human-friendly format (e.g. `24h` or `2d`) or
+/// in ISO 8601 (e.g. `P2D`), rejecting negative durations.
+fn parse_non_negative_span(name: &str, default: Span) -> anyhow::Result<Span> {
+  let span = parse_env(name, default)?;
+
+  if span.is_negative() {
+    Err(AppError::ConfigError(format!("could not read {name}: duration cannot be negative")))?;
+  }
+
+  Ok(span)
+}
+
+/// Parse a limit that is disabled when set to an empty value or `0`.
+fn parse_optional_limit(name: &str, default: usize) -> anyhow::Result<Option<usize>> {
+  match env::var(name) {
+    Ok(value) if value.trim().is_empty() => Ok(None),
+    _ => Ok(Some(parse_env(name, default)?).filter(|limit| *limit > 0)),
+  }
+}
+
 fn parse_weights_from_env() -> anyhow::Result<HashMap<String, f64>> {
   let mut weights = HashMap::new();
 
@@ -173,6 +264,27 @@ fn parse_weights_from_env() -> anyhow::Result<HashMap<String, f64>> {
   Ok(weights)
 }
 
+fn parse_schema_defaults_from_env() -> anyhow::Result<HashMap<String, SchemaDefaults>> {
+  match env::var("SCHEMA_DEFAULTS") {
+    Ok(value) if !value.is_empty() => serde_json::from_str(&value).context("could not read SCHEMA_DEFAULTS"),
+    _ => Ok(HashMap::new()),
+  }
+}
+
+fn parse_api_keys_from_env() -> anyhow::Result<HashMap<String, Vec<String>>> {
+  match env::var("API_KEYS") {
+    Ok(value) if !value.is_empty() => serde_json::from_str(&value).context("could not read API_KEYS"),
+    _ => Ok(HashMap::new()),
+  }
+}
+
+fn parse_catalog_headers_from_env() -> anyhow::Result<HashMap<String, String>> {
+  match env::var("CATALOG_HEADERS") {
+    Ok(value) if !value.is_empty() => serde_json::from_str(&value).context("could not read CATALOG_HEADERS"),
+    _ => Ok(HashMap::new()),
+  }
+}
+
 fn parse_index_tls_verification() -> Result<EsTlsVerification, anyhow::Error> {
   if env::var("INDEX_TLS_SKIP_VERIFY").unwrap_or_default() == "1" {
     return Ok(EsTlsVerification::SkipVerify);
@@ -210,9 +322,11 @@ mod tests {
     net::{IpAddr, Ipv4Addr},
   };
 
+  use jiff::{Span, ToSpan};
+
   use crate::api::config::WrappedEsAuthMethod;
 
-  use super::{Config, Env, EsAuthMethod, TracingExporter};
+  use super::{Config, Env, EsAuthMethod, LogFormat, TracingExporter};
 
   #[serial_test::serial]
   #[tokio::test]
@@ -230,7 +344,7 @@ mod tests {
 
     let config = Config::from_env().await.unwrap();
 
-    assert_eq!(config.env, Env::Production);
+    assert_eq!(config.log_format, LogFormat::Json);
     assert_eq!(config.listen_addr, "0.0.0.0:8080");
     assert_eq!(config.match_candidates, 3);
     assert_eq!(config.index_url, "http://index");
@@ -321,6 +435,34 @@ mod tests {
     assert!("other".parse::<TracingExporter>().is_err());
   }
 
+  #[test]
+  fn log_format_from_str() {
+    assert_eq!("compact".parse::<LogFormat>().unwrap(), LogFormat::Compact);
+    assert_eq!("pretty".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert!("other".parse::<LogFormat>().is_err());
+
+    assert_eq!(LogFormat::default_for(&Env::Dev), LogFormat::Compact);
+    assert_eq!(LogFormat::default_for(&Env::Production), LogFormat::Json);
+  }
+
+  #[tokio::test]
+  #[serial_test::serial]
+  async fn log_format_from_env() {
+    unsafe { env::set_var("ENV", "production") };
+    assert_eq!(Config::from_env().await.unwrap().log_format, LogFormat::Json);
+
+    unsafe { env::set_var("LOG_FORMAT", "pretty") };
+    assert_eq!(Config::from_env().await.unwrap().log_format, LogFormat::Pretty);
+
+    unsafe {
+      env::remove_var("ENV");
+      env::remove_var("LOG_FORMAT");
+    }
+
+    assert_eq!(Config::from_env().await.unwrap().log_format, LogFormat::Compact);
+  }
+
   #[test]
   #[serial_test::serial]
   fn tracing_exporter_from_str() {
@@ -375,6 +517,52 @@ mod tests {
     unsafe { env::remove_var("INDEX_TLS_CA_CERT") };
   }
 
+  #[test]
+  #[serial_test::serial]
+  fn parse_outdated_grace() {
+    unsafe { env::set_var("OUTDATED_GRACE", "24h") };
+    assert_eq!(super::parse_non_negative_span("OUTDATED_GRACE", Span::default()).unwrap().fieldwise(), 24.hours().fieldwise());
+
+    unsafe { env::set_var("OUTDATED_GRACE", "2d") };
+    assert_eq!(super::parse_non_negative_span("OUTDATED_GRACE", Span::default()).unwrap().fieldwise(), 2.days().fieldwise());
+
+    unsafe { env::remove_var("OUTDATED_GRACE") };
+    assert!(super::parse_non_negative_span("OUTDATED_GRACE", Span::default()).unwrap().is_zero());
+  }
+
+  #[test]
+  #[serial_test::serial]
+  fn parse_outdated_grace_invalid() {
+    unsafe { env::set_var("OUTDATED_GRACE", "yesterday-ish") };
+    assert!(super::parse_non_negative_span("OUTDATED_GRACE", Span::default()).is_err());
+
+    unsafe { env::set_var("OUTDATED_GRACE", "-2d") };
+    assert!(super::parse_non_negative_span("OUTDATED_GRACE", Span::default()).is_err());
+
+    unsafe { env::remove_var("OUTDATED_GRACE") };
+  }
+
+  #[test]
+  #[serial_test::serial]
+  fn parse_optional_limit() {
+    unsafe { env::remove_var("MOTIVA_TEST_LIMIT") };
+    assert_eq!(super::parse_optional_limit("MOTIVA_TEST_LIMIT", 100).unwrap(), Some(100));
+
+    unsafe { env::set_var("MOTIVA_TEST_LIMIT", "20") };
+    assert_eq!(super::parse_optional_limit("MOTIVA_TEST_LIMIT", 100).unwrap(), Some(20));
+
+    unsafe { env::set_var("MOTIVA_TEST_LIMIT", "0") };
+    assert_eq!(super::parse_optional_limit("MOTIVA_TEST_LIMIT", 100).unwrap(), None);
+
+    unsafe { env::set_var("MOTIVA_TEST_LIMIT", "") };
+    assert_eq!(super::parse_optional_limit("MOTIVA_TEST_LIMIT", 100).unwrap(), None);
+
+    unsafe { env::set_var("MOTIVA_TEST_LIMIT", "many") };
+    assert!(super::parse_optional_limit("MOTIVA_TEST_LIMIT", 100).is_err());
+
+    unsafe { env::remove_var("MOTIVA_TEST_LIMIT") };
+  }
+
   #[test]
   #[serial_test::serial]
   fn parse_env_empty_returns_default() {
@@ -395,6 +583,52 @@ mod tests {
     assert!("encoded_api_key".parse::<WrappedEsAuthMethod>().is_err());
   }
 
+  #[test]
+  #[serial_test::serial]
+  fn parse_schema_defaults() {
+    use libmotiva::SchemaDefaults;
+
+    assert!(super::parse_schema_defaults_from_env().unwrap().is_empty());
+
+    unsafe { env::set_var("SCHEMA_DEFAULTS", r#"{"Vessel": {"threshold": 0.8, "cutoff": 0.6}, "Person": {"cutoff": 0.4}}"#) };
+
+    let defaults = super::parse_schema_defaults_from_env().unwrap();
+
+    assert_eq!(
+      defaults["Vessel"],
+      SchemaDefaults {
+        threshold: Some(0.8),
+        cutoff: Some(0.6)
+      }
+    );
+    assert_eq!(defaults["Person"], SchemaDefaults { threshold: None, cutoff: Some(0.4) });
+
+    unsafe { env::set_var("SCHEMA_DEFAULTS", "invalid") };
+
+    assert!(super::parse_schema_defaults_from_env().is_err());
+
+    unsafe { env::remove_var("SCHEMA_DEFAULTS") };
+  }
+
+  #[test]
+  #[serial_test::serial]
+  fn parse_api_keys() {
+    assert!(super::parse_api_keys_from_env().unwrap().is_empty());
+
+    unsafe { env::set_var("API_KEYS", r#"{"key1": ["default", "sanctions"], "key2": []}"#) };
+
+    let keys = super::parse_api_keys_from_env().unwrap();
+
+    assert_eq!(keys["key1"], vec!["default", "sanctions"]);
+    assert!(keys["key2"].is_empty());
+
+    unsafe { env::set_var("API_KEYS", "invalid") };
+
+    assert!(super::parse_api_keys_from_env().is_err());
+
+    unsafe { env::remove_var("API_KEYS") };
+  }
+
   #[test]
   #[serial_test::serial]
   fn parse_weights() {
@@ -4,6 +4,7 @@ use ahash::RandomState;
 use libmotiva::prelude::*;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_inline_default::serde_inline_default;
+use utoipa::ToSchema;
 use validator::{Validate, ValidationError};
 
 #[serde_inline_default]
@@ -13,9 +14,11 @@ pub struct GetEntityParams {
   pub nested: bool,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, Validate)]
+/// Entities to match, keyed by an arbitrary query identifier.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema, Validate)]
 pub(crate) struct Payload {
   #[validate(nested)]
+  #[schema(value_type = HashMap<String, SearchEntity>)]
   pub queries: HashMap<String, SearchEntity, RandomState>,
   #[serde(default)]
   #[validate(custom(function = "validate_weights"))]
@@ -26,7 +29,7 @@ pub(crate) struct Payload {
   pub params: PayloadParams,
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, Validate)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema, Validate)]
 pub(crate) struct PayloadParams {
   #[serde(default)]
   pub include_datasets: Option<Vec<String>>,
@@ -36,13 +39,16 @@ pub(crate) struct PayloadParams {
   pub exclude_entity_ids: Option<Vec<String>>,
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, ToSchema)]
 pub(super) struct MatchResponse {
+  #[schema(value_type = HashMap<String, MatchResults>)]
   pub responses: HashMap<String, MatchResults, RandomState>,
   pub limit: usize,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub warnings: Vec<String>,
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, ToSchema)]
 pub(super) struct MatchResults {
   pub status: u16,
   pub results: Vec<MatchHit>,
@@ -50,33 +56,172 @@ pub(super) struct MatchResults {
   pub total: Option<MatchTotal>,
 }
 
-#[derive(Default, Serialize)]
+/// A single query of a streamed match request, sent as one NDJSON line.
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub(crate) struct StreamedQuery {
+  /// Arbitrary identifier, echoed back in the matching results line.
+  pub id: String,
+  #[validate(nested)]
+  pub query: SearchEntity,
+}
+
+/// Results of a single streamed query, sent as one NDJSON line.
+#[derive(Default, Serialize, ToSchema)]
+pub(super) struct StreamedResults {
+  /// Identifier of the query, absent if the line could not be parsed.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  #[serde(flatten)]
+  pub results: MatchResults,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Default, Serialize, ToSchema)]
 pub(super) struct MatchTotal {
   pub relation: &'static str,
   pub value: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub(super) struct MatchHit {
   #[serde(flatten)]
   pub entity: Entity,
 
   #[serde(rename = "match")]
   pub match_: bool,
-  #[serde(serialize_with = "serialize_score")]
-  pub score: f64,
+  pub score: Score,
+  /// Scores computed by each requested algorithm, when several were requested
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  pub scores: HashMap<String, Score>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub reasons: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(value_type = Option<Object>)]
+  pub intermediates: Option<ScoreIntermediates>,
+}
+
+#[derive(Default, Serialize, ToSchema)]
+pub(super) struct ScoresResponse {
+  #[schema(value_type = HashMap<String, ScoresResults>)]
+  pub responses: HashMap<String, ScoresResults, RandomState>,
+  pub limit: usize,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub warnings: Vec<String>,
+}
+
+#[derive(Default, Serialize, ToSchema)]
+pub(super) struct ScoresResults {
+  pub status: u16,
+  #[schema(value_type = HashMap<String, ScoreHit>)]
+  pub scores: HashMap<String, ScoreHit, RandomState>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct ScoreHit {
+  #[serde(rename = "match")]
+  pub match_: bool,
+  pub score: Score,
+}
+
+/// Score of an indexed entity against another one.
+#[derive(Serialize, ToSchema)]
+pub struct CompareResponse {
+  /// ID of the entity used as the query
+  pub left: String,
+  #[serde(flatten)]
+  pub entity: Entity,
+  pub score: Score,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct PreviewResponse {
+  #[schema(value_type = HashMap<String, PreviewResults>)]
+  pub responses: HashMap<String, PreviewResults, RandomState>,
+}
+
+#[derive(Default, Serialize, ToSchema)]
+pub(super) struct PreviewResults {
+  pub status: u16,
+  pub results: Vec<PreviewHit>,
+}
+
+/// Unscored search candidate.
+#[derive(Serialize, ToSchema)]
+pub(super) struct PreviewHit {
+  pub id: String,
+  pub caption: String,
+  pub schema: String,
+  pub datasets: Vec<String>,
+}
+
+impl From<Entity> for PreviewHit {
+  fn from(entity: Entity) -> Self {
+    PreviewHit {
+      schema: entity.schema.as_str().to_string(),
+      id: entity.id,
+      caption: entity.caption,
+      datasets: entity.datasets,
+    }
+  }
+}
+
+/// Score of a result, rendered either as a float between 0.0 and 1.0, or as an
+/// integer percentage.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum Score {
+  Ratio(#[serde(serialize_with = "serialize_score")] f64),
+  Rounded(f64),
+  Percent(u8),
+}
+
+impl Score {
+  pub fn new(score: f64, as_percent: bool) -> Score {
+    match as_percent {
+      true => Score::Percent((score.clamp(0.0, 1.0) * 100.0).round() as u8),
+      false => Score::Ratio(score),
+    }
+  }
+
+  /// Round a ratio score to the provided number of decimals, if any.
+  pub fn with_precision(self, precision: Option<u8>) -> Score {
+    match (self, precision) {
+      (Score::Ratio(score), Some(precision)) => Score::Rounded(round_score(score, precision)),
+      (score, _) => score,
+    }
+  }
+}
+
+impl From<MatchResponse> for ScoresResponse {
+  fn from(value: MatchResponse) -> Self {
+    ScoresResponse {
+      responses: value.responses.into_iter().map(|(id, results)| (id, results.into())).collect(),
+      limit: value.limit,
+      warnings: value.warnings,
+    }
+  }
+}
+
+impl From<MatchResults> for ScoresResults {
+  fn from(value: MatchResults) -> Self {
+    ScoresResults {
+      status: value.status,
+      scores: value.results.into_iter().map(|hit| (hit.entity.id, ScoreHit { match_: hit.match_, score: hit.score })).collect(),
+    }
+  }
 }
 
 #[derive(Serialize)]
 pub struct Algorithms {
   pub algorithms: Vec<AlgorithmDescription>,
-  pub best: &'static str,
-  pub default: &'static str,
+  pub best: String,
+  pub default: String,
 }
 
 #[derive(Serialize)]
 pub struct AlgorithmDescription {
-  pub name: &'static str,
+  pub name: String,
 }
 
 #[derive(Serialize)]
@@ -85,6 +230,19 @@ pub struct Version {
   pub index: String,
 }
 
+#[derive(Serialize)]
+pub struct BuildInfo {
+  pub version: String,
+  pub datasets: usize,
+  pub index_stale: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CountResponse {
+  /// Number of indexed entities matching the filters
+  pub count: u64,
+}
+
 fn validate_weights(weights: &HashMap<String, f64>) -> Result<(), ValidationError> {
   for (k, v) in weights {
     if !(&-1.0..=&1.0).contains(&v) {
@@ -103,6 +261,27 @@ fn validate_weights(weights: &HashMap<String, f64>) -> Result<(), ValidationErro
 mod tests {
   use std::collections::HashMap;
 
+  #[test]
+  fn score_as_percent() {
+    use super::Score;
+
+    assert_eq!(serde_json::to_value(Score::new(0.72, false)).unwrap(), serde_json::json!(0.72));
+    assert_eq!(serde_json::to_value(Score::new(0.72, true)).unwrap(), serde_json::json!(72));
+    assert_eq!(serde_json::to_value(Score::new(0.7251, true)).unwrap(), serde_json::json!(73));
+    assert_eq!(serde_json::to_value(Score::new(1.0, true)).unwrap(), serde_json::json!(100));
+  }
+
+  #[test]
+  fn score_precision() {
+    use super::Score;
+
+    assert_eq!(serde_json::to_value(Score::new(2.0 / 3.0, false)).unwrap(), serde_json::json!(0.667));
+    assert_eq!(serde_json::to_value(Score::new(2.0 / 3.0, false).with_precision(Some(3))).unwrap(), serde_json::json!(0.667));
+    assert_eq!(serde_json::to_value(Score::new(2.0 / 3.0, false).with_precision(Some(1))).unwrap(), serde_json::json!(0.7));
+    assert_eq!(serde_json::to_value(Score::new(2.0 / 3.0, false).with_precision(Some(5))).unwrap(), serde_json::json!(0.66667));
+    assert_eq!(serde_json::to_value(Score::new(2.0 / 3.0, true).with_precision(Some(1))).unwrap(), serde_json::json!(67));
+  }
+
   #[test]
   fn validate_weights() {
     let mut weights = HashMap::new();
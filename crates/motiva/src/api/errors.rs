@@ -16,10 +16,16 @@ pub(super) struct ApiError(pub StatusCode, pub String, pub Option<Vec<String>>);
 pub enum AppError {
   #[error("bad request")]
   BadRequest,
+  #[error("unknown schema '{0}'")]
+  InvalidSchema(String),
   #[error("invalid credentials")]
   InvalidCredentials,
+  #[error("access to this resource is forbidden")]
+  Forbidden,
   #[error("missing resource")]
   ResourceNotFound,
+  #[error("request body is too large")]
+  PayloadTooLarge,
   #[error("server error, please check your logs for more information")]
   ServerError,
   #[error("the index is not ready, please try again later")]
@@ -43,7 +49,8 @@ impl From<MotivaError> for AppError {
       MotivaError::MissingIndex(_) => AppError::ServerError,
       MotivaError::IndexUnavailable => AppError::ServiceUnavailable,
       MotivaError::IndexError(err) => AppError::IndexError(err.to_string()),
-      MotivaError::InvalidSchema(_) => AppError::BadRequest,
+      MotivaError::InvalidSchema(schema) => AppError::InvalidSchema(schema),
+      MotivaError::InvalidParameter(_) => AppError::BadRequest,
       MotivaError::ResourceNotFound => AppError::ResourceNotFound,
       MotivaError::OtherError(err) => AppError::OtherError(err),
     }
@@ -62,8 +69,11 @@ impl From<&AppError> for ApiError {
   fn from(value: &AppError) -> Self {
     match value {
       AppError::BadRequest => ApiError(StatusCode::BAD_REQUEST, value.to_string(), None),
+      AppError::InvalidSchema(_) => ApiError(StatusCode::UNPROCESSABLE_ENTITY, value.to_string(), Some(vec![SCHEMA_HINT.to_string()])),
       AppError::InvalidCredentials => ApiError(StatusCode::UNAUTHORIZED, value.to_string(), None),
+      AppError::Forbidden => ApiError(StatusCode::FORBIDDEN, value.to_string(), None),
       AppError::ResourceNotFound => ApiError(StatusCode::NOT_FOUND, value.to_string(), None),
+      AppError::PayloadTooLarge => ApiError(StatusCode::PAYLOAD_TOO_LARGE, value.to_string(), None),
       AppError::ServiceUnavailable => ApiError(StatusCode::SERVICE_UNAVAILABLE, value.to_string(), None),
       AppError::IndexError(_) => ApiError(StatusCode::INTERNAL_SERVER_ERROR, value.to_string(), None),
       AppError::InvalidQuery(err) => ApiError(StatusCode::BAD_REQUEST, value.to_string(), Some(vec![err.to_string()])),
@@ -123,7 +133,8 @@ mod tests {
         StatusCode::INTERNAL_SERVER_ERROR,
         "error from indexer: index error",
       ),
-      (MotivaError::InvalidSchema("invalid schema".into()), StatusCode::BAD_REQUEST, "bad request"),
+      (MotivaError::InvalidSchema("Wizard".into()), StatusCode::UNPROCESSABLE_ENTITY, "unknown schema 'Wizard'"),
+      (MotivaError::InvalidParameter("invalid parameter".into()), StatusCode::BAD_REQUEST, "bad request"),
       (MotivaError::IndexUnavailable, StatusCode::SERVICE_UNAVAILABLE, "the index is not ready, please try again later"),
       (MotivaError::OtherError(anyhow::anyhow!("any error")), StatusCode::INTERNAL_SERVER_ERROR, "any error"),
     ];
@@ -134,7 +145,7 @@ mod tests {
 
       assert_eq!(resp.status(), status);
 
-      let body: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 128).await.unwrap()).unwrap();
+      let body: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 256).await.unwrap()).unwrap();
 
       assert_json_include!(
           actual: body,
@@ -158,6 +169,7 @@ mod tests {
       ),
       (AppError::ResourceNotFound, StatusCode::NOT_FOUND, "missing resource"),
       (AppError::InvalidCredentials, StatusCode::UNAUTHORIZED, "invalid credentials"),
+      (AppError::Forbidden, StatusCode::FORBIDDEN, "access to this resource is forbidden"),
       (AppError::IndexError("index error".into()), StatusCode::INTERNAL_SERVER_ERROR, "error from indexer: index error"),
       (AppError::ConfigError("config error".into()), StatusCode::INTERNAL_SERVER_ERROR, "invalid configuration: config error"),
       (AppError::ServerError, StatusCode::INTERNAL_SERVER_ERROR, "server error, please check your logs for more information"),
@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use axum::{Json, extract::State};
 use libmotiva::prelude::*;
 use serde::Deserialize;
 use serde_inline_default::serde_inline_default;
 use tracing::instrument;
+use utoipa::ToSchema;
 
 use crate::api::{
   AppState,
@@ -17,19 +18,68 @@ use crate::api::{
 pub struct GetCatalogParams {
   #[serde_inline_default(false)]
   force_refresh: bool,
+  category: Option<String>,
+  #[serde_inline_default(false)]
+  loaded: bool,
 }
 
+#[utoipa::path(
+  get,
+  path = "/catalog",
+  tag = "catalog",
+  params(
+    ("force_refresh" = Option<bool>, Query, description = "Refresh the catalog from upstream before returning it"),
+    ("category" = Option<String>, Query, description = "Only return datasets of this category (e.g. `sanctions`)"),
+    ("loaded" = Option<bool>, Query, description = "Only return datasets loaded from the manifest or present in the index"),
+  ),
+  responses((status = 200, description = "Datasets available for matching", body = Catalog))
+)]
 #[instrument(skip_all)]
 pub async fn get_catalog<F: CatalogFetcher, P: IndexProvider>(State(state): State<AppState<F, P>>, _: Auth<F, P>, Query(query): Query<GetCatalogParams>) -> Result<Json<Catalog>, AppError> {
-  Ok(Json(state.motiva.get_catalog(query.force_refresh).await?))
+  let mut catalog = state.motiva.get_catalog(query.force_refresh).await?;
+
+  if let Some(category) = &query.category {
+    catalog.datasets.retain(|dataset| dataset.category.as_deref() == Some(category.as_str()));
+  }
+
+  if query.loaded {
+    catalog.datasets.retain(|dataset| dataset.load || dataset.index_version.is_some());
+  }
+
+  if query.category.is_some() || query.loaded {
+    let names = catalog.datasets.iter().map(|dataset| dataset.name.as_str()).collect::<HashSet<_>>();
+
+    catalog.current.retain(|name| names.contains(name.as_str()));
+    catalog.outdated.retain(|name| names.contains(name.as_str()));
+  }
+
+  Ok(Json(catalog))
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[utoipa::path(
+  get,
+  path = "/catalog/diff",
+  tag = "catalog",
+  responses((status = 200, description = "Datasets added, removed, updated, or whose index status changed during the latest catalog refresh", body = CatalogDiff))
+)]
+#[instrument(skip_all)]
+pub async fn get_catalog_diff<F: CatalogFetcher, P: IndexProvider>(State(state): State<AppState<F, P>>, _: Auth<F, P>) -> Json<CatalogDiff> {
+  Json(state.motiva.catalog_diff().await)
+}
+
+#[derive(Clone, Debug, Default, Deserialize, ToSchema)]
 pub struct GetFieldValuesBody {
   fields: Vec<String>,
   query: Option<serde_json::Value>,
 }
 
+#[utoipa::path(
+  post,
+  path = "/catalog/fields",
+  tag = "catalog",
+  request_body = GetFieldValuesBody,
+  responses((status = 200, description = "Distinct indexed values, keyed by field", body = HashMap<String, Vec<String>>))
+)]
 #[instrument(skip_all)]
 pub async fn get_field_values<F: CatalogFetcher, P: IndexProvider>(
   State(state): State<AppState<F, P>>,
@@ -0,0 +1,72 @@
+use axum::{Json, extract::State};
+use libmotiva::prelude::*;
+use serde::Deserialize;
+use tracing::instrument;
+
+use crate::api::{
+  AppState,
+  dto::{CompareResponse, Score},
+  errors::AppError,
+  middlewares::{auth::Auth, types::Query},
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CompareParams {
+  left: String,
+  right: String,
+  #[serde(default)]
+  algorithm: Algorithm,
+  custom_algorithm: Option<String>,
+  #[serde(default)]
+  score_as_percent: bool,
+}
+
+#[utoipa::path(
+  get,
+  path = "/compare",
+  tag = "matching",
+  params(
+    ("left" = String, Query, description = "ID of the entity used as the query"),
+    ("right" = String, Query, description = "ID of the entity scored against the query"),
+    ("algorithm" = Option<String>, Query, description = "Algorithm used for scoring (defaults to `logic-v1`)"),
+    ("custom_algorithm" = Option<String>, Query, description = "Name of a registered custom algorithm to use instead of `algorithm`"),
+    ("score_as_percent" = Option<bool>, Query, description = "Render the score as an integer percentage"),
+  ),
+  responses(
+    (status = 200, description = "Score and feature breakdown of the right entity against the left one", body = CompareResponse),
+    (status = 400, description = "Unknown algorithm"),
+    (status = 404, description = "One of the entities does not exist")
+  )
+)]
+#[instrument(skip_all)]
+pub async fn compare<F: CatalogFetcher, P: IndexProvider>(State(state): State<AppState<F, P>>, _: Auth<F, P>, Query(params): Query<CompareParams>) -> Result<Json<CompareResponse>, AppError> {
+  if !state.motiva.ready() {
+    return Err(AppError::ServiceUnavailable);
+  }
+
+  let algorithm = match &params.custom_algorithm {
+    Some(name) => state.motiva.custom_algorithm(name).ok_or(AppError::BadRequest)?,
+    None => params.algorithm.into(),
+  };
+
+  let options = ScoringOptions {
+    cutoff: 0.0,
+    weights: state.config.weights.clone(),
+    explain: true,
+    ..Default::default()
+  };
+
+  let normalization = QueryNormalization {
+    phonetic_algorithm: state.config.phonetic_algorithm,
+    name_replacers: state.config.name_replacers.clone(),
+    ignore_generational_suffixes: state.config.ignore_generational_suffixes,
+  };
+
+  let (entity, score) = state.motiva.compare(&params.left, &params.right, &algorithm, &normalization, &options).await?;
+
+  Ok(Json(CompareResponse {
+    left: params.left,
+    entity,
+    score: Score::new(score, params.score_as_percent),
+  }))
+}
@@ -0,0 +1,44 @@
+use axum::{
+  Json,
+  extract::{Path, State},
+};
+use libmotiva::prelude::*;
+use tracing::instrument;
+
+use crate::api::{
+  AppState,
+  dto::CountResponse,
+  errors::AppError,
+  middlewares::{auth::Auth, types::Query},
+};
+
+/// Count the indexed entities a match in the scope could return, applying the
+/// same dataset, topic and exclusion filters, without scoring anything.
+#[utoipa::path(
+  get,
+  path = "/count/{scope}",
+  tag = "matching",
+  params(("scope" = String, Path, description = "Dataset or collection to count entities from"), MatchParams),
+  responses(
+    (status = 200, description = "Number of entities matching the filters", body = CountResponse),
+    (status = 400, description = "Invalid query parameters"),
+    (status = 503, description = "The index is not ready")
+  )
+)]
+#[instrument(skip_all, fields(scope))]
+pub async fn count<F: CatalogFetcher, P: IndexProvider>(
+  State(state): State<AppState<F, P>>,
+  _: Auth<F, P>,
+  Path((scope,)): Path<(String,)>,
+  Query(query): Query<MatchParams>,
+) -> Result<Json<CountResponse>, AppError> {
+  tracing::Span::current().record("scope", scope.as_str());
+
+  if !state.motiva.ready() {
+    return Err(AppError::ServiceUnavailable);
+  }
+
+  let count = state.motiva.count(&scope, &query).await?;
+
+  Ok(Json(CountResponse { count }))
+}
@@ -10,6 +10,20 @@ use tracing::instrument;
 
 use crate::api::{AppState, dto::GetEntityParams, errors::AppError, middlewares::auth::Auth};
 
+#[utoipa::path(
+  get,
+  path = "/entities/{id}",
+  tag = "entities",
+  params(
+    ("id" = String, Path, description = "ID of the entity to fetch"),
+    ("nested" = Option<bool>, Query, description = "Whether to inline related entities (defaults to true)")
+  ),
+  responses(
+    (status = 200, description = "The requested entity", body = Entity),
+    (status = 308, description = "The entity was merged into another one, which is redirected to"),
+    (status = 404, description = "The entity does not exist")
+  )
+)]
 #[instrument(skip_all)]
 pub async fn get_entity<F: CatalogFetcher, P: IndexProvider>(
   State(state): State<AppState<F, P>>,
@@ -21,7 +35,11 @@ pub async fn get_entity<F: CatalogFetcher, P: IndexProvider>(
     return Err(AppError::ServiceUnavailable);
   }
 
-  let behavior = if params.nested { GetEntityBehavior::FetchNestedEntity } else { GetEntityBehavior::RootOnly };
+  let behavior = if params.nested {
+    GetEntityBehavior::FetchNestedEntity(EdgeExpansion::default())
+  } else {
+    GetEntityBehavior::RootOnly
+  };
   let limit = GetEntityLimits::new(state.config.enrichment_max_recursion, state.config.enrichment_query_limit);
 
   match state.motiva.get_entity(&id, behavior, limit).await.map_err(Into::<AppError>::into)? {
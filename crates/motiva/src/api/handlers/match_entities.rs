@@ -1,140 +1,229 @@
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use ahash::RandomState;
+use axum::body::{Body, Bytes};
 use axum::extract::Path;
+use axum::http::header::CONTENT_TYPE;
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use futures_util::{StreamExt, TryStreamExt, future};
 use itertools::Itertools;
 use libmotiva::prelude::*;
 use metrics::histogram;
-use tracing::{Instrument, instrument};
+use tokio::sync::Semaphore;
+use tokio_util::codec::{FramedRead, LinesCodec};
+use tokio_util::io::StreamReader;
+use tracing::{Instrument, Span, instrument};
+use validator::Validate;
 
 use crate::api::errors::AppError;
 use crate::api::middlewares::auth::Auth;
 use crate::api::middlewares::types::Query;
 use crate::api::{
   AppState,
-  dto::{MatchHit, MatchResponse, MatchResults, MatchTotal, Payload},
+  dto::{MatchHit, MatchResponse, MatchResults, MatchTotal, Payload, PreviewHit, PreviewResponse, PreviewResults, Score, ScoresResponse, StreamedQuery, StreamedResults},
   middlewares::types::TypedJson,
 };
 
-#[instrument(skip_all)]
+#[utoipa::path(
+  post,
+  path = "/match/{scope}",
+  tag = "matching",
+  params(("scope" = String, Path, description = "Dataset or collection to match against"), MatchParams),
+  request_body = Payload,
+  responses(
+    (status = 200, description = "Scored candidates for each query", body = MatchResponse),
+    (status = 400, description = "Invalid query parameters or payload"),
+    (status = 503, description = "The index is not ready")
+  )
+)]
+#[instrument(skip_all, fields(scope, algorithm, query_count))]
 pub async fn match_entities<F: CatalogFetcher, P: IndexProvider + 'static>(
   State(state): State<AppState<F, P>>,
   _: Auth<F, P>,
   Path((scope,)): Path<(String,)>,
-  Query(mut query): Query<MatchParams>,
-  TypedJson(mut body): TypedJson<Payload>,
+  Query(query): Query<MatchParams>,
+  TypedJson(body): TypedJson<Payload>,
 ) -> Result<(StatusCode, impl IntoResponse), AppError> {
-  if !state.motiva.ready() {
-    return Err(AppError::ServiceUnavailable);
-  }
+  let response = run_match(state, scope, query, body).await?;
 
-  query.scope = scope;
-  query.candidate_factor = state.config.match_candidates;
+  Ok((StatusCode::OK, Json(response)))
+}
 
-  if let Some(datasets) = body.params.include_datasets {
-    query.include_dataset = datasets;
-  }
-  if let Some(datasets) = body.params.exclude_datasets {
-    query.exclude_dataset = datasets;
-  }
-  if let Some(entity_ids) = body.params.exclude_entity_ids {
-    query.exclude_entity_ids = entity_ids;
-  }
+/// Same as [`match_entities`], but only returns the IDs and scores of matching entities.
+#[utoipa::path(
+  post,
+  path = "/match/{scope}/scores",
+  tag = "matching",
+  params(("scope" = String, Path, description = "Dataset or collection to match against"), MatchParams),
+  request_body = Payload,
+  responses(
+    (status = 200, description = "Scores of matching entities for each query", body = ScoresResponse),
+    (status = 400, description = "Invalid query parameters or payload"),
+    (status = 503, description = "The index is not ready")
+  )
+)]
+#[instrument(skip_all, fields(scope, algorithm, query_count))]
+pub async fn match_scores<F: CatalogFetcher, P: IndexProvider + 'static>(
+  State(state): State<AppState<F, P>>,
+  _: Auth<F, P>,
+  Path((scope,)): Path<(String,)>,
+  Query(query): Query<MatchParams>,
+  TypedJson(body): TypedJson<Payload>,
+) -> Result<(StatusCode, impl IntoResponse), AppError> {
+  let response = run_match(state, scope, query, body).await?;
 
-  body.queries.iter_mut().for_each(|(_, entity)| {
-    entity.precompute();
-  });
+  Ok((StatusCode::OK, Json(ScoresResponse::from(response))))
+}
+
+/// Number of streamed queries processed concurrently, unless `MATCH_CONCURRENCY` is set.
+const STREAM_CONCURRENCY: usize = 16;
+
+/// Same as [`match_entities`], but reads queries from an NDJSON body and
+/// streams results back as NDJSON, as queries are processed.
+///
+/// Each line of the body is a query with an `id` and a `query` entity, and
+/// each line of the response holds the results for one query, in completion
+/// order. Lines that cannot be parsed produce an error line without an `id`.
+#[utoipa::path(
+  post,
+  path = "/match/{scope}/stream",
+  tag = "matching",
+  params(("scope" = String, Path, description = "Dataset or collection to match against"), MatchParams),
+  request_body(content = StreamedQuery, content_type = "application/x-ndjson"),
+  responses(
+    (status = 200, description = "Scored candidates for each query, one per line", body = StreamedResults, content_type = "application/x-ndjson"),
+    (status = 400, description = "Invalid query parameters"),
+    (status = 503, description = "The index is not ready")
+  )
+)]
+#[instrument(skip_all, fields(scope, algorithm))]
+pub async fn match_stream<F: CatalogFetcher, P: IndexProvider + 'static>(
+  State(state): State<AppState<F, P>>,
+  _: Auth<F, P>,
+  Path((scope,)): Path<(String,)>,
+  Query(query): Query<MatchParams>,
+  body: Body,
+) -> Result<(StatusCode, impl IntoResponse), AppError> {
+  let query = Arc::new(prepare_params(&state, scope, query)?);
+  let concurrency = state.config.match_concurrency.unwrap_or(STREAM_CONCURRENCY);
+  let codec = match state.config.max_body_size {
+    Some(limit) => LinesCodec::new_with_max_length(limit),
+    None => LinesCodec::new(),
+  };
 
   let state = Arc::new(state);
+  let weights = Arc::new(state.config.weights.clone());
 
-  let options = Arc::new(ScoringOptions {
-    cutoff: query.cutoff,
-    weights: state.config.weights.clone().into_iter().chain(body.weights.clone()).collect(),
-    explain: query.explain,
-  });
+  let lines = FramedRead::new(StreamReader::new(body.into_data_stream().map_err(io::Error::other)), codec);
 
-  let tasks = body.queries.into_iter().map(|(id, entity)| {
-    let mut query = query.clone();
-    let options = options.clone();
+  let results = lines
+    .filter(|line| future::ready(!matches!(line, Ok(line) if line.trim().is_empty())))
+    .map(move |line| {
+      let state = Arc::clone(&state);
+      let query = Arc::clone(&query);
+      let weights = Arc::clone(&weights);
 
-    if let Some(ref params) = entity.params {
-      if let Some(ref datasets) = params.include_datasets {
-        query.include_dataset = datasets.clone();
-      }
-      if let Some(ref datasets) = params.exclude_datasets {
-        query.exclude_dataset = datasets.clone();
+      async move {
+        let streamed = line
+          .map_err(|err| err.to_string())
+          .and_then(|line| serde_json::from_str::<StreamedQuery>(&line).map_err(|err| err.to_string()))
+          .and_then(|streamed| streamed.validate().map(|_| streamed).map_err(|err| err.to_string()));
+
+        let StreamedQuery { id, query: mut entity } = match streamed {
+          Ok(streamed) => streamed,
+
+          Err(err) => {
+            tracing::warn!(error = err.as_str(), "could not parse streamed query");
+
+            return StreamedResults {
+              results: MatchResults { status: 400, ..Default::default() },
+              error: Some(err),
+              ..Default::default()
+            };
+          }
+        };
+
+        prepare_entity(&state, &mut entity);
+
+        let query = entity_query(&query, &entity);
+
+        match tokio::spawn(match_query(state, query, weights, None, id.clone(), entity).in_current_span()).await {
+          Ok((id, results, _)) => StreamedResults { id: Some(id), results, error: None },
+
+          Err(_) => StreamedResults {
+            id: Some(id),
+            results: MatchResults { status: 500, ..Default::default() },
+            error: None,
+          },
+        }
       }
-    }
+      .in_current_span()
+    })
+    .buffer_unordered(concurrency)
+    .map(|results| {
+      let mut line = serde_json::to_vec(&results).unwrap_or_default();
+      line.push(b'\n');
+
+      Ok::<_, Infallible>(Bytes::from(line))
+    });
+
+  Ok((StatusCode::OK, ([(CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(results))))
+}
+
+/// Only run the index search for each query, and return the raw candidates
+/// without scoring them.
+#[utoipa::path(
+  post,
+  path = "/match/{scope}/preview",
+  tag = "matching",
+  params(("scope" = String, Path, description = "Dataset or collection to match against"), MatchParams),
+  request_body = Payload,
+  responses(
+    (status = 200, description = "Unscored candidates for each query", body = PreviewResponse),
+    (status = 400, description = "Invalid query parameters or payload"),
+    (status = 503, description = "The index is not ready")
+  )
+)]
+#[instrument(skip_all, fields(scope, algorithm, query_count))]
+pub async fn match_preview<F: CatalogFetcher, P: IndexProvider + 'static>(
+  State(state): State<AppState<F, P>>,
+  _: Auth<F, P>,
+  Path((scope,)): Path<(String,)>,
+  Query(query): Query<MatchParams>,
+  TypedJson(body): TypedJson<Payload>,
+) -> Result<(StatusCode, impl IntoResponse), AppError> {
+  let (query, body) = prepare_query(&state, scope, query, body)?;
+  let state = Arc::new(state);
+
+  let tasks = body.queries.into_iter().map(|(id, entity)| {
+    let query = entity_query(&query, &entity);
 
     tokio::spawn({
       let state = Arc::clone(&state);
 
       async move {
         if entity.properties.is_empty() {
-          return (
+          return (id, PreviewResults { status: 200, results: vec![] });
+        }
+
+        match state.motiva.search(&entity, &query).await {
+          Ok(hits) => (
             id,
-            MatchResults {
+            PreviewResults {
               status: 200,
-              total: Some(MatchTotal { relation: "eq", value: 0 }),
-              results: vec![],
+              results: hits.into_iter().map(PreviewHit::from).collect(),
             },
-          );
-        }
-
-        let hits = match state.motiva.search(&entity, &query).await {
-          Ok(hits) => hits,
+          ),
 
           Err(err) => {
             tracing::error!(error = ?err, "index query returned an error");
 
-            return (id, MatchResults { status: 500, ..Default::default() });
+            (id, PreviewResults { status: 500, ..Default::default() })
           }
-        };
-
-        let scores = match query.algorithm {
-          Algorithm::NameBased => state.motiva.score::<NameBased>(&entity, hits, &options),
-          Algorithm::NameQualified => state.motiva.score::<NameQualified>(&entity, hits, &options),
-          Algorithm::MarbleV0 => state.motiva.score::<MarbleV0>(&entity, hits, &options),
-          Algorithm::LogicV1 | Algorithm::Best => state.motiva.score::<LogicV1>(&entity, hits, &options),
-        };
-
-        match scores {
-          Ok(scores) => {
-            let pre_cutoff_count = scores.len();
-            let post_threshold_count = scores.iter().filter(|(_, score)| score >= &query.threshold).count();
-
-            let hits = scores
-              .into_iter()
-              .filter(|(_, score)| score >= &query.cutoff)
-              // Yente's implementation sorts by descending score, but let's order by (-score, id) so we get stable ordering
-              .sorted_by(|(lhs, lscore), (rhs, rscore)| lscore.total_cmp(rscore).reverse().then_with(|| lhs.id.cmp(&rhs.id)))
-              .take(query.limit)
-              .map(|(entity, score)| MatchHit {
-                entity,
-                score,
-                match_: score >= query.threshold,
-              })
-              .collect::<Vec<_>>();
-
-            histogram!("motiva_matches_above_cutoff_total").record(hits.len() as f64);
-            histogram!("motiva_matches_below_cutoff_total").record((pre_cutoff_count - hits.len()) as f64);
-
-            (
-              id,
-              MatchResults {
-                status: 200,
-                total: Some(MatchTotal {
-                  relation: "eq",
-                  value: post_threshold_count,
-                }),
-                results: hits,
-              },
-            )
-          }
-
-          Err(_) => (id, MatchResults { status: 500, ..Default::default() }),
         }
       }
       .in_current_span()
@@ -152,7 +241,303 @@ pub async fn match_entities<F: CatalogFetcher, P: IndexProvider + 'static>(
     }
   }
 
-  let response = MatchResponse { responses, limit: query.limit };
+  Ok((StatusCode::OK, Json(PreviewResponse { responses })))
+}
 
-  Ok((StatusCode::OK, Json(response)))
+/// Merge request-wide parameters into the query, and precompute the search entities.
+fn prepare_query<F: CatalogFetcher, P: IndexProvider>(state: &AppState<F, P>, scope: String, query: MatchParams, mut body: Payload) -> Result<(MatchParams, Payload), AppError> {
+  Span::current().record("query_count", body.queries.len());
+
+  let mut query = prepare_params(state, scope, query)?;
+
+  if let Some(datasets) = body.params.include_datasets.take() {
+    query.include_dataset = datasets;
+  }
+  if let Some(datasets) = body.params.exclude_datasets.take() {
+    query.exclude_dataset = datasets;
+  }
+  if let Some(entity_ids) = body.params.exclude_entity_ids.take() {
+    query.exclude_entity_ids = entity_ids;
+  }
+
+  body.queries.iter_mut().for_each(|(_, entity)| prepare_entity(state, entity));
+
+  Ok((query, body))
+}
+
+/// Validate the request parameters and apply the server-side settings to them.
+fn prepare_params<F: CatalogFetcher, P: IndexProvider>(state: &AppState<F, P>, scope: String, mut query: MatchParams) -> Result<MatchParams, AppError> {
+  let span = Span::current();
+
+  span.record("scope", scope.as_str());
+
+  if !state.motiva.ready() {
+    return Err(AppError::ServiceUnavailable);
+  }
+
+  let Some(algorithm) = state.motiva.requested_algorithm(&query) else {
+    return Err(AppError::BadRequest);
+  };
+
+  span.record("algorithm", algorithm.name());
+
+  query.validate_field_mapping()?;
+
+  query.scope = scope;
+  query.candidate_factor = state.config.match_candidates;
+
+  if let Some(max_limit) = state.config.max_limit
+    && query.limit > max_limit
+  {
+    tracing::warn!(requested = query.limit, max_limit, "requested limit is above the maximum, clamping");
+
+    query.limit = max_limit;
+  }
+
+  Ok(query)
+}
+
+fn prepare_entity<F: CatalogFetcher, P: IndexProvider>(state: &AppState<F, P>, entity: &mut SearchEntity) {
+  entity.phonetic_algorithm = state.config.phonetic_algorithm;
+  entity.name_replacers = state.config.name_replacers.clone();
+  entity.ignore_generational_suffixes = state.config.ignore_generational_suffixes;
+  entity.precompute();
+}
+
+/// Apply the per-query dataset overrides, if any.
+fn entity_query(query: &MatchParams, entity: &SearchEntity) -> MatchParams {
+  let mut query = query.clone();
+
+  if let Some(ref params) = entity.params {
+    if let Some(ref datasets) = params.include_datasets {
+      query.include_dataset = datasets.clone();
+    }
+    if let Some(ref datasets) = params.exclude_datasets {
+      query.exclude_dataset = datasets.clone();
+    }
+  }
+
+  query
+}
+
+/// Score the selected results with each additional requested algorithm, for comparison.
+fn score_other_algorithms<F: CatalogFetcher, P: IndexProvider>(
+  state: &AppState<F, P>,
+  query: &MatchParams,
+  primary: &ScoringAlgorithm,
+  entity: &SearchEntity,
+  options: &ScoringOptions,
+  hits: &mut [MatchHit],
+) {
+  for hit in hits.iter_mut() {
+    hit.scores.insert(primary.name().to_string(), hit.score);
+  }
+
+  for algorithm in query.algorithms.iter().filter(|algorithm| algorithm.name() != primary.name()).unique_by(|algorithm| algorithm.name()) {
+    let candidates = hits.iter().map(|hit| hit.entity.clone()).collect();
+
+    match state.motiva.score_algorithm(&(*algorithm).into(), entity, candidates, options) {
+      Ok(scores) => {
+        for (hit, (_, score)) in hits.iter_mut().zip(scores) {
+          hit
+            .scores
+            .insert(algorithm.name().to_string(), Score::new(score, query.score_as_percent).with_precision(query.score_precision));
+        }
+      }
+
+      Err(err) => tracing::warn!(algorithm = algorithm.name(), error = ?err, "could not score results with additional algorithm"),
+    }
+  }
+}
+
+async fn run_match<F: CatalogFetcher, P: IndexProvider + 'static>(state: AppState<F, P>, scope: String, query: MatchParams, body: Payload) -> Result<MatchResponse, AppError> {
+  let start = Instant::now();
+  let requested_limit = query.limit;
+  let (query, body) = prepare_query(&state, scope, query, body)?;
+
+  let mut warnings = Vec::new();
+
+  if query.limit < requested_limit {
+    warnings.push(format!("limit was reduced from {requested_limit} to the maximum of {}", query.limit));
+  }
+
+  let state = Arc::new(state);
+
+  let weights: Arc<HashMap<String, f64>> = Arc::new(state.config.weights.clone().into_iter().chain(body.weights.clone()).collect());
+
+  let permits = state.config.match_concurrency.map(|count| Arc::new(Semaphore::new(count)));
+
+  let tasks = body.queries.into_iter().map(|(id, entity)| {
+    let query = entity_query(&query, &entity);
+
+    tokio::spawn(match_query(Arc::clone(&state), query, Arc::clone(&weights), permits.clone(), id, entity).in_current_span())
+  });
+
+  let mut responses = HashMap::with_capacity_and_hasher(tasks.len(), RandomState::default());
+  let mut candidate_count = 0;
+
+  for task in tasks {
+    match task.await {
+      Err(_) => return Err(AppError::ServerError),
+      Ok((id, results, candidates)) => {
+        responses.insert(id, results);
+        candidate_count += candidates;
+      }
+    }
+  }
+
+  if let Some(slow_query_ms) = state.config.slow_query_ms {
+    let elapsed = start.elapsed();
+
+    if elapsed > Duration::from_millis(slow_query_ms) {
+      tracing::warn!(
+        scope = query.scope.as_str(),
+        query_count = responses.len(),
+        candidate_count,
+        elapsed_ms = elapsed.as_millis() as u64,
+        threshold_ms = slow_query_ms,
+        "slow match query"
+      );
+    }
+  }
+
+  Ok(MatchResponse {
+    responses,
+    limit: query.limit,
+    warnings,
+  })
+}
+
+/// Build the reasons returned for a result, depending on the requested options.
+fn hit_reasons(query: &MatchParams, hit: &Entity) -> Vec<String> {
+  let mut reasons = match query.include_reasons {
+    true => match_reasons(&hit.features),
+    false => Vec::new(),
+  };
+
+  if query.include_provenance
+    && let Some(reason) = provenance_reason(&hit.datasets)
+  {
+    reasons.push(reason);
+  }
+
+  reasons
+}
+
+/// Search and score candidates for a single query.
+///
+/// When `permits` is set, scoring waits for one of them, bounding how many
+/// queries of the same request are scored concurrently.
+///
+/// Returns the query ID, its results and the number of candidates that were scored.
+async fn match_query<F: CatalogFetcher, P: IndexProvider>(
+  state: Arc<AppState<F, P>>,
+  query: MatchParams,
+  weights: Arc<HashMap<String, f64>>,
+  permits: Option<Arc<Semaphore>>,
+  id: String,
+  entity: SearchEntity,
+) -> (String, MatchResults, usize) {
+  if entity.properties.is_empty() {
+    return (
+      id,
+      MatchResults {
+        status: 200,
+        total: Some(MatchTotal { relation: "eq", value: 0 }),
+        results: vec![],
+      },
+      0,
+    );
+  }
+
+  let (threshold, cutoff) = state.motiva.thresholds(&entity, &query);
+
+  let options = ScoringOptions {
+    cutoff,
+    weights: weights.as_ref().clone(),
+    explain: query.explain,
+    penalize_missing: query.penalize_missing.clone(),
+    escalate_mismatches: query.escalate_mismatches,
+  };
+
+  let hits = match state.motiva.search(&entity, &query).await {
+    Ok(hits) => hits,
+
+    Err(err) => {
+      tracing::error!(error = ?err, "index query returned an error");
+
+      return (id, MatchResults { status: 500, ..Default::default() }, 0);
+    }
+  };
+
+  let candidate_count = hits.len();
+
+  let _permit = match permits {
+    Some(ref permits) => Some(permits.acquire().await),
+    None => None,
+  };
+
+  // The algorithm was checked when preparing the request, and registered
+  // algorithms cannot be removed.
+  let Some(algorithm) = state.motiva.requested_algorithm(&query) else {
+    return (id, MatchResults { status: 400, ..Default::default() }, candidate_count);
+  };
+
+  let scores = state.motiva.score_algorithm(&algorithm, &entity, hits, &options);
+
+  match scores {
+    Ok(scores) => {
+      let pre_cutoff_count = scores.len();
+      let post_threshold_count = scores.iter().filter(|(_, score)| is_match(*score, threshold)).count();
+
+      let mut hits = scores
+        .into_iter()
+        .filter(|(_, score)| score >= &cutoff)
+        // Yente's implementation sorts by descending score, but let's order by (-score, id) so we get stable ordering
+        .sorted_by(|(lhs, lscore), (rhs, rscore)| lscore.total_cmp(rscore).reverse().then_with(|| lhs.id.cmp(&rhs.id)))
+        .take(query.limit)
+        .map(|(hit, score)| MatchHit {
+          reasons: hit_reasons(&query, &hit),
+          intermediates: (query.explain_score && state.config.enable_explain_score).then(|| score_intermediates(&entity, &hit)),
+          entity: hit,
+          score: Score::new(score, query.score_as_percent).with_precision(query.score_precision),
+          scores: HashMap::new(),
+          match_: is_match(score, threshold),
+        })
+        .collect::<Vec<_>>();
+
+      if !query.algorithms.is_empty() {
+        score_other_algorithms(&state, &query, &algorithm, &entity, &options, &mut hits);
+      }
+
+      // The index score is only meant for debugging ranking issues.
+      if !query.explain {
+        for hit in hits.iter_mut() {
+          hit.entity.index_score = None;
+        }
+      }
+
+      for hit in hits.iter_mut() {
+        hit.entity.score_precision = query.score_precision;
+      }
+
+      histogram!("motiva_matches_above_cutoff_total").record(hits.len() as f64);
+      histogram!("motiva_matches_below_cutoff_total").record((pre_cutoff_count - hits.len()) as f64);
+
+      (
+        id,
+        MatchResults {
+          status: 200,
+          total: Some(MatchTotal {
+            relation: "eq",
+            value: post_threshold_count,
+          }),
+          results: hits,
+        },
+        candidate_count,
+      )
+    }
+
+    Err(_) => (id, MatchResults { status: 500, ..Default::default() }, candidate_count),
+  }
 }
@@ -1,6 +1,9 @@
 mod catalog;
+mod compare;
+mod count;
 mod get_entity;
 mod match_entities;
+mod openapi;
 
 use axum::Json;
 use axum::extract::State;
@@ -9,12 +12,15 @@ use libmotiva::prelude::*;
 use reqwest::StatusCode;
 
 use crate::api::AppState;
-use crate::api::dto::{AlgorithmDescription, Algorithms, Version};
+use crate::api::dto::{AlgorithmDescription, Algorithms, BuildInfo, Version};
 use crate::api::errors::AppError;
 
-pub use self::catalog::{get_catalog, get_field_values};
+pub use self::catalog::{get_catalog, get_catalog_diff, get_field_values};
+pub use self::compare::compare;
+pub use self::count::count;
 pub use self::get_entity::get_entity;
-pub use self::match_entities::match_entities;
+pub use self::match_entities::{match_entities, match_preview, match_scores, match_stream};
+pub use self::openapi::openapi;
 
 pub async fn not_found() -> impl IntoResponse {
   AppError::ResourceNotFound
@@ -39,13 +45,13 @@ pub async fn prometheus<F: CatalogFetcher, P: IndexProvider>(State(state): State
   (StatusCode::OK, prometheus.render())
 }
 
-pub async fn algorithms() -> Json<Algorithms> {
-  const ALGORITHMS: [Algorithm; 3] = [Algorithm::NameBased, Algorithm::NameQualified, Algorithm::LogicV1];
+pub async fn algorithms<F: CatalogFetcher, P: IndexProvider>(State(state): State<AppState<F, P>>) -> Json<Algorithms> {
+  let builtin = [Algorithm::NameBased, Algorithm::NameQualified, Algorithm::LogicV1].map(|alg| alg.name().to_string());
 
   Json(Algorithms {
-    algorithms: ALGORITHMS.into_iter().map(|alg| AlgorithmDescription { name: alg.name() }).collect(),
-    best: Algorithm::best().name(),
-    default: Algorithm::default().name(),
+    algorithms: builtin.into_iter().chain(state.motiva.custom_algorithms()).map(|name| AlgorithmDescription { name }).collect(),
+    best: Algorithm::best().name().to_string(),
+    default: Algorithm::default().name().to_string(),
   })
 }
 
@@ -57,3 +63,13 @@ pub async fn version<F: CatalogFetcher, P: IndexProvider>(State(state): State<Ap
     index: state.motiva.index_version().to_string(),
   })
 }
+
+pub async fn build_info<F: CatalogFetcher, P: IndexProvider>(State(state): State<AppState<F, P>>) -> Result<Json<BuildInfo>, AppError> {
+  let catalog = state.motiva.get_catalog(false).await?;
+
+  Ok(Json(BuildInfo {
+    version: env!("CARGO_PKG_VERSION").to_string(),
+    datasets: catalog.datasets.len(),
+    index_stale: catalog.index_stale,
+  }))
+}
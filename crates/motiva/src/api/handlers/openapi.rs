@@ -0,0 +1,57 @@
+use axum::Json;
+use libmotiva::prelude::*;
+use utoipa::OpenApi;
+
+use crate::api::dto::{
+  CompareResponse, CountResponse, MatchHit, MatchResponse, MatchResults, MatchTotal, Payload, PayloadParams, PreviewHit, PreviewResponse, PreviewResults, Score, ScoreHit, ScoresResponse,
+  ScoresResults, StreamedQuery, StreamedResults,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+  info(title = "Motiva", description = "Sanctioned entities matching API"),
+  paths(
+    super::match_entities::match_entities,
+    super::match_entities::match_scores,
+    super::match_entities::match_preview,
+    super::match_entities::match_stream,
+    super::count::count,
+    super::compare::compare,
+    super::get_entity::get_entity,
+    super::catalog::get_catalog,
+    super::catalog::get_catalog_diff,
+    super::catalog::get_field_values,
+  ),
+  components(schemas(
+    Payload,
+    PayloadParams,
+    MatchResponse,
+    MatchResults,
+    MatchTotal,
+    MatchHit,
+    StreamedQuery,
+    StreamedResults,
+    Score,
+    ScoresResponse,
+    ScoresResults,
+    ScoreHit,
+    PreviewResponse,
+    PreviewResults,
+    PreviewHit,
+    CompareResponse,
+    CountResponse,
+    SearchEntity,
+    Entity,
+    Catalog,
+    CatalogDiff,
+    Algorithm,
+    SearchType,
+    IndexType,
+    super::catalog::GetFieldValuesBody,
+  ))
+)]
+struct ApiDoc;
+
+pub async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+  Json(ApiDoc::openapi())
+}
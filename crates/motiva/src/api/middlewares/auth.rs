@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use anyhow::Context;
 use axum::{
   RequestPartsExt,
-  extract::{FromRef, FromRequestParts, State},
+  extract::{FromRef, FromRequestParts, RawPathParams, State},
   http::request::Parts,
 };
 use axum_extra::{
@@ -38,9 +38,11 @@ where
   async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
     let State(app_state) = parts.extract_with_state::<State<AppState<_, P>>, S>(state).await.unwrap();
 
-    let Some(api_key) = &app_state.config.api_key else {
+    let config = &app_state.config;
+
+    if config.api_key.is_none() && config.api_keys.is_empty() {
       return Ok(Auth { _marker: PhantomData });
-    };
+    }
 
     let header = parts
       .extract::<TypedHeader<Authorization<Bearer>>>()
@@ -48,8 +50,21 @@ where
       .context("no authorization header found")
       .context(AppError::InvalidCredentials)?;
 
-    if header.token() != api_key {
+    if config.api_key.as_deref() == Some(header.token()) {
+      return Ok(Auth { _marker: PhantomData });
+    }
+
+    let Some(scopes) = config.api_keys.get(header.token()) else {
       return Err(AppError::InvalidCredentials);
+    };
+
+    // Scoped keys are only restricted on routes that target a scope, they can
+    // still be used on every other authenticated route.
+    if let Ok(params) = parts.extract::<RawPathParams>().await
+      && let Some((_, scope)) = params.iter().find(|(name, _)| *name == "scope")
+      && !scopes.iter().any(|allowed| allowed == scope)
+    {
+      return Err(AppError::Forbidden);
     }
 
     Ok(Auth::<F, P> { _marker: PhantomData })
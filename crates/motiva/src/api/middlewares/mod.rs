@@ -1,10 +1,10 @@
 use axum::{
-  body::Body,
-  http::{Request, StatusCode},
+  body::{Body, HttpBody},
+  http::{Request, StatusCode, header::CONTENT_LENGTH},
   middleware::Next,
   response::Response,
 };
-use metrics::counter;
+use metrics::{counter, histogram};
 use opentelemetry::global;
 use opentelemetry_http::HeaderExtractor;
 use tracing::Span;
@@ -33,13 +33,30 @@ pub(crate) async fn request_id(request: Request<Body>, next: Next) -> Result<Res
 }
 
 pub(super) async fn metrics(request: Request<Body>, next: Next) -> Result<Response, StatusCode> {
+  if let Some(size) = body_size(&request) {
+    histogram!("http_request_size_bytes").record(size as f64);
+  }
+
   let response = next.run(request).await;
 
   counter!("http_requests_total", "status" => response.status().as_u16().to_string()).increment(1);
 
+  if let Some(size) = response.size_hint().exact() {
+    histogram!("http_response_size_bytes").record(size as f64);
+  }
+
   Ok(response)
 }
 
+fn body_size(request: &Request<Body>) -> Option<u64> {
+  request
+    .headers()
+    .get(CONTENT_LENGTH)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.parse().ok())
+    .or_else(|| request.body().size_hint().exact())
+}
+
 pub(crate) fn create_request_span(req: &axum::extract::Request) -> Span {
   let parent = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(req.headers())));
   let span = tracing::info_span!("request", request_id = req.extensions().get::<RequestId>().unwrap().0.to_string());
@@ -1,15 +1,20 @@
 use axum::{
   Json, RequestExt,
   body::Body,
-  extract::{FromRequest, rejection::JsonRejection},
+  extract::{
+    FromRequest,
+    rejection::{JsonDataError, JsonRejection},
+  },
   http::{Request, StatusCode},
   response::{IntoResponse, Response},
 };
 use axum_macros::FromRequestParts;
 use serde::{Deserialize, de::DeserializeOwned};
+use serde_path_to_error::Segment;
+use std::error::Error;
 use validator::{Validate, ValidationErrors, ValidationErrorsKind};
 
-use crate::api::errors::ApiError;
+use crate::api::errors::{ApiError, AppError};
 
 pub struct TypedJson<T>(pub T);
 
@@ -24,8 +29,9 @@ impl IntoResponse for TypedJsonRejection {
     let (status, message, details) = match self {
       TypedJsonRejection::JsonRejection(err) => match err {
         JsonRejection::JsonSyntaxError(_) => (StatusCode::BAD_REQUEST, "invalid payload format".to_string(), None),
-        JsonRejection::JsonDataError(err) => (StatusCode::BAD_REQUEST, "payload does not match expected format".to_string(), Some(vec![err.to_string()])),
+        JsonRejection::JsonDataError(err) => (StatusCode::BAD_REQUEST, "payload does not match expected format".to_string(), Some(vec![describe_data_error(&err)])),
         JsonRejection::MissingJsonContentType(_) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, "invalid media type, expected application/json".to_string(), None),
+        err if err.status() == StatusCode::PAYLOAD_TOO_LARGE => (StatusCode::PAYLOAD_TOO_LARGE, AppError::PayloadTooLarge.to_string(), None),
         err => (StatusCode::BAD_REQUEST, "invalid payload".to_string(), Some(vec![err.to_string()])),
       },
 
@@ -48,6 +54,29 @@ impl IntoResponse for TypedJsonRejection {
   }
 }
 
+/// Describe where and why deserialization failed, naming the offending query
+/// when the error is located within `queries`.
+fn describe_data_error(err: &JsonDataError) -> String {
+  let Some(error) = std::iter::successors(err.source(), |&err| err.source()).find_map(|err| err.downcast_ref::<serde_path_to_error::Error<serde_json::Error>>()) else {
+    return err.to_string();
+  };
+
+  let mut segments = error.path().iter();
+
+  match (segments.next(), segments.next()) {
+    (Some(Segment::Map { key: root }), Some(Segment::Map { key })) if root == "queries" => {
+      let field = segments.map(ToString::to_string).collect::<Vec<_>>().join(".");
+
+      match field.is_empty() {
+        true => format!("query '{key}': {}", error.inner()),
+        false => format!("query '{key}': {field}: {}", error.inner()),
+      }
+    }
+
+    _ => error.to_string(),
+  }
+}
+
 fn flatten_validation_errors(errs: &ValidationErrors, prefix: &str, out: &mut Vec<String>) {
   for (field, kind) in errs.errors() {
     let path = if prefix.is_empty() { field.to_string() } else { format!("{prefix}.{field}") };
@@ -1,7 +1,9 @@
 use std::{sync::Arc, time::Duration};
 
 use axum::{
-  Router, middleware,
+  Router,
+  extract::DefaultBodyLimit,
+  middleware,
   routing::{get, post},
 };
 use jiff::ToSpan;
@@ -33,6 +35,10 @@ pub async fn routes<F: CatalogFetcher, P: IndexProvider>(config: Config, fetcher
   let motiva = {
     let config = MotivaConfig {
       outdated_grace: config.outdated_grace,
+      schema_defaults: config.schema_defaults.clone(),
+      search_type: config.search_type,
+      caption_preference: config.caption_preference,
+      ..Default::default()
     };
 
     Motiva::custom(provider.clone()).fetcher(fetcher).config(config).build().await?
@@ -78,10 +84,21 @@ pub async fn routes<F: CatalogFetcher, P: IndexProvider>(config: Config, fetcher
 pub(crate) fn router<F: CatalogFetcher, P: IndexProvider>(state: AppState<F, P>) -> Router {
   Router::new()
     .route("/catalog", get(handlers::get_catalog))
+    .route("/catalog/diff", get(handlers::get_catalog_diff))
     .route("/catalog/fields", post(handlers::get_field_values))
     .route("/match/{scope}", post(handlers::match_entities))
+    .route("/match/{scope}/scores", post(handlers::match_scores))
+    .route("/match/{scope}/preview", post(handlers::match_preview))
+    .route("/match/{scope}/stream", post(handlers::match_stream))
+    .route("/count/{scope}", get(handlers::count))
+    .route("/compare", get(handlers::compare))
     .route("/entities/{id}", get(handlers::get_entity))
     .fallback(handlers::not_found)
+    // Only bounds buffered bodies, streamed bodies are bounded per line by their handler instead.
+    .layer(match state.config.max_body_size {
+      Some(limit) => DefaultBodyLimit::max(limit),
+      None => DefaultBodyLimit::disable(),
+    })
     .layer(TimeoutLayer::with_status_code(
       StatusCode::REQUEST_TIMEOUT,
       state.config.request_timeout.try_into().unwrap_or(Duration::from_secs(10)),
@@ -91,10 +108,12 @@ pub(crate) fn router<F: CatalogFetcher, P: IndexProvider>(state: AppState<F, P>)
     .layer(middleware::from_fn(middlewares::metrics))
     // The routes below will not go through the observability middlewares above
     .route("/algorithms", get(handlers::algorithms))
+    .route("/openapi.json", get(handlers::openapi))
     .route("/healthz", get(handlers::healthz))
     .route("/readyz", get(handlers::readyz))
     .route("/metrics", get(handlers::prometheus))
     .route("/-/version", get(handlers::version))
+    .route("/version", get(handlers::build_info))
     .layer(middleware::from_fn(middlewares::request_id))
     .with_state(state)
 }
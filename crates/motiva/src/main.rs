@@ -31,6 +31,8 @@ async fn main() -> anyhow::Result<()> {
     auth: config.index_auth_method.clone(),
     tls: &config.index_tls_verification,
     index_name: config.index_name.clone(),
+    minimum_version: config.index_min_version.clone(),
+    flavor: config.index_flavor,
   };
 
   let provider = ElasticsearchProvider::new(&config.index_url, options).await?;
@@ -57,8 +59,8 @@ async fn run<P: IndexProvider>(mut config: Config, provider: P) -> anyhow::Resul
     None => tokio::net::TcpListener::bind(&config.listen_addr).await.expect("could not create listener"),
   };
 
-  let manifest_url = config.manifest_url.clone();
-  let app = api::routes(config, HttpCatalogFetcher::from_manifest_url(manifest_url)?, provider).await?;
+  let fetcher = HttpCatalogFetcher::from_manifest_url(config.manifest_url.clone())?.with_headers(config.catalog_user_agent.as_deref(), &config.catalog_headers)?;
+  let app = api::routes(config, fetcher, provider).await?;
 
   tracing::info!(motiva = git_version(), "listening on {}", listener.local_addr()?.to_string());
 
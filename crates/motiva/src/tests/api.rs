@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::sync::{
+  Arc,
+  atomic::{AtomicUsize, Ordering},
+};
 
 use axum::{
   Router,
   routing::{get, post},
 };
 use axum_test::TestServer;
-use libmotiva::{MockedElasticsearch, prelude::*};
+use libmotiva::{Manifest, MockedElasticsearch, prelude::*};
 use serde_json::json;
 
 use crate::api::{AppState, config::Config, handlers};
@@ -43,6 +46,29 @@ async fn api_not_version() {
   }));
 }
 
+#[tokio::test]
+async fn api_build_info() {
+  let index = MockedElasticsearch::builder().healthy(true).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).fetcher(TestFetcher::default()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/version", get(handlers::build_info)).with_state(state);
+  let server = TestServer::new(app);
+  let response = server.get("/version").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  response.assert_json(&json!({
+      "version": env!("CARGO_PKG_VERSION"),
+      "datasets": 0,
+      "index_stale": false,
+  }));
+}
+
 #[tokio::test]
 async fn api_health_unhealthy() {
   let index = MockedElasticsearch::builder().ready(false).build();
@@ -104,6 +130,253 @@ async fn api_algorithms() {
   }));
 }
 
+#[tokio::test]
+async fn api_catalog_category() {
+  let mut fetcher = TestFetcher::default();
+
+  for catalog in fetcher.catalogs.values_mut() {
+    catalog.datasets = vec![
+      CatalogDataset {
+        name: "us_ofac_sdn".to_string(),
+        category: Some("sanctions".to_string()),
+        ..Default::default()
+      },
+      CatalogDataset {
+        name: "eu_fsf".to_string(),
+        category: Some("sanctions".to_string()),
+        ..Default::default()
+      },
+      CatalogDataset {
+        name: "wd_peps".to_string(),
+        category: Some("peps".to_string()),
+        ..Default::default()
+      },
+    ];
+  }
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(MockedElasticsearch::builder().healthy(true).build()).fetcher(fetcher).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/catalog", get(handlers::get_catalog)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server.get("/catalog").add_query_param("category", "sanctions").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let names = response.json::<serde_json::Value>()["datasets"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|dataset| dataset["name"].as_str().unwrap().to_string())
+    .collect::<Vec<_>>();
+
+  assert_eq!(names, vec!["us_ofac_sdn", "eu_fsf"]);
+
+  let response = server.get("/catalog").await;
+
+  assert_eq!(response.json::<serde_json::Value>()["datasets"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn api_catalog_loaded() {
+  let mut fetcher = TestFetcher::default();
+
+  for catalog in fetcher.catalogs.values_mut() {
+    catalog.datasets = ["default", "indexed", "unused"]
+      .into_iter()
+      .map(|name| CatalogDataset {
+        name: name.to_string(),
+        version: "1".to_string(),
+        ..Default::default()
+      })
+      .collect();
+  }
+
+  let index = MockedElasticsearch::builder().healthy(true).indices(vec![("indexed".to_string(), "1".to_string())]).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).fetcher(fetcher).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/catalog", get(handlers::get_catalog)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server.get("/catalog").add_query_param("loaded", "true").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let body = response.json::<serde_json::Value>();
+  let names = body["datasets"].as_array().unwrap().iter().map(|dataset| dataset["name"].as_str().unwrap()).collect::<Vec<_>>();
+
+  assert_eq!(names, vec!["default", "indexed"]);
+  assert_eq!(body["current"], json!(["indexed"]));
+}
+
+#[tokio::test]
+async fn api_catalog_diff() {
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(MockedElasticsearch::builder().healthy(true).build()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/catalog/diff", get(handlers::get_catalog_diff)).with_state(state);
+  let server = TestServer::new(app);
+  let response = server.get("/catalog/diff").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  response.assert_json(&json!({
+      "added": [],
+      "removed": [],
+      "updated": [],
+      "outdated": [],
+      "current": [],
+  }));
+}
+
+/// Fetcher serving a new version of the `default` dataset on every fetch.
+#[derive(Clone, Default)]
+struct VersionedFetcher(Arc<AtomicUsize>);
+
+impl CatalogFetcher for VersionedFetcher {
+  async fn fetch_manifest(&self) -> anyhow::Result<Manifest> {
+    Ok(Manifest::default())
+  }
+
+  async fn fetch_catalog(&self, _: &str, _: Option<&str>) -> anyhow::Result<Catalog> {
+    let version = self.0.fetch_add(1, Ordering::SeqCst);
+
+    Ok(Catalog {
+      datasets: vec![CatalogDataset {
+        name: "default".to_string(),
+        version: format!("v{version}"),
+        ..Default::default()
+      }],
+      ..Default::default()
+    })
+  }
+}
+
+#[tokio::test]
+async fn api_catalog_diff_updated() {
+  let motiva = Motiva::custom(MockedElasticsearch::builder().healthy(true).build())
+    .fetcher(VersionedFetcher::default())
+    .build()
+    .await
+    .unwrap();
+
+  motiva.refresh_catalog().await;
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva,
+  };
+
+  let app = Router::new().route("/catalog/diff", get(handlers::get_catalog_diff)).with_state(state);
+  let server = TestServer::new(app);
+  let response = server.get("/catalog/diff").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  response.assert_json(&json!({
+      "added": [],
+      "removed": [],
+      "updated": [{ "name": "default", "previous": "v0", "current": "v1" }],
+      "outdated": [],
+      "current": [],
+  }));
+}
+
+#[tokio::test]
+async fn api_openapi() {
+  let app = Router::new().route("/openapi.json", get(handlers::openapi));
+  let server = TestServer::new(app);
+  let response = server.get("/openapi.json").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let spec = response.json::<serde_json::Value>();
+
+  assert!(spec["components"]["schemas"]["Payload"].is_object());
+  assert!(spec["components"]["schemas"]["MatchResponse"].is_object());
+  assert!(spec["paths"]["/match/{scope}"]["post"].is_object());
+  assert!(spec["paths"]["/entities/{id}"]["get"].is_object());
+
+  assert!(spec["components"]["schemas"]["SearchEntity"]["properties"]["params"].is_object());
+  assert!(spec["components"]["schemas"]["Entity"]["properties"]["matched_queries"].is_object());
+  assert_eq!(
+    spec["paths"]["/catalog"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+    "#/components/schemas/Catalog"
+  );
+  assert_eq!(
+    spec["paths"]["/catalog/diff"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+    "#/components/schemas/CatalogDiff"
+  );
+
+  let params = spec["paths"]["/match/{scope}"]["post"]["parameters"].as_array().unwrap();
+
+  for name in ["explain_score", "name_parts_should_match", "seed"] {
+    assert!(params.iter().any(|param| param["name"] == name), "missing query parameter {name}");
+  }
+}
+
+#[tokio::test]
+async fn api_match_unknown_algorithm() {
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(MockedElasticsearch::default()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default?algorithm=does-not-exist")
+    .json(&json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } }))
+    .await;
+
+  assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+async fn api_match_custom_algorithm() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()])
+    .build();
+
+  let motiva = Motiva::test(index).build().await.unwrap();
+
+  motiva.register_algorithm(FeatureAlgorithm::new("constant"));
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva,
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+  let payload = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+
+  let response = server.post("/match/default?custom_algorithm=constant&cutoff=0").json(&payload).await;
+
+  assert_eq!(response.status_code(), 200);
+  assert_eq!(response.json::<serde_json::Value>()["responses"]["test"]["results"].as_array().unwrap().len(), 1);
+
+  let response = server.post("/match/default?custom_algorithm=does-not-exist").json(&payload).await;
+
+  assert_eq!(response.status_code(), 400);
+}
+
 #[tokio::test]
 async fn api_match() {
   let index = MockedElasticsearch::builder()
@@ -176,33 +449,92 @@ async fn api_match() {
 }
 
 #[tokio::test]
-async fn api_match_not_ready() {
-  let index = MockedElasticsearch::builder().ready(false).build();
+async fn api_match_max_limit() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Vladimir Poutine"])]).build(),
+    ])
+    .build();
 
   let state = AppState {
-    config: Arc::new(Config::default()),
+    config: Arc::new(Config {
+      max_limit: Some(1),
+      ..Default::default()
+    }),
     prometheus: None,
-    motiva: Motiva::test(index).fetcher(TestFetcher::default()).build().await.unwrap(),
+    motiva: Motiva::test(index).build().await.unwrap(),
   };
 
   let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
   let server = TestServer::new(app);
+  let payload = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
 
-  let response = server
-    .post("/match/default")
-    .json(&json!({
-        "queries": {
-            "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } }
-        }
-    }))
-    .await;
+  let response = server.post("/match/default?cutoff=0.0&limit=1").json(&payload).await;
+  let body = response.json::<serde_json::Value>();
 
-  assert_eq!(response.status_code(), 503);
+  assert_eq!(response.status_code(), 200);
+  assert_eq!(body["limit"], 1);
+  assert_eq!(body["responses"]["test"]["results"].as_array().unwrap().len(), 1);
+  assert!(body.get("warnings").is_none());
+
+  let response = server.post("/match/default?cutoff=0.0&limit=50").json(&payload).await;
+  let body = response.json::<serde_json::Value>();
+
+  assert_eq!(response.status_code(), 200);
+  assert_eq!(body["limit"], 1);
+  assert_eq!(body["responses"]["test"]["results"].as_array().unwrap().len(), 1);
+  assert_eq!(body["warnings"], json!(["limit was reduced from 50 to the maximum of 1"]));
 }
 
 #[tokio::test]
-async fn api_invalid_query() {
-  let index = MockedElasticsearch::builder().healthy(false).build();
+async fn api_match_slow_query() {
+  use std::{sync::Mutex, time::Duration};
+
+  use crate::tests::log_writer::VecLogWriter;
+
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()])
+    .delay(Duration::from_millis(50))
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config {
+      slow_query_ms: Some(10),
+      ..Default::default()
+    }),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let lines = Arc::new(Mutex::new(Vec::default()));
+  let (writer, _wait) = VecLogWriter::new(Arc::clone(&lines));
+  let subscriber = tracing_subscriber::fmt().with_ansi(false).with_writer(move || writer.clone()).finish();
+  let _guard = tracing::subscriber::set_default(subscriber);
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+  let payload = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+
+  let response = server.post("/match/default").json(&payload).await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let lines = lines.lock().unwrap();
+  let warning = lines.iter().find(|line| line.contains("slow match query")).expect("slow query was not logged");
+
+  assert!(warning.contains("WARN"));
+  assert!(warning.contains(r#"scope="default""#));
+  assert!(warning.contains("query_count=1"));
+  assert!(warning.contains("candidate_count=1"));
+  assert!(warning.contains("elapsed_ms="));
+}
+
+#[tokio::test]
+async fn api_match_multiple_algorithms() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()])
+    .build();
 
   let state = AppState {
     config: Arc::new(Config::default()),
@@ -212,16 +544,97 @@ async fn api_invalid_query() {
 
   let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
   let server = TestServer::new(app);
-  let response = server.post("/match/default?changed_since=invalid").await;
+  let payload = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+
+  let response = server.post("/match/default?algorithms=logic-v1&algorithms=name-qualified").json(&payload).await;
+  let body = response.json::<serde_json::Value>();
+  let result = &body["responses"]["test"]["results"][0];
+
+  assert_eq!(response.status_code(), 200);
+  assert_eq!(result["scores"]["logic-v1"], result["score"]);
+  assert!(result["scores"]["name-qualified"].as_f64().unwrap() > 0.0);
+  assert_eq!(result["scores"].as_object().unwrap().len(), 2);
+
+  let response = server.post("/match/default").json(&payload).await;
+
+  assert!(response.json::<serde_json::Value>()["responses"]["test"]["results"][0].get("scores").is_none());
+
+  let response = server.post("/match/default?algorithms=unknown").json(&payload).await;
 
   assert_eq!(response.status_code(), 400);
+}
 
-  response.assert_text_contains("failed to parse year in date");
+#[tokio::test]
+async fn api_compare() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Vladimir Poutine"])]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/compare", get(handlers::compare)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server.get("/compare?left=Q7747&right=A1234&algorithm=logic-v1").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let body = response.json::<serde_json::Value>();
+
+  assert_eq!(body["left"], "Q7747");
+  assert_eq!(body["id"], "A1234");
+  assert!(body["score"].as_f64().unwrap() > 0.0);
+  assert!(body["features"].as_object().is_some_and(|features| !features.is_empty()));
+  assert!(body["explanations"].as_object().is_some_and(|explanations| !explanations.is_empty()));
+
+  assert_eq!(server.get("/compare?left=Q7747&right=unknown").await.status_code(), 404);
+  assert_eq!(server.get("/compare?left=Q7747&right=A1234&algorithm=unknown").await.status_code(), 400);
 }
 
 #[tokio::test]
-async fn api_unparsable_payload() {
-  let index = MockedElasticsearch::builder().healthy(false).build();
+async fn api_compare_configured_weights() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Vladimir Poutine"])]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config {
+      weights: std::collections::HashMap::from([("person_name_jaro_winkler".to_string(), 0.5)]),
+      ..Default::default()
+    }),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/compare", get(handlers::compare)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server.get("/compare?left=Q7747&right=A1234&algorithm=logic-v1").await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let explanation = &response.json::<serde_json::Value>()["explanations"]["person_name_jaro_winkler"];
+  let score = explanation["score"].as_f64().unwrap();
+
+  assert!(score > 0.0);
+  assert!((explanation["weighted"].as_f64().unwrap() - score * 0.5).abs() < 0.001);
+}
+
+#[tokio::test]
+async fn api_match_empty_properties() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["x"])]).build()])
+    .build();
 
   let state = AppState {
     config: Arc::new(Config::default()),
@@ -232,15 +645,745 @@ async fn api_unparsable_payload() {
   let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
   let server = TestServer::new(app);
 
-  let payloads = &[r#"{ "queries": { "test": { "schema": } } }"#, r#"{ "queries": { "test": { "schema": 12 } } }"#];
+  let response = server
+    .post("/match/default")
+    .json(&json!({ "queries": { "test": { "schema": "Person", "properties": { "name": [] } } } }))
+    .await;
 
-  for payload in payloads {
-    let response = server.post("/match/default?changed_since=invalid").json(payload).await;
+  assert_eq!(response.status_code(), 422);
 
-    assert_eq!(response.status_code(), 400);
-  }
+  response.assert_json_contains(&json!({ "message": "payload failed validation" }));
+  response.assert_text_contains("properties: at least one property must have a non-empty value");
 
-  let response = server.post("/match/default?changed_since=invalid").text("{}").await;
+  let response = server
+    .post("/match/default")
+    .json(&json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["x"] } } } }))
+    .await;
 
-  assert_eq!(response.status_code(), 400);
+  assert_eq!(response.status_code(), 200);
+}
+
+#[tokio::test]
+async fn api_match_scores() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Bob the Builder"])]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}/scores", post(handlers::match_scores)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default/scores?cutoff=0.0")
+    .json(&json!({
+        "queries": {
+            "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } }
+        }
+    }))
+    .await;
+
+  assert_eq!(response.status_code(), 200);
+
+  let body = response.json::<serde_json::Value>();
+
+  assert_eq!(
+    body,
+    json!({
+        "limit": 5,
+        "responses": {
+            "test": {
+                "status": 200,
+                "scores": {
+                    "Q7747": { "match": true, "score": 1.0 },
+                    "A1234": { "match": false, "score": 0.0 },
+                }
+            }
+        }
+    })
+  );
+}
+
+#[tokio::test]
+async fn api_match_not_ready() {
+  let index = MockedElasticsearch::builder().ready(false).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).fetcher(TestFetcher::default()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default")
+    .json(&json!({
+        "queries": {
+            "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } }
+        }
+    }))
+    .await;
+
+  assert_eq!(response.status_code(), 503);
+}
+
+#[tokio::test]
+async fn api_invalid_query() {
+  let index = MockedElasticsearch::builder().healthy(false).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+  let response = server.post("/match/default?changed_since=invalid").await;
+
+  assert_eq!(response.status_code(), 400);
+
+  response.assert_text_contains("failed to parse year in date");
+}
+
+#[tokio::test]
+async fn api_unparsable_payload() {
+  let index = MockedElasticsearch::builder().healthy(false).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let payloads = &[r#"{ "queries": { "test": { "schema": } } }"#, r#"{ "queries": { "test": { "schema": 12 } } }"#];
+
+  for payload in payloads {
+    let response = server.post("/match/default?changed_since=invalid").json(payload).await;
+
+    assert_eq!(response.status_code(), 400);
+  }
+
+  let response = server.post("/match/default?changed_since=invalid").text("{}").await;
+
+  assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+async fn api_match_explain_score() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["PUTIN, Vladimir Vladimirovich"])]).build()])
+    .build();
+
+  let payload = json!({
+      "queries": {
+          "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } }
+      }
+  });
+
+  for enabled in [true, false] {
+    let state = AppState {
+      config: Arc::new(Config {
+        enable_explain_score: enabled,
+        ..Default::default()
+      }),
+      prometheus: None,
+      motiva: Motiva::test(index.clone()).build().await.unwrap(),
+    };
+
+    let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+    let server = TestServer::new(app);
+    let response = server.post("/match/default?cutoff=0.0&explain_score=true").json(&payload).await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let body = response.json::<serde_json::Value>();
+    let hit = &body["responses"]["test"]["results"][0];
+
+    match enabled {
+      true => {
+        assert_eq!(hit["intermediates"]["query"]["tokens"], json!(["putin", "vladimir"]));
+        assert_eq!(hit["intermediates"]["result"]["tokens"], json!(["putin", "vladimir", "vladimirovich"]));
+      }
+
+      false => assert!(hit.get("intermediates").is_none()),
+    }
+  }
+}
+
+#[tokio::test]
+async fn api_match_preview() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Bob the Builder"])]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}/preview", post(handlers::match_preview)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default/preview")
+    .json(&json!({
+        "queries": {
+            "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } }
+        }
+    }))
+    .await;
+
+  assert_eq!(response.status_code(), 200);
+
+  response.assert_json(&json!({
+      "responses": {
+          "test": {
+              "status": 200,
+              "results": [
+                  { "id": "Q7747", "caption": "", "schema": "Person", "datasets": [] },
+                  { "id": "A1234", "caption": "", "schema": "Person", "datasets": [] },
+              ]
+          }
+      }
+  }));
+
+  let body = response.json::<serde_json::Value>();
+  let hit = body["responses"]["test"]["results"][0].as_object().unwrap();
+
+  assert!(!hit.contains_key("score"));
+  assert!(!hit.contains_key("features"));
+}
+
+#[tokio::test]
+async fn api_payload_error_names_query() {
+  let index = MockedElasticsearch::builder().healthy(true).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default")
+    .json(&json!({
+      "queries": {
+        "valid": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } },
+        "019d4e2d-4b9f-7e41-a2c3-1f0e5b6d8a90": { "schema": 12, "properties": {} }
+      }
+    }))
+    .await;
+
+  assert_eq!(response.status_code(), 400);
+
+  response.assert_json_contains(&json!({ "message": "payload does not match expected format" }));
+  response.assert_text_contains("query '019d4e2d-4b9f-7e41-a2c3-1f0e5b6d8a90': schema: invalid type: integer `12`");
+
+  let response = server
+    .post("/match/default")
+    .json(&json!({
+      "queries": {
+        "019d4e2d": { "schema": "Person", "properties": { "name": "Vladimir Putin" } }
+      }
+    }))
+    .await;
+
+  assert_eq!(response.status_code(), 400);
+
+  response.assert_text_contains("query '019d4e2d': properties.name: invalid type: string");
+}
+
+#[tokio::test]
+async fn api_match_span_attributes() {
+  use std::{collections::HashMap, fmt::Debug, sync::Mutex};
+
+  use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+  };
+  use tracing_subscriber::{Layer, layer::Context, layer::SubscriberExt};
+
+  #[derive(Clone, Default)]
+  struct SpanFields(Arc<Mutex<HashMap<String, String>>>);
+
+  impl Visit for SpanFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+      self.0.lock().unwrap().insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+      self.0.lock().unwrap().insert(field.name().to_string(), format!("{value:?}"));
+    }
+  }
+
+  impl<S: Subscriber> Layer<S> for SpanFields {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+      attrs.record(&mut self.clone());
+    }
+
+    fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+      values.record(&mut self.clone());
+    }
+  }
+
+  let fields = SpanFields::default();
+  let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(fields.clone()));
+
+  let index = MockedElasticsearch::builder().build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  server
+    .post("/match/sanctions?algorithm=name-based")
+    .json(&json!({
+        "queries": {
+            "first": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } },
+            "second": { "schema": "Person", "properties": { "name": ["Bob the Builder"] } }
+        }
+    }))
+    .await;
+
+  let fields = fields.0.lock().unwrap();
+
+  assert_eq!(fields.get("scope").map(String::as_str), Some("sanctions"));
+  assert_eq!(fields.get("algorithm").map(String::as_str), Some("name-based"));
+  assert_eq!(fields.get("query_count").map(String::as_str), Some("2"));
+}
+
+#[tokio::test]
+async fn api_match_stream() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Bob the Builder"])]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}/stream", post(handlers::match_stream)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = [
+    json!({ "id": "putin", "query": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } }).to_string(),
+    String::new(),
+    json!({ "id": "bob", "query": { "schema": "Person", "properties": { "name": ["Bob the Builder"] } } }).to_string(),
+    json!({ "id": "empty", "query": { "schema": "Person", "properties": {} } }).to_string(),
+    "not json".to_string(),
+  ]
+  .join("\n");
+
+  let response = server.post("/match/default/stream?limit=1").content_type("application/x-ndjson").bytes(body.into()).await;
+
+  response.assert_status_ok();
+  assert_eq!(response.header("content-type"), "application/x-ndjson");
+
+  let mut lines = response.text().lines().map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()).collect::<Vec<_>>();
+
+  lines.sort_by_key(|line| line["id"].as_str().unwrap_or_default().to_string());
+
+  assert_eq!(lines.len(), 4);
+  assert_eq!(lines[0]["status"], 400);
+  assert!(lines[0]["error"].is_string());
+  assert_eq!(lines[1]["id"], "bob");
+  assert_eq!(lines[1]["results"][0]["id"], "A1234");
+  assert_eq!(lines[2]["id"], "empty");
+  assert_eq!(lines[2]["results"].as_array().unwrap().len(), 0);
+  assert_eq!(lines[3]["id"], "putin");
+  assert_eq!(lines[3]["results"][0]["id"], "Q7747");
+}
+
+#[tokio::test]
+async fn api_match_concurrency_limit() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config {
+      match_concurrency: Some(1),
+      ..Default::default()
+    }),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let queries = (0..8)
+    .map(|index| (format!("q{index}"), json!({ "schema": "Person", "properties": { "name": ["Vladimir Putin"] } })))
+    .collect::<serde_json::Map<_, _>>();
+
+  let response = tokio::time::timeout(std::time::Duration::from_secs(5), server.post("/match/default").json(&json!({ "queries": queries })))
+    .await
+    .expect("request did not complete under the concurrency limit");
+
+  response.assert_status_ok();
+
+  let body = response.json::<serde_json::Value>();
+
+  for index in 0..8 {
+    assert_eq!(body["responses"][format!("q{index}")]["status"], 200);
+    assert_eq!(body["responses"][format!("q{index}")]["results"][0]["id"], "Q7747");
+  }
+}
+
+#[tokio::test]
+async fn api_match_duplicate_property_keys() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build(),
+      Entity::builder("Person").id("A1234").properties(&[("name", &["Bob the Builder"])]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = r#"{ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"], "name": ["Bob the Builder"] } } } }"#;
+  let response = server.post("/match/default").content_type("application/json").bytes(body.into()).await;
+
+  response.assert_status_ok();
+
+  let body = response.json::<serde_json::Value>();
+  let results = body["responses"]["test"]["results"].as_array().unwrap();
+
+  assert_eq!(results.len(), 2);
+  assert!(results.iter().all(|result| result["match"] == true));
+}
+
+#[tokio::test]
+async fn api_match_provenance() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person")
+        .id("Q7747")
+        .properties(&[("name", &["Vladimir Putin"])])
+        .datasets(&["us_ofac_sdn", "eu_fsf"])
+        .build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default?include_reasons=true&include_provenance=true")
+    .json(&json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } }))
+    .await;
+
+  response.assert_status_ok();
+
+  let body = response.json::<serde_json::Value>();
+  let result = &body["responses"]["test"]["results"][0];
+
+  assert_eq!(result["match"], true);
+  assert_eq!(result["reasons"][0], "name matches exactly");
+  assert_eq!(result["reasons"].as_array().unwrap().last().unwrap(), "listed in eu_fsf, us_ofac_sdn");
+}
+
+#[tokio::test]
+async fn api_match_field_mapping() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index.clone()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+  let response = server.post("/match/default?field_mapping=address:address_full&field_mapping=date:all_dates").json(&body).await;
+
+  response.assert_status_ok();
+
+  let calls = index.calls();
+
+  assert_eq!(calls.search.len(), 1);
+  assert_eq!(calls.search[0].1.field_mapping.get("address").map(String::as_str), Some("address_full"));
+  assert_eq!(calls.search[0].1.field_mapping.get("date").map(String::as_str), Some("all_dates"));
+
+  for mapping in ["birthDate:dates", "address:", "address"] {
+    let response = server.post(&format!("/match/default?field_mapping={mapping}")).json(&body).await;
+
+    assert_eq!(response.status_code(), 400);
+  }
+
+  assert_eq!(index.calls().search.len(), 1);
+}
+
+#[tokio::test]
+async fn api_match_search_type() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build()])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index.clone()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+  let response = server.post("/match/default?search_type=query_then_fetch").json(&body).await;
+
+  response.assert_status_ok();
+
+  let calls = index.calls();
+
+  assert_eq!(calls.search.len(), 1);
+  assert_eq!(calls.search[0].1.search_type, Some(SearchType::QueryThenFetch));
+
+  let response = server.post("/match/default?search_type=scan").json(&body).await;
+
+  assert_eq!(response.status_code(), 400);
+  assert_eq!(index.calls().search.len(), 1);
+}
+
+#[tokio::test]
+async fn api_count() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![
+      Entity::builder("Person").id("Q7747").datasets(&["us_ofac_sdn"]).build(),
+      Entity::builder("Person").id("A1234").datasets(&["eu_fsf"]).build(),
+      Entity::builder("Vessel").id("V1234").datasets(&["us_ofac_sdn"]).build(),
+    ])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/count/{scope}", get(handlers::count)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server.get("/count/default").await;
+
+  response.assert_status_ok();
+  response.assert_json(&json!({ "count": 3 }));
+
+  let response = server.get("/count/default?include_dataset=us_ofac_sdn&exclude_schema=Vessel").await;
+
+  response.assert_status_ok();
+  response.assert_json(&json!({ "count": 1 }));
+}
+
+#[tokio::test]
+async fn api_count_not_ready() {
+  let index = MockedElasticsearch::builder().ready(false).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/count/{scope}", get(handlers::count)).with_state(state);
+  let server = TestServer::new(app);
+
+  assert_eq!(server.get("/count/default").await.status_code(), 503);
+}
+
+#[tokio::test]
+async fn api_match_unknown_schema() {
+  let index = MockedElasticsearch::builder().build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let response = server
+    .post("/match/default")
+    .json(&json!({ "queries": { "test": { "schema": "Wizard", "properties": { "name": ["Gandalf"] } } } }))
+    .await;
+
+  assert_eq!(response.status_code(), 422);
+
+  response.assert_text_contains("unknown schema 'Wizard'");
+  response.assert_text_contains(SCHEMA_HINT);
+}
+
+#[tokio::test]
+async fn api_match_index_score() {
+  let mut entity = Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build();
+
+  entity.index_score = Some(42.17);
+
+  let index = MockedElasticsearch::builder().entities(vec![entity]).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+
+  let response = server.post("/match/default?explain=true").json(&body).await;
+
+  response.assert_status_ok();
+
+  assert_eq!(response.json::<serde_json::Value>()["responses"]["test"]["results"][0]["index_score"], json!(42.17));
+
+  let response = server.post("/match/default").json(&body).await;
+
+  response.assert_status_ok();
+
+  assert!(response.json::<serde_json::Value>()["responses"]["test"]["results"][0].get("index_score").is_none());
+}
+
+#[tokio::test]
+async fn api_match_score_precision() {
+  let index = MockedElasticsearch::builder()
+    .entities(vec![Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putn"])]).build()])
+    .build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+
+  for precision in [None, Some(1), Some(5)] {
+    let url = match precision {
+      Some(precision) => format!("/match/default?cutoff=0.0&explain=true&score_precision={precision}"),
+      None => "/match/default?cutoff=0.0&explain=true".to_string(),
+    };
+
+    let response = server.post(&url).json(&body).await;
+
+    response.assert_status_ok();
+
+    let decimals = precision.unwrap_or(3);
+    let result = &response.json::<serde_json::Value>()["responses"]["test"]["results"][0];
+    let features = result["features"].as_object().unwrap();
+    let explanations = result["explanations"].as_object().unwrap();
+
+    assert!(!features.is_empty());
+    assert!(features.values().any(|score| score.as_f64().unwrap().fract() != 0.0));
+
+    for (name, score) in features {
+      let score = score.as_f64().unwrap();
+
+      assert_eq!(score, round_score(score, decimals), "feature {name} at precision {decimals}");
+
+      if score != 0.0 {
+        assert_eq!(explanations[name]["score"].as_f64().unwrap(), score, "explanation {name} at precision {decimals}");
+      }
+    }
+
+    if decimals > 3 {
+      assert!(features.values().any(|score| score.as_f64().unwrap() != round_score(score.as_f64().unwrap(), 3)));
+    }
+  }
+}
+
+#[tokio::test]
+async fn api_match_filter_schemas() {
+  let mut entities = (0..20)
+    .map(|i| Entity::builder("Vessel").id(&format!("vessel-{i}")).properties(&[("name", &["Vladimir Putin"])]).build())
+    .collect::<Vec<_>>();
+
+  entities.push(Entity::builder("Person").id("Q7747").properties(&[("name", &["Vladimir Putin"])]).build());
+
+  let index = MockedElasticsearch::builder().entities(entities).build();
+
+  let state = AppState {
+    config: Arc::new(Config::default()),
+    prometheus: None,
+    motiva: Motiva::test(index.clone()).build().await.unwrap(),
+  };
+
+  let app = Router::new().route("/match/{scope}", post(handlers::match_entities)).with_state(state);
+  let server = TestServer::new(app);
+
+  let body = json!({ "queries": { "test": { "schema": "Person", "properties": { "name": ["Vladimir Putin"] } } } });
+
+  let response = server.post("/match/default").json(&body).await;
+
+  response.assert_status_ok();
+
+  assert!(response.json::<serde_json::Value>()["responses"]["test"]["results"].as_array().unwrap().is_empty());
+
+  let response = server.post("/match/default?filter_schemas=true").json(&body).await;
+
+  response.assert_status_ok();
+
+  let results = response.json::<serde_json::Value>()["responses"]["test"]["results"].as_array().unwrap().clone();
+
+  assert_eq!(results.len(), 1);
+  assert_eq!(results[0]["id"], "Q7747");
+
+  let calls = index.calls();
+
+  assert!(!calls.search[0].1.filter_schemas);
+  assert!(calls.search[1].1.filter_schemas);
 }
@@ -1,4 +1,5 @@
 use std::{
+  collections::HashMap,
   sync::{Arc, Mutex},
   time::Duration,
 };
@@ -63,6 +64,109 @@ async fn api_valid_credentials() {
   assert_eq!(response.status_code(), 415);
 }
 
+fn scoped_config() -> Config {
+  Config {
+    api_key: Some("myapikey".into()),
+    api_keys: HashMap::from([("scopedkey".to_string(), vec!["sanctions".to_string()])]),
+    ..Default::default()
+  }
+}
+
+#[tokio::test]
+async fn api_scoped_key_allowed_scope() {
+  let index = MockedElasticsearch::builder().healthy(false).build();
+
+  let state = AppState {
+    config: Arc::new(scoped_config()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = api::router(state);
+  let server = TestServer::new(app);
+  let response = server.post("/match/sanctions").add_header(AUTHORIZATION, "Bearer scopedkey").await;
+
+  assert_eq!(response.status_code(), 415);
+
+  // The unscoped key keeps access to every scope
+  let response = server.post("/match/default").add_header(AUTHORIZATION, "Bearer myapikey").await;
+
+  assert_eq!(response.status_code(), 415);
+}
+
+#[tokio::test]
+async fn api_scoped_key_forbidden_scope() {
+  let index = MockedElasticsearch::builder().healthy(false).build();
+
+  let state = AppState {
+    config: Arc::new(scoped_config()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = api::router(state);
+  let server = TestServer::new(app);
+  let response = server.post("/match/default").add_header(AUTHORIZATION, "Bearer scopedkey").await;
+
+  assert_eq!(response.status_code(), 403);
+
+  response.assert_text_contains("access to this resource is forbidden");
+
+  let response = server.post("/match/default/scores").add_header(AUTHORIZATION, "Bearer scopedkey").await;
+
+  assert_eq!(response.status_code(), 403);
+}
+
+#[tokio::test]
+async fn api_scoped_key_unknown_key() {
+  let index = MockedElasticsearch::builder().healthy(false).build();
+
+  let state = AppState {
+    config: Arc::new(scoped_config()),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = api::router(state);
+  let server = TestServer::new(app);
+  let response = server.post("/match/sanctions").add_header(AUTHORIZATION, "Bearer unknownkey").await;
+
+  assert_eq!(response.status_code(), 401);
+
+  response.assert_text_contains("invalid credentials");
+}
+
+#[tokio::test]
+async fn api_body_too_large() {
+  let index = MockedElasticsearch::builder().healthy(true).build();
+
+  let state = AppState {
+    config: Arc::new(Config {
+      max_body_size: Some(64),
+      ..Default::default()
+    }),
+    prometheus: None,
+    motiva: Motiva::test(index).build().await.unwrap(),
+  };
+
+  let app = api::router(state);
+  let server = TestServer::new(app);
+  let response = server.post("/match/default").text("a".repeat(65)).content_type("application/json").await;
+
+  assert_eq!(response.status_code(), 413);
+
+  response.assert_text_contains("request body is too large");
+
+  let response = server.post("/match/default").text("a".repeat(64)).content_type("application/json").await;
+
+  assert_eq!(response.status_code(), 400);
+
+  let response = server.post("/match/default/stream").text("a".repeat(65)).content_type("application/x-ndjson").await;
+
+  assert_eq!(response.status_code(), 200);
+  assert!(response.text().contains("max line length exceeded"));
+}
+
 // The following tests need to be run into a fork because the tracing framework
 // sets up global state that cannot be duplicated.
 rusty_fork_test! {
@@ -164,9 +268,12 @@ rusty_fork_test! {
             let app = api::router(state);
             let server = TestServer::new(app);
             let _ = server.post("/match/default").await;
+            let _ = server.post("/match/default").text("{}").await;
             let resp = server.get("/metrics").await;
 
-            assert!(resp.text().contains(r#"http_requests_total{service="motiva",status="415"}"#))
+            assert!(resp.text().contains(r#"http_requests_total{service="motiva",status="415"}"#));
+            assert!(resp.text().contains("http_request_size_bytes"));
+            assert!(resp.text().contains("http_response_size_bytes"));
         });
     }
 }
@@ -15,7 +15,7 @@ use tracing_subscriber::{EnvFilter, Layer, Registry, fmt, layer::SubscriberExt,
 #[cfg(feature = "gcp")]
 use opentelemetry_gcloud_trace::GcpCloudTraceExporterBuilder;
 
-use crate::api::config::{self, Config, Env, TracingExporter};
+use crate::api::config::{self, Config, LogFormat, TracingExporter};
 
 pub fn build_prometheus() -> Result<PrometheusHandle, BuildError> {
   let builder = PrometheusBuilder::new()
@@ -43,19 +43,16 @@ impl Drop for TraceGuards {
 pub async fn init_tracing(config: &Config, writer: impl Write + Send + 'static) -> TraceGuards {
   let (appender, logging_guard) = tracing_appender::non_blocking(writer);
 
-  let logging_formatter = match config.env {
-    #[cfg(not(test))]
-    Env::Dev => fmt::layer().compact().with_writer(appender).with_ansi(true).boxed(),
-    Env::Production => json_subscriber::layer()
+  let logging_formatter = match config.log_format {
+    LogFormat::Compact => fmt::layer().compact().with_writer(appender).with_ansi(cfg!(not(test))).boxed(),
+    LogFormat::Pretty => fmt::layer().pretty().with_writer(appender).with_ansi(cfg!(not(test))).boxed(),
+    LogFormat::Json => json_subscriber::layer()
       .with_writer(appender)
       .flatten_event(true)
       .flatten_span_list_on_top_level(true)
       .with_current_span(false)
       .with_span_list(false)
       .boxed(),
-
-    #[cfg(test)]
-    Env::Dev => fmt::layer().compact().with_writer(appender).with_ansi(false).boxed(),
   };
 
   let guard = TraceGuards { _logging: logging_guard, trace: None };